@@ -0,0 +1,43 @@
+//! Text scanning on the scoring hot path, against the regexes it replaced
+//!
+//! Run with `cargo bench --bench scanning`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use readability_rust::{count_commas, has_content, is_whitespace};
+use regex::Regex;
+
+/// About a megabyte of prose, mostly ASCII with some Arabic and CJK commas
+fn corpus() -> String {
+    let paragraph = "Rivers carry silt, sand and clay to the sea, where deltas form over \
+                     centuries; the Nile، the Yangtze，and the Ganges are the best known.\n\n    ";
+    paragraph.repeat(1024 * 1024 / paragraph.len())
+}
+
+fn bench_commas(c: &mut Criterion) {
+    let text = corpus();
+    let regex = Regex::new(r"\u{002C}|\u{060C}|\u{FE50}|\u{FE10}|\u{FE11}|\u{2E41}|\u{2E34}|\u{2E32}|\u{FF0C}").unwrap();
+    assert_eq!(count_commas(&text), regex.find_iter(&text).count());
+
+    let mut group = c.benchmark_group("count_commas");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("memchr", |b| b.iter(|| count_commas(black_box(&text))));
+    group.bench_function("regex", |b| b.iter(|| regex.find_iter(black_box(&text)).count()));
+    group.finish();
+}
+
+fn bench_whitespace(c: &mut Criterion) {
+    let blank = " \n\t".repeat(4096);
+    let text = corpus();
+    let whitespace = Regex::new(r"^\s*$").unwrap();
+    let content = Regex::new(r"\S$").unwrap();
+
+    let mut group = c.benchmark_group("whitespace");
+    group.bench_function("is_whitespace", |b| b.iter(|| is_whitespace(black_box(&blank))));
+    group.bench_function("is_whitespace_regex", |b| b.iter(|| whitespace.is_match(black_box(&blank))));
+    group.bench_function("has_content", |b| b.iter(|| has_content(black_box(&text))));
+    group.bench_function("has_content_regex", |b| b.iter(|| content.is_match(black_box(&text))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_commas, bench_whitespace);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+//! Candidate scoring and extraction on a page of about 10k nodes
+//!
+//! Run with `cargo bench --bench scoring`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use readability_rust::Readability;
+
+/// A news page: navigation, an article of 400 paragraphs and a comment thread
+fn page() -> String {
+    let mut html = String::from("<html><head><title>Rivers</title></head><body><nav class=\"menu\"><ul>");
+    for i in 0..200 {
+        html.push_str(&format!("<li><a href=\"/section/{}\">Section {}</a></li>", i, i));
+    }
+    html.push_str("</ul></nav><div id=\"main\"><article class=\"post\">");
+    for i in 0..400 {
+        html.push_str(&format!(
+            "<div class=\"block-{}\"><p>Rivers carry <b>silt</b>, sand and clay to the sea, where deltas form over \
+             centuries; <a href=\"/notes/{}\">note {}</a> covers the Nile, the Yangtze and the Ganges.</p></div>",
+            i % 7, i, i
+        ));
+    }
+    html.push_str("</article></div><div id=\"comments\" class=\"comment-list\">");
+    for i in 0..300 {
+        html.push_str(&format!("<div class=\"comment\"><span class=\"author\">Reader {}</span><p>Great piece, thanks.</p></div>", i));
+    }
+    html.push_str("</div></body></html>");
+    html
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let html = page();
+    let mut group = c.benchmark_group("scoring");
+    group.sample_size(20);
+    group.bench_function("parse_10k_nodes", |b| {
+        b.iter(|| Readability::new(black_box(&html), None).unwrap().parse().unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
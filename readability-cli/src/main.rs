@@ -0,0 +1,1089 @@
+//! Command-line interface for the Readability library
+
+use clap::{Arg, ArgMatches, Command};
+use clap_complete::Shell;
+use readability_rust::format::{write_article, OutputFormat};
+#[cfg(feature = "oracle")]
+use readability_rust::{corpus, oracle};
+#[cfg(feature = "fetch")]
+use readability_rust::fetch::{FetchOutcome, FetchPolicy, Fetcher, Validators};
+#[cfg(feature = "fetch")]
+use readability_rust::sitemap;
+use readability_rust::{
+    bundle, decode_lossy, is_html_file, is_probably_readerable, plugins, tune, CleanerRegistry, Article, CharThreshold, ExtractionProfile, Readability, ReadabilityError,
+    ReadabilityOptions,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// Exit codes, one per failure class (clap exits with 2 on usage errors)
+const EXIT_FAILURE: i32 = 1;
+const EXIT_FETCH_ERROR: i32 = 3;
+const EXIT_NOT_READERABLE: i32 = 4;
+const EXIT_NOT_AN_ARTICLE: i32 = 5;
+const EXIT_TOO_SHORT: i32 = 6;
+
+/// How often `--watch` polls the input for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Outcomes that `--fail-on` can turn into failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailOn {
+    /// The article text is shorter than `--min-content-length`
+    Short,
+    /// The document doesn't look readerable
+    NotReaderable,
+    /// The input couldn't be read; exit with its own code instead of the general one
+    FetchError,
+}
+
+impl FailOn {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "short" => Ok(FailOn::Short),
+            "not-readerable" => Ok(FailOn::NotReaderable),
+            "fetch-error" => Ok(FailOn::FetchError),
+            _ => Err(format!("unknown condition '{}' (expected short, not-readerable or fetch-error)", value)),
+        }
+    }
+}
+
+/// Parse `--char-threshold`: a number of characters or `auto`
+fn parse_char_threshold(value: &str) -> Result<CharThreshold, String> {
+    if value.eq_ignore_ascii_case("auto") {
+        return Ok(CharThreshold::Auto);
+    }
+    value.parse::<usize>()
+        .map(CharThreshold::Chars)
+        .map_err(|_| format!("invalid threshold '{}' (expected a number or auto)", value))
+}
+
+/// Parse `--profile`: an extraction profile name
+fn parse_profile(value: &str) -> Result<ExtractionProfile, String> {
+    match value {
+        "generic" => Ok(ExtractionProfile::Generic),
+        "mediawiki" => Ok(ExtractionProfile::MediaWiki),
+        "docs" => Ok(ExtractionProfile::Docs),
+        "email" => Ok(ExtractionProfile::Email),
+        _ => Err(format!("unknown profile '{}' (expected generic, mediawiki, docs or email)", value)),
+    }
+}
+
+/// Parse `--since`: a `YYYY-MM-DD` date
+#[cfg(feature = "fetch")]
+fn parse_since(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let valid = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(index, byte)| match index {
+            4 | 7 => *byte == b'-',
+            _ => byte.is_ascii_digit(),
+        });
+    if valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!("invalid date '{}' (expected YYYY-MM-DD)", value))
+    }
+}
+
+/// Parse a sweep of values for `tune`: a comma-separated list (`25,100,500`)
+/// or an inclusive range with a step (`25..500:25`)
+fn parse_sweep(value: &str) -> Result<Vec<f64>, String> {
+    let invalid = || format!("invalid sweep '{}' (expected a list like 25,100 or a range like 25..500:25)", value);
+    let number = |text: &str| text.trim().parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0);
+    let Some((start, rest)) = value.split_once("..") else {
+        return value.split(',').map(|item| number(item).ok_or_else(invalid)).collect();
+    };
+    let (end, step) = rest.split_once(':').ok_or_else(invalid)?;
+    let (start, end, step) = (number(start).ok_or_else(invalid)?, number(end).ok_or_else(invalid)?, number(step).ok_or_else(invalid)?);
+    if step <= 0.0 || start > end {
+        return Err(invalid());
+    }
+    // Counting steps avoids accumulating floating-point error
+    let steps = ((end - start) / step + 1e-9).floor() as usize;
+    Ok((0..=steps).map(|index| start + step * index as f64).collect())
+}
+
+/// Parse `--plugin`: the name of a registered cleaner plugin
+fn parse_plugin(value: &str) -> Result<String, String> {
+    match plugins::find_plugin(value) {
+        Some(_) => Ok(value.to_string()),
+        None => Err(format!("unknown plugin '{}' (available: {})", value, plugins::plugin_names().join(", "))),
+    }
+}
+
+/// A failed run: the exit code and the message for stderr
+#[derive(Debug)]
+struct Failure {
+    code: i32,
+    message: String,
+}
+
+impl Failure {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    /// Machine-readable failure class, for structured logs
+    fn reason(&self) -> &'static str {
+        match self.code {
+            EXIT_FETCH_ERROR => "fetch_error",
+            EXIT_NOT_READERABLE => "not_readerable",
+            EXIT_NOT_AN_ARTICLE => "not_an_article",
+            EXIT_TOO_SHORT => "too_short",
+            _ => "extraction_failed",
+        }
+    }
+}
+
+impl<E: Into<Box<dyn std::error::Error>>> From<E> for Failure {
+    fn from(error: E) -> Self {
+        Self::new(EXIT_FAILURE, format!("Error: {}", error.into()))
+    }
+}
+
+/// How per-document outcomes are reported on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+struct CliOptions {
+    input: Option<String>,
+    output: Option<String>,
+    format: OutputFormat,
+    base_uri: Option<String>,
+    debug: bool,
+    check_only: bool,
+    watch: bool,
+    quiet: bool,
+    log_format: LogFormat,
+    fail_on: Vec<FailOn>,
+    min_content_length: usize,
+    char_threshold: CharThreshold,
+    keep_classes: bool,
+    xhtml: bool,
+    disable_json_ld: bool,
+    profile: Option<ExtractionProfile>,
+    plugins: Vec<String>,
+}
+
+fn main() {
+    let matches = cli().get_matches();
+
+    match matches.subcommand() {
+        Some(("completions", sub_matches)) => {
+            let shell = *sub_matches.get_one::<Shell>("shell").unwrap();
+            let mut command = cli();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+            return;
+        }
+        Some(("man", _)) => {
+            if let Err(e) = clap_mangen::Man::new(cli()).render(&mut io::stdout()) {
+                eprintln!("Error: {}", e);
+                process::exit(EXIT_FAILURE);
+            }
+            return;
+        }
+        #[cfg(feature = "oracle")]
+        Some(("compare-js", sub_matches)) => {
+            match compare_js(sub_matches) {
+                Ok(true) => return,
+                Ok(false) => process::exit(EXIT_FAILURE),
+                Err(failure) => {
+                    eprintln!("{}", failure.message);
+                    process::exit(failure.code);
+                }
+            }
+        }
+        Some(("tune", sub_matches)) => {
+            if let Err(failure) = tune_thresholds(sub_matches) {
+                eprintln!("{}", failure.message);
+                process::exit(failure.code);
+            }
+            return;
+        }
+        Some(("bundle", sub_matches)) => {
+            if let Err(failure) = bundle_articles(sub_matches) {
+                eprintln!("{}", failure.message);
+                process::exit(failure.code);
+            }
+            return;
+        }
+        #[cfg(feature = "fetch")]
+        Some(("sitemap", sub_matches)) => {
+            if let Err(failure) = crawl_sitemap(sub_matches) {
+                eprintln!("{}", failure.message);
+                process::exit(failure.code);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let base_uri = matches.get_one::<String>("base-uri").cloned();
+    let format = match OutputFormat::from(matches.get_one::<String>("format").unwrap().as_str()) {
+        // The page's own URL is the canonical version of its AMP page
+        OutputFormat::Amp { .. } => OutputFormat::Amp { canonical_url: base_uri.clone() },
+        format => format,
+    };
+    let cli_options = CliOptions {
+        input: matches.get_one::<String>("input").cloned(),
+        output: matches.get_one::<String>("output").cloned(),
+        format,
+        base_uri,
+        debug: matches.get_flag("debug"),
+        check_only: matches.get_flag("check"),
+        watch: matches.get_flag("watch"),
+        quiet: matches.get_flag("quiet"),
+        log_format: match matches.get_one::<String>("log-format").unwrap().as_str() {
+            "json" => LogFormat::Json,
+            _ => LogFormat::Text,
+        },
+        fail_on: matches.get_many::<FailOn>("fail-on").into_iter().flatten().copied().collect(),
+        min_content_length: *matches.get_one::<usize>("min-content-length").unwrap(),
+        char_threshold: *matches.get_one::<CharThreshold>("char-threshold").unwrap(),
+        keep_classes: matches.get_flag("keep-classes"),
+        xhtml: matches.get_flag("xhtml"),
+        disable_json_ld: matches.get_flag("disable-json-ld"),
+        profile: matches.get_one::<ExtractionProfile>("profile").copied(),
+        plugins: matches.get_many::<String>("plugin").into_iter().flatten().cloned().collect(),
+    };
+
+    // Failures are already logged by `run`
+    if let Err(failure) = run(cli_options) {
+        process::exit(failure.code);
+    }
+}
+
+/// The command-line definition, shared by argument parsing, completions and the man page
+fn cli() -> Command {
+    let command = Command::new("readability-rust")
+        .version("0.1.0")
+        .author("Mozilla Readability Rust Port")
+        .about("Extract article content from web pages, removing clutter like ads and navigation")
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("completions")
+                .about("Print a shell completion script")
+                .arg(
+                    Arg::new("shell")
+                        .value_name("SHELL")
+                        .help("Shell to generate completions for")
+                        .required(true)
+                        .value_parser(clap::value_parser!(Shell))
+                )
+        )
+        .subcommand(Command::new("man").about("Print the man page"))
+        .subcommand(
+            Command::new("tune")
+                .about("Report precision/recall of the readerable check over a labeled corpus for a sweep of thresholds")
+                .arg(
+                    Arg::new("corpus")
+                        .value_name("DIR")
+                        .help("Directory with readable/ and unreadable/ subdirectories of HTML files")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("char-thresholds")
+                        .long("char-thresholds")
+                        .value_name("SWEEP")
+                        .help("Character thresholds to try: a list (25,100) or a range (25..500:25)")
+                        .default_value("25,50,100,140,250,500")
+                        .value_parser(parse_sweep)
+                )
+                .arg(
+                    Arg::new("min-scores")
+                        .long("min-scores")
+                        .value_name("SWEEP")
+                        .help("Minimum readerable scores to try: a list (20,40) or a range (10..60:10)")
+                        .default_value("8,20,30,40,60")
+                        .value_parser(parse_sweep)
+                )
+        )
+        .subcommand(
+            Command::new("bundle")
+                .about("Bundle several pages into one reading list: an HTML index of reader pages, an EPUB or an Atom feed")
+                .arg(
+                    Arg::new("inputs")
+                        .value_name("INPUT")
+                        .help("HTML files to bundle, in reading order (http(s) URLs are fetched)")
+                        .required(true)
+                        .num_args(1..)
+                )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .value_name("FORMAT")
+                        .help("Bundle format: html (a directory with index.html), epub or atom")
+                        .value_parser(["html", "epub", "atom"])
+                        .default_value("html")
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("PATH")
+                        .help("Directory for html, file for epub and atom")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("title")
+                        .long("title")
+                        .value_name("TITLE")
+                        .help("Title of the reading list")
+                        .default_value("Reading List")
+                )
+                .arg(
+                    Arg::new("quiet")
+                        .short('q')
+                        .long("quiet")
+                        .help("Suppress per-input error messages")
+                        .action(clap::ArgAction::SetTrue)
+                )
+        );
+    #[cfg(feature = "oracle")]
+    let command = command.subcommand(
+        Command::new("compare-js")
+            .about("Diff the extraction with Mozilla's Readability.js (needs Node)")
+            .arg(
+                Arg::new("input")
+                    .short('i')
+                    .long("input")
+                    .value_name("FILE")
+                    .help("Input HTML file (use '-' for stdin)")
+            )
+            .arg(
+                Arg::new("base-uri")
+                    .long("base-uri")
+                    .value_name("URI")
+                    .help("Base URI for resolving relative URLs")
+                    .default_value(corpus::TEST_PAGE_URI)
+            )
+            .arg(
+                Arg::new("node")
+                    .long("node")
+                    .value_name("PATH")
+                    .help("Node binary to run Readability.js with")
+                    .default_value("node")
+            )
+            .arg(
+                Arg::new("min-similarity")
+                    .long("min-similarity")
+                    .value_name("RATIO")
+                    .help("Minimum text similarity (0-1) that counts as parity")
+                    .default_value("0.9")
+                    .value_parser(clap::value_parser!(f64))
+            )
+    );
+    #[cfg(feature = "fetch")]
+    let command = command.subcommand(
+        Command::new("sitemap")
+            .about("Fetch and extract every page listed in a sitemap (or sitemap index), one JSON line each")
+            .arg(
+                Arg::new("url")
+                    .value_name("URL")
+                    .help("URL of the sitemap or sitemap index")
+                    .required(true)
+            )
+            .arg(
+                Arg::new("since")
+                    .long("since")
+                    .value_name("DATE")
+                    .help("Only pages last modified on or after this date (YYYY-MM-DD)")
+                    .value_parser(parse_since)
+            )
+            .arg(
+                Arg::new("format")
+                    .short('f')
+                    .long("format")
+                    .value_name("FORMAT")
+                    .help("Output format: jsonl")
+                    .value_parser(["jsonl"])
+                    .default_value("jsonl")
+            )
+            .arg(
+                Arg::new("output")
+                    .short('o')
+                    .long("output")
+                    .value_name("FILE")
+                    .help("Output file (default: stdout)")
+            )
+            .arg(
+                Arg::new("respect-robots-txt")
+                    .long("respect-robots-txt")
+                    .help("Skip pages that the site's robots.txt disallows")
+                    .action(clap::ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("fallback-wayback")
+                    .long("fallback-wayback")
+                    .help("Extract pages that are gone or unreachable from their latest Wayback Machine capture")
+                    .action(clap::ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("quiet")
+                    .short('q')
+                    .long("quiet")
+                    .help("Suppress per-page error messages")
+                    .action(clap::ArgAction::SetTrue)
+            )
+    );
+    command
+        .arg(
+            Arg::new("input")
+                .short('i')
+                .long("input")
+                .value_name("FILE")
+                .help("Input HTML file (use '-' for stdin)")
+                .required(false)
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("Output file (default: stdout)")
+                .required(false)
+        )
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output format: json, text, html, blocks-json, atom, amp (links --base-uri as its canonical URL)")
+                .default_value("json")
+                .required(false)
+        )
+        .arg(
+            Arg::new("base-uri")
+                .short('b')
+                .long("base-uri")
+                .value_name("URI")
+                .help("Base URI for resolving relative URLs")
+                .required(false)
+        )
+        .arg(
+            Arg::new("debug")
+                .short('d')
+                .long("debug")
+                .help("Enable debug output")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("check")
+                .short('c')
+                .long("check")
+                .help("Only check if document is readable (exit code 0=readable, 4=not readable)")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .help("Re-run extraction whenever the input file (or an HTML file in the input directory) changes")
+                .requires("input")
+                .conflicts_with("check")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Suppress error messages; only the exit code reports failures")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("log-format")
+                .long("log-format")
+                .value_name("FORMAT")
+                .help("Format of per-document log records on stderr")
+                .value_parser(["text", "json"])
+                .default_value("text")
+        )
+        .arg(
+            Arg::new("fail-on")
+                .long("fail-on")
+                .value_name("CONDITION")
+                .help("Also fail on: short, not-readerable, fetch-error (comma-separated or repeated)")
+                .value_delimiter(',')
+                .value_parser(FailOn::parse)
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("min-content-length")
+                .long("min-content-length")
+                .value_name("LENGTH")
+                .help("Minimum article length in characters for --fail-on short")
+                .default_value("140")
+                .value_parser(clap::value_parser!(usize))
+        )
+        .arg(
+            Arg::new("char-threshold")
+                .long("char-threshold")
+                .value_name("CHARS")
+                .help("Minimum character threshold for article content, or 'auto' to pick it by language")
+                .default_value("500")
+                .value_parser(parse_char_threshold)
+        )
+        .arg(
+            Arg::new("keep-classes")
+                .long("keep-classes")
+                .help("Keep CSS classes in output")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("xhtml")
+                .long("xhtml")
+                .help("Write the content as well-formed XHTML")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("disable-json-ld")
+                .long("disable-json-ld")
+                .help("Disable JSON-LD parsing for metadata")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("PROFILE")
+                .help("Input profile: generic, mediawiki, docs, email (default: detected from the document)")
+                .value_parser(parse_profile)
+        )
+        .arg(
+            Arg::new("plugin")
+                .long("plugin")
+                .value_name("NAME")
+                .help(format!(
+                    "Run a cleaner plugin on the content: {} (comma-separated or repeated)",
+                    plugins::plugin_names().join(", ")
+                ))
+                .value_delimiter(',')
+                .value_parser(parse_plugin)
+                .action(clap::ArgAction::Append)
+        )
+}
+
+fn run(options: CliOptions) -> Result<(), Failure> {
+    let input = options.input.as_deref().unwrap_or("-");
+    let result = if options.watch {
+        let result = watch(&options);
+        if let Err(failure) = &result {
+            log_result(&options, input, Duration::ZERO, Err(failure));
+        }
+        result
+    } else {
+        let started = Instant::now();
+        let result = read_input(&options.input)
+            .map_err(|e| Failure::new(EXIT_FETCH_ERROR, format!("Error: {}", e)))
+            .and_then(|html| {
+                if options.debug {
+                    eprintln!("Read {} bytes of HTML", html.len());
+                }
+
+                // If check-only mode, just test readability
+                if options.check_only {
+                    let readable = is_readerable(&options, &html);
+                    if options.debug {
+                        eprintln!("Document is {}readable", if readable { "" } else { "not " });
+                    }
+                    return if readable {
+                        Ok(None)
+                    } else {
+                        Err(Failure::new(EXIT_NOT_READERABLE, "Document is not readerable"))
+                    };
+                }
+
+                extract(&options, &html, options.output.as_deref().map(Path::new)).map(Some)
+            });
+        log_result(&options, input, started.elapsed(), result.as_ref().copied());
+        result.map(drop)
+    };
+    result.map_err(|failure| fetch_error_code(&options, failure))
+}
+
+/// Unreadable input fails with the general exit code, or its own one under `--fail-on fetch-error`
+fn fetch_error_code(options: &CliOptions, failure: Failure) -> Failure {
+    if failure.code == EXIT_FETCH_ERROR && !options.fail_on.contains(&FailOn::FetchError) {
+        Failure { code: EXIT_FAILURE, ..failure }
+    } else {
+        failure
+    }
+}
+
+/// `is_probably_readerable` with the user's `--char-threshold`
+fn is_readerable(options: &CliOptions, html: &[u8]) -> bool {
+    let readability_options = ReadabilityOptions { char_threshold: options.char_threshold, ..Default::default() };
+    is_probably_readerable(&decode_lossy(html).0, Some(readability_options))
+}
+
+/// Extract the article from `html` and write it to `output` (stdout if `None`),
+/// returning the article length
+fn extract(options: &CliOptions, html: &[u8], output: Option<&Path>) -> Result<usize, Failure> {
+    if options.fail_on.contains(&FailOn::NotReaderable) && !is_readerable(options, html) {
+        return Err(Failure::new(EXIT_NOT_READERABLE, "Document is not readerable"));
+    }
+
+    let mut cleaners = CleanerRegistry::new();
+    for plugin in options.plugins.iter().filter_map(|name| plugins::find_plugin(name)) {
+        cleaners.register_shared(plugin);
+    }
+
+    // Create readability options
+    let readability_options = ReadabilityOptions {
+        debug: options.debug,
+        char_threshold: options.char_threshold,
+        keep_classes: options.keep_classes,
+        xhtml_output: options.xhtml,
+        disable_json_ld: options.disable_json_ld,
+        profile: options.profile,
+        include_blocks: matches!(options.format, OutputFormat::BlocksJson),
+        cleaners,
+        ..Default::default()
+    };
+
+    // Create readability parser
+    let mut readability = if let Some(base_uri) = &options.base_uri {
+        Readability::from_bytes_with_base_uri(html, base_uri, Some(readability_options))?
+    } else {
+        Readability::from_bytes(html, Some(readability_options))?
+    };
+
+    // Parse the document
+    let article = readability.parse_result();
+    
+    match article {
+        Ok(article) => {
+            let length = article.length.unwrap_or(0);
+            if options.fail_on.contains(&FailOn::Short) && length < options.min_content_length {
+                return Err(Failure::new(
+                    EXIT_TOO_SHORT,
+                    format!("Article is too short ({} < {} characters)", length, options.min_content_length),
+                ));
+            }
+            write_output(&article, &options.format, output)?;
+            
+            if options.debug {
+                eprintln!("Successfully extracted article:");
+                eprintln!("  Title: {}", article.title.as_deref().unwrap_or("None"));
+                eprintln!("  Length: {} characters", article.length.unwrap_or(0));
+                if article.decoding_errors > 0 {
+                    eprintln!("  Replaced {} invalid UTF-8 sequences", article.decoding_errors);
+                }
+            }
+            Ok(length)
+        }
+        Err(ReadabilityError::NotAnArticle(reason)) => {
+            Err(Failure::new(EXIT_NOT_AN_ARTICLE, format!("Document is not an article ({})", reason)))
+        }
+        Err(ReadabilityError::UnsupportedInput(reason)) => {
+            Err(Failure::new(EXIT_FAILURE, format!("Unsupported input ({})", reason)))
+        }
+        Err(_) => {
+            Err(Failure::new(EXIT_FAILURE, "Failed to extract article content from the document"))
+        }
+    }
+}
+
+/// Report the outcome of one document on stderr, as text or a JSON line
+fn log_result(options: &CliOptions, input: &str, elapsed: Duration, result: Result<Option<usize>, &Failure>) {
+    if options.quiet {
+        return;
+    }
+    match options.log_format {
+        LogFormat::Json => {
+            let record = serde_json::json!({
+                "input": input,
+                "outcome": if result.is_ok() { "ok" } else { "error" },
+                "duration_ms": elapsed.as_secs_f64() * 1000.0,
+                "content_length": result.ok().flatten(),
+                "reason": result.err().map(Failure::reason),
+                "message": result.err().map(|failure| &failure.message),
+            });
+            eprintln!("{}", record);
+        }
+        LogFormat::Text => match result {
+            Ok(_) if options.watch => eprintln!("{}: updated", input),
+            Ok(_) => {}
+            Err(failure) if options.watch => eprintln!("{}: {}", input, failure.message),
+            Err(failure) => eprintln!("{}", failure.message),
+        },
+    }
+}
+
+/// Re-run extraction whenever the input file, or an HTML file in the input
+/// directory, changes
+///
+/// Failures are reported per file and watching continues. For a directory,
+/// `--output` names another directory that receives one output file per
+/// input file.
+fn watch(options: &CliOptions) -> Result<(), Failure> {
+    let input = match options.input.as_deref() {
+        Some(path) if path != "-" => Path::new(path),
+        _ => return Err(Failure::new(EXIT_FAILURE, "Error: --watch needs an input file or directory")),
+    };
+    if !input.exists() {
+        return Err(Failure::new(EXIT_FETCH_ERROR, format!("Error: Input '{}' does not exist", input.display())));
+    }
+    // Output files written next to the inputs would be watched and extracted again
+    if let (Some(output), true) = (&options.output, input.is_dir()) {
+        if fs::canonicalize(output).ok() == fs::canonicalize(input).ok() {
+            return Err(Failure::new(EXIT_FAILURE, "Error: --output must be a directory other than the watched one"));
+        }
+    }
+
+    let mut seen: HashMap<PathBuf, SystemTime> = HashMap::new();
+    loop {
+        for path in watched_files(input) {
+            let Ok(modified) = fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+                continue;
+            };
+            if seen.insert(path.clone(), modified) == Some(modified) {
+                continue;
+            }
+
+            let output = match (&options.output, input.is_dir()) {
+                (Some(dir), true) => fs::create_dir_all(dir)
+                    .map_err(|e| Failure::new(EXIT_FAILURE, format!("Error: Cannot create '{}': {}", dir, e)))
+                    .map(|()| {
+                        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                        Some(Path::new(dir).join(format!("{}.{}", stem, output_extension(&options.format))))
+                    }),
+                (output, _) => Ok(output.as_ref().map(PathBuf::from)),
+            };
+            let started = Instant::now();
+            let result = output.and_then(|output| {
+                fs::read(&path)
+                    .map_err(|e| Failure::new(EXIT_FETCH_ERROR, format!("Error: {}", e)))
+                    .and_then(|html| extract(options, &html, output.as_deref()))
+            });
+            log_result(options, &path.display().to_string(), started.elapsed(), result.as_ref().copied().map(Some));
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// The file itself, or the HTML files directly inside a directory
+fn watched_files(input: &Path) -> Vec<PathBuf> {
+    if !input.is_dir() {
+        return vec![input.to_path_buf()];
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(input)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_html_file(path))
+        .collect();
+    files.sort();
+    files
+}
+
+/// File extension for output written in `format`
+fn output_extension(format: &OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Json | OutputFormat::BlocksJson => "json",
+        OutputFormat::Text => "txt",
+        OutputFormat::Html | OutputFormat::Amp { .. } => "html",
+        OutputFormat::Atom => "xml",
+    }
+}
+
+/// Run the `compare-js` subcommand, printing the diff; `Ok(false)` if the outputs differ
+#[cfg(feature = "oracle")]
+fn compare_js(matches: &ArgMatches) -> Result<bool, Failure> {
+    let html = read_input(&matches.get_one::<String>("input").cloned())
+        .map_err(|e| Failure::new(EXIT_FETCH_ERROR, format!("Error: {}", e)))?;
+    let oracle = oracle::JsOracle::new().with_node(matches.get_one::<String>("node").unwrap());
+    let base_uri = matches.get_one::<String>("base-uri").unwrap();
+    let diff = oracle::compare_with_js(&oracle, &decode_lossy(&html).0, base_uri, None)?;
+    println!("{}", serde_json::to_string_pretty(&diff)?);
+    Ok(diff.is_parity(*matches.get_one::<f64>("min-similarity").unwrap()))
+}
+
+/// Run the `bundle` subcommand: extract every input into one reading list
+///
+/// Inputs that fail are reported on stderr and left out; the bundle of the
+/// others is still written, and the run then fails.
+fn bundle_articles(matches: &ArgMatches) -> Result<(), Failure> {
+    let inputs: Vec<&String> = matches.get_many::<String>("inputs").unwrap().collect();
+    let output = Path::new(matches.get_one::<String>("output").unwrap());
+    let quiet = matches.get_flag("quiet");
+
+    let mut list = bundle::ReadingList::new(matches.get_one::<String>("title").unwrap());
+    let mut failed = 0;
+    for &input in &inputs {
+        let result = if input.starts_with("http://") || input.starts_with("https://") {
+            fetch_article(input)
+        } else {
+            read_input(&Some(input.clone())).map_err(|e| e.to_string()).and_then(|html| {
+                Readability::from_bytes(&html, None).and_then(|mut readability| readability.parse_result()).map_err(|e| e.to_string())
+            })
+        };
+        match result {
+            Ok(article) => list.push(input.as_str(), article),
+            Err(error) => {
+                failed += 1;
+                if !quiet {
+                    eprintln!("{}: {}", input, error);
+                }
+            }
+        }
+    }
+
+    if list.entries.is_empty() {
+        return Err(Failure::new(EXIT_FAILURE, "Error: no input could be extracted"));
+    }
+    match matches.get_one::<String>("format").unwrap().as_str() {
+        "epub" => {
+            let writer = list.write_epub(BufWriter::new(fs::File::create(output)?))?;
+            writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        }
+        "atom" => {
+            let mut writer = BufWriter::new(fs::File::create(output)?);
+            list.write_atom(&mut writer)?;
+            writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        }
+        _ => list.write_html(output)?,
+    }
+    if failed > 0 {
+        return Err(Failure::new(EXIT_FAILURE, format!("Error: {} of {} inputs failed", failed, inputs.len())));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "fetch")]
+fn fetch_article(url: &str) -> Result<Article, String> {
+    readability_rust::fetch::fetch_and_parse(url, None).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "fetch"))]
+fn fetch_article(_url: &str) -> Result<Article, String> {
+    Err("fetching URLs needs the fetch feature".to_string())
+}
+
+/// Run the `sitemap` subcommand: stream one JSON line per extracted page
+///
+/// Pages that fail are reported on stderr and skipped; the run then fails
+/// once all pages were tried.
+#[cfg(feature = "fetch")]
+fn crawl_sitemap(matches: &ArgMatches) -> Result<(), Failure> {
+    let url = matches.get_one::<String>("url").unwrap();
+    let since = matches.get_one::<String>("since").map(String::as_str);
+    let quiet = matches.get_flag("quiet");
+    let fetcher = Fetcher::new(FetchPolicy {
+        respect_robots_txt: matches.get_flag("respect-robots-txt"),
+        fallback_wayback: matches.get_flag("fallback-wayback"),
+        ..Default::default()
+    });
+
+    let entries = sitemap::entries(&fetcher, url, since)
+        .map_err(|e| Failure::new(EXIT_FETCH_ERROR, format!("Error: {}", e)))?;
+    let mut writer: Box<dyn Write> = match matches.get_one::<String>("output") {
+        Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut failed = 0;
+    for entry in &entries {
+        match fetcher.fetch_if_modified(&entry.loc, &Validators::default(), None) {
+            Ok(FetchOutcome::Modified(fetched)) => {
+                serde_json::to_writer(&mut writer, &fetched)?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+            // Only answered to requests with validators
+            Ok(FetchOutcome::NotModified) => {}
+            Err(error) => {
+                failed += 1;
+                if !quiet {
+                    eprintln!("{}: {}", entry.loc, error);
+                }
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(Failure::new(EXIT_FETCH_ERROR, format!("Error: {} of {} pages failed", failed, entries.len())));
+    }
+    Ok(())
+}
+
+/// Run the `tune` subcommand, printing one row per setting and the best one
+fn tune_thresholds(matches: &ArgMatches) -> Result<(), Failure> {
+    let dir = Path::new(matches.get_one::<String>("corpus").unwrap());
+    let pages = tune::load_labeled_corpus(dir)
+        .map_err(|e| Failure::new(EXIT_FETCH_ERROR, format!("Error: {}: {}", dir.display(), e)))?;
+    let char_thresholds: Vec<usize> =
+        matches.get_one::<Vec<f64>>("char-thresholds").unwrap().iter().map(|threshold| threshold.round() as usize).collect();
+    let min_scores = matches.get_one::<Vec<f64>>("min-scores").unwrap();
+
+    let results = tune::sweep(&pages, &char_thresholds, min_scores, &ReadabilityOptions::default());
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{:>14} {:>9} {:>9} {:>6} {:>6}", "char_threshold", "min_score", "precision", "recall", "f1")?;
+    for result in &results {
+        writeln!(
+            stdout,
+            "{:>14} {:>9} {:>9.3} {:>6.3} {:>6.3}",
+            result.char_threshold,
+            result.min_score,
+            result.precision(),
+            result.recall(),
+            result.f1()
+        )?;
+    }
+    if let Some(best) = tune::best(&results) {
+        writeln!(
+            stdout,
+            "\nBest F1 {:.3} over {} pages: char_threshold {}, readerable_min_score {}",
+            best.f1(),
+            pages.len(),
+            best.char_threshold,
+            best.min_score
+        )?;
+    }
+    Ok(())
+}
+
+fn read_input(input: &Option<String>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    match input {
+        Some(path) if path == "-" => {
+            // Read from stdin
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            Ok(buffer)
+        }
+        Some(path) => {
+            // Read from file
+            if !Path::new(path).exists() {
+                return Err(format!("Input file '{}' does not exist", path).into());
+            }
+            fs::read(path).map_err(|e| e.into())
+        }
+        None => {
+            // Read from stdin if no input specified
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            Ok(buffer)
+        }
+    }
+}
+
+fn write_output(
+    article: &Article,
+    format: &OutputFormat,
+    output: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Stream straight to the destination so large articles aren't buffered twice
+    let Some(path) = output else {
+        let mut writer = BufWriter::new(io::stdout().lock());
+        write_article(&mut writer, article, format)?;
+        writer.flush()?;
+        return Ok(());
+    };
+
+    // Write next to the target and rename, so readers never see a partial file
+    let file_name = path.file_name().ok_or_else(|| format!("Invalid output path '{}'", path.display()))?;
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    let mut writer = BufWriter::new(fs::File::create(&temp_path)?);
+    write_article(&mut writer, article, format)?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fail_on_parse() {
+        assert_eq!(FailOn::parse("short"), Ok(FailOn::Short));
+        assert_eq!(FailOn::parse("not-readerable"), Ok(FailOn::NotReaderable));
+        assert_eq!(FailOn::parse("fetch-error"), Ok(FailOn::FetchError));
+        assert!(FailOn::parse("slow").is_err());
+    }
+
+    #[test]
+    fn test_parse_char_threshold() {
+        assert_eq!(parse_char_threshold("300"), Ok(CharThreshold::Chars(300)));
+        assert_eq!(parse_char_threshold("auto"), Ok(CharThreshold::Auto));
+        assert!(parse_char_threshold("many").is_err());
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        assert_eq!(parse_profile("email"), Ok(ExtractionProfile::Email));
+        assert_eq!(parse_profile("mediawiki"), Ok(ExtractionProfile::MediaWiki));
+        assert!(parse_profile("newsletter").is_err());
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("2024-01-31"), Ok("2024-01-31".to_string()));
+        assert!(parse_since("2024-1-31").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_parse_sweep() {
+        assert_eq!(parse_sweep("25,100, 500"), Ok(vec![25.0, 100.0, 500.0]));
+        assert_eq!(parse_sweep("0.1..0.3:0.1").map(|values| values.len()), Ok(3));
+        assert_eq!(parse_sweep("25..100:25"), Ok(vec![25.0, 50.0, 75.0, 100.0]));
+        assert!(parse_sweep("25..100").is_err());
+        assert!(parse_sweep("100..25:5").is_err());
+        assert!(parse_sweep("a,b").is_err());
+    }
+
+    #[test]
+    fn test_parse_plugin() {
+        assert_eq!(parse_plugin("strip-links"), Ok("strip-links".to_string()));
+        assert!(parse_plugin("medium").unwrap_err().contains("strip-images"));
+    }
+
+    #[test]
+    fn test_failure_reason() {
+        assert_eq!(Failure::new(EXIT_FETCH_ERROR, "").reason(), "fetch_error");
+        assert_eq!(Failure::new(EXIT_TOO_SHORT, "").reason(), "too_short");
+        assert_eq!(Failure::from("boom").reason(), "extraction_failed");
+    }
+
+    #[test]
+    fn test_watched_files() {
+        let dir = std::env::temp_dir().join(format!("readability-watch-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["b.html", "a.HTM", "notes.txt"] {
+            fs::write(dir.join(name), "<p>x</p>").unwrap();
+        }
+
+        let files = watched_files(&dir);
+        assert_eq!(files, vec![dir.join("a.HTM"), dir.join("b.html")]);
+        assert_eq!(watched_files(&dir.join("b.html")), vec![dir.join("b.html")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_output_is_atomic() {
+        let dir = std::env::temp_dir().join(format!("readability-output-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("article.txt");
+        let article = Article { title: Some("Title".to_string()), ..Default::default() };
+
+        write_output(&article, &OutputFormat::Text, Some(&path)).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Title: Title\n\n");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1); // no temp file left behind
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        cli().debug_assert();
+    }
+}
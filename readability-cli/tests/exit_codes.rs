@@ -0,0 +1,64 @@
+//! Exit codes of the `readability-rust` binary
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// A page too short to be readerable at the default `--char-threshold`
+const SHORT_PAGE: &str = "<html><body><p>A short note about rivers and the sea, just a sentence.</p></body></html>";
+
+/// Run the binary with `args`, feeding `stdin` as the input document, and
+/// return its exit code and stderr
+fn run(args: &[&str], stdin: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_readability-rust"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (output.status.code().unwrap(), String::from_utf8(output.stderr).unwrap())
+}
+
+fn exit_code(args: &[&str], stdin: &str) -> i32 {
+    run(args, stdin).0
+}
+
+#[test]
+fn test_check_exit_codes() {
+    assert_eq!(exit_code(&["--check"], SHORT_PAGE), 4);
+    assert_eq!(exit_code(&["--check", "--char-threshold", "20"], SHORT_PAGE), 0);
+}
+
+#[test]
+fn test_check_is_logged() {
+    let (code, stderr) = run(&["--check", "--log-format", "json"], SHORT_PAGE);
+    assert_eq!(code, 4);
+    assert!(stderr.contains("\"reason\":\"not_readerable\""), "{}", stderr);
+}
+
+#[test]
+fn test_fail_on_not_readerable_uses_char_threshold() {
+    assert_eq!(exit_code(&["--fail-on", "not-readerable"], SHORT_PAGE), 4);
+    assert_eq!(exit_code(&["--fail-on", "not-readerable", "--char-threshold", "20"], SHORT_PAGE), 0);
+}
+
+#[test]
+fn test_watch_rejects_output_into_input_directory() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("watched");
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir = dir.to_str().unwrap();
+    let (code, stderr) = run(&["-i", dir, "-o", dir, "--watch"], "");
+    assert_eq!(code, 1);
+    assert!(stderr.contains("--output"), "{}", stderr);
+}
+
+#[test]
+fn test_fetch_error_fails() {
+    let missing = Path::new(env!("CARGO_TARGET_TMPDIR")).join("missing.html");
+    let missing = missing.to_str().unwrap();
+    assert_eq!(exit_code(&["-i", missing], ""), 1);
+    assert_eq!(exit_code(&["-i", missing, "--fail-on", "fetch-error"], ""), 3);
+}
@@ -0,0 +1,631 @@
+//! Text analysis helpers operating on extracted article text
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::{OnceLock, RwLock};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Common English abbreviations that should not end a sentence
+pub const ENGLISH_ABBREVIATIONS: &[&str] = &[
+    "Mr.", "Mrs.", "Ms.", "Dr.", "Prof.", "Sr.", "Jr.", "St.", "vs.", "etc.",
+    "e.g.", "i.e.", "Inc.", "Ltd.", "Co.", "Corp.", "No.", "Fig.", "Jan.", "Feb.",
+    "Mar.", "Apr.", "Jun.", "Jul.", "Aug.", "Sep.", "Sept.", "Oct.", "Nov.", "Dec.",
+];
+
+/// Common German abbreviations that should not end a sentence
+pub const GERMAN_ABBREVIATIONS: &[&str] = &[
+    "Dr.", "Prof.", "Hr.", "Fr.", "bzw.", "ca.", "d.h.", "evtl.", "ggf.", "Nr.",
+    "s.", "sog.", "u.a.", "usw.", "vgl.", "z.B.", "z.T.",
+];
+
+/// Common French abbreviations that should not end a sentence
+pub const FRENCH_ABBREVIATIONS: &[&str] = &[
+    "M.", "MM.", "Mme.", "Mlle.", "Dr.", "Pr.", "etc.", "cf.", "p.", "n°.",
+];
+
+/// Built-in English stop words
+pub const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be",
+    "been", "but", "by", "can", "could", "for", "from", "had", "has", "have", "he", "her",
+    "his", "how", "i", "if", "in", "into", "is", "it", "its", "more", "not", "of", "on",
+    "or", "our", "she", "so", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "to", "was", "we", "were", "what", "when", "which", "who",
+    "will", "with", "would", "you", "your",
+];
+
+/// Built-in German stop words
+pub const GERMAN_STOP_WORDS: &[&str] = &[
+    "aber", "als", "am", "an", "auch", "auf", "aus", "bei", "bis", "das", "dass", "dem",
+    "den", "der", "des", "die", "ein", "eine", "einem", "einen", "einer", "es", "für",
+    "hat", "ich", "im", "in", "ist", "mit", "nach", "nicht", "noch", "oder", "sich",
+    "sie", "sind", "so", "über", "um", "und", "vom", "von", "vor", "war", "wie", "wir",
+    "wird", "zu", "zum", "zur",
+];
+
+/// Built-in French stop words
+pub const FRENCH_STOP_WORDS: &[&str] = &[
+    "au", "aux", "avec", "ce", "ces", "dans", "de", "des", "du", "elle", "en", "est",
+    "et", "il", "ils", "je", "la", "le", "les", "leur", "lui", "mais", "ne", "nous",
+    "ou", "par", "pas", "pour", "qui", "que", "sa", "se", "ses", "son", "sur", "un",
+    "une", "vous",
+];
+
+/// Built-in Spanish stop words
+pub const SPANISH_STOP_WORDS: &[&str] = &[
+    "al", "como", "con", "de", "del", "el", "en", "es", "esta", "este", "la", "las",
+    "lo", "los", "más", "no", "o", "para", "pero", "por", "que", "se", "si", "sin",
+    "su", "sus", "un", "una", "y",
+];
+
+type StopWordRegistry = RwLock<HashMap<String, HashSet<String>>>;
+
+/// Global stop-word registry, seeded with the built-in lists
+static STOP_WORDS: OnceLock<StopWordRegistry> = OnceLock::new();
+
+fn stop_word_registry() -> &'static StopWordRegistry {
+    STOP_WORDS.get_or_init(|| {
+        let builtin = [
+            ("en", ENGLISH_STOP_WORDS),
+            ("de", GERMAN_STOP_WORDS),
+            ("fr", FRENCH_STOP_WORDS),
+            ("es", SPANISH_STOP_WORDS),
+        ];
+        let registry = builtin
+            .iter()
+            .map(|(lang, words)| {
+                (lang.to_string(), words.iter().map(|word| word.to_string()).collect())
+            })
+            .collect();
+        RwLock::new(registry)
+    })
+}
+
+pub(crate) fn primary_language(lang: Option<&str>) -> Option<String> {
+    lang.and_then(|lang| lang.split(['-', '_']).next())
+        .filter(|lang| !lang.is_empty())
+        .map(|lang| lang.to_lowercase())
+}
+
+/// Register additional stop words for a language, extending any existing list
+pub fn register_stop_words<I, S>(lang: &str, words: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let Some(lang) = primary_language(Some(lang)) else {
+        return;
+    };
+    let mut registry = stop_word_registry().write().unwrap_or_else(|e| e.into_inner());
+    registry
+        .entry(lang)
+        .or_default()
+        .extend(words.into_iter().map(|word| word.as_ref().to_lowercase()));
+}
+
+/// Check whether `word` is a stop word for `lang` (English when unknown)
+pub fn is_stop_word(word: &str, lang: Option<&str>) -> bool {
+    let lang = primary_language(lang).unwrap_or_else(|| "en".to_string());
+    let registry = stop_word_registry().read().unwrap_or_else(|e| e.into_inner());
+    registry
+        .get(&lang)
+        .is_some_and(|words| words.contains(&word.to_lowercase()))
+}
+
+/// Check if a character belongs to a CJK script without word separators
+pub fn is_cjk_char(ch: char) -> bool {
+    matches!(ch as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x20000..=0x2FFFF // CJK Extensions B+
+    )
+}
+
+/// Approximate the number of BPE tokens (as in GPT-style tokenizers) in `text`
+///
+/// Each CJK character and punctuation mark counts as a token, and runs of
+/// other characters as one token per four characters, rounded up.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run = 0usize;
+    for ch in text.chars() {
+        if ch.is_alphanumeric() && !is_cjk_char(ch) {
+            run += 1;
+            continue;
+        }
+        tokens += run.div_ceil(4);
+        run = 0;
+        if !ch.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + run.div_ceil(4)
+}
+
+/// Guess a language from the dominant non-Latin script in `text`
+///
+/// Only scripts that change scoring behavior are recognized: kana implies
+/// Japanese, other CJK ideographs Chinese, and Thai characters Thai.
+pub fn detect_language_from_script(text: &str) -> Option<&'static str> {
+    let mut letters = 0usize;
+    let mut kana = 0usize;
+    let mut han = 0usize;
+    let mut thai = 0usize;
+
+    for ch in text.chars().filter(|ch| ch.is_alphabetic()).take(2000) {
+        letters += 1;
+        match ch as u32 {
+            0x3040..=0x30FF => kana += 1,
+            0x0E00..=0x0E7F => thai += 1,
+            _ if is_cjk_char(ch) => han += 1,
+            _ => {}
+        }
+    }
+
+    if letters == 0 {
+        None
+    } else if (kana + han) * 2 > letters {
+        Some(if kana > 0 { "ja" } else { "zh" })
+    } else if thai * 2 > letters {
+        Some("th")
+    } else {
+        None
+    }
+}
+
+/// Split text into lowercase word tokens for `lang`
+///
+/// For Chinese and Japanese, runs of CJK characters are split into
+/// overlapping character bigrams. Stop words are not removed; see
+/// [`keywords_for_lang`].
+pub fn tokenize_for_lang(text: &str, lang: Option<&str>) -> Vec<String> {
+    let bigrams = matches!(primary_language(lang).as_deref(), Some("zh" | "ja"));
+    if !bigrams {
+        return text.unicode_words().map(|word| word.to_lowercase()).collect();
+    }
+
+    let mut tokens = Vec::new();
+    let mut run: Vec<char> = Vec::new();
+    let mut segment_start = 0;
+
+    for (offset, ch) in text.char_indices() {
+        if is_cjk_char(ch) {
+            if run.is_empty() {
+                tokens.extend(text[segment_start..offset].unicode_words().map(|w| w.to_lowercase()));
+            }
+            run.push(ch);
+        } else if !run.is_empty() {
+            push_cjk_bigrams(&run, &mut tokens);
+            run.clear();
+            segment_start = offset;
+        }
+    }
+
+    if run.is_empty() {
+        tokens.extend(text[segment_start..].unicode_words().map(|w| w.to_lowercase()));
+    } else {
+        push_cjk_bigrams(&run, &mut tokens);
+    }
+
+    tokens
+}
+
+fn push_cjk_bigrams(run: &[char], tokens: &mut Vec<String>) {
+    if run.len() == 1 {
+        tokens.push(run[0].to_string());
+    } else {
+        tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>()));
+    }
+}
+
+/// Tokenize `text` for `lang` and drop stop words and very short tokens
+pub fn keywords_for_lang(text: &str, lang: Option<&str>) -> Vec<String> {
+    tokenize_for_lang(text, lang)
+        .into_iter()
+        .filter(|token| {
+            let min_chars = if token.chars().any(is_cjk_char) { 1 } else { 3 };
+            token.chars().count() >= min_chars && !is_stop_word(token, lang)
+        })
+        .collect()
+}
+
+/// A sentence within a larger text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sentence<'a> {
+    /// Sentence text with surrounding whitespace trimmed
+    pub text: &'a str,
+    /// Byte range of `text` within the source string
+    pub range: Range<usize>,
+}
+
+/// Get the abbreviation list for a language tag such as `en` or `de-AT`
+pub fn abbreviations_for(lang: Option<&str>) -> &'static [&'static str] {
+    let primary = lang
+        .and_then(|lang| lang.split(['-', '_']).next())
+        .map(|lang| lang.to_lowercase());
+
+    match primary.as_deref() {
+        Some("de") => GERMAN_ABBREVIATIONS,
+        Some("fr") => FRENCH_ABBREVIATIONS,
+        Some("en") | None => ENGLISH_ABBREVIATIONS,
+        _ => &[],
+    }
+}
+
+/// Split text into sentences using Unicode sentence boundaries
+///
+/// Boundaries that directly follow one of `abbreviations` are not treated as
+/// sentence ends.
+pub fn split_sentences<'a>(text: &'a str, abbreviations: &[&str]) -> Vec<Sentence<'a>> {
+    let mut sentences = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (offset, segment) in text.split_sentence_bound_indices() {
+        let sentence_start = *start.get_or_insert(offset);
+        let end = offset + segment.trim_end().len();
+        let candidate = &text[sentence_start..end];
+
+        let last_word = candidate.split_whitespace().last().unwrap_or("");
+        let followed_by_text = offset + segment.len() < text.len();
+        if followed_by_text && abbreviations.contains(&last_word) {
+            continue;
+        }
+
+        start = None;
+        let leading = candidate.len() - candidate.trim_start().len();
+        if candidate.trim().is_empty() {
+            continue;
+        }
+        sentences.push(Sentence {
+            text: candidate.trim(),
+            range: sentence_start + leading..end,
+        });
+    }
+
+    sentences
+}
+
+/// Build an extractive summary of at most `max_sentences` sentences
+///
+/// Sentences are ranked by the frequency of their keywords across the whole
+/// text plus a bonus for appearing early, then returned in original order.
+pub fn summarize(text: &str, lang: Option<&str>, max_sentences: usize) -> Option<String> {
+    if max_sentences == 0 {
+        return None;
+    }
+
+    let sentences = split_sentences(text, abbreviations_for(lang));
+    if sentences.is_empty() {
+        return None;
+    }
+
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for word in keywords_for_lang(text, lang) {
+        *frequencies.entry(word).or_insert(0) += 1;
+    }
+
+    let count = sentences.len() as f64;
+    let mut ranked: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(index, sentence)| {
+            let words = keywords_for_lang(sentence.text, lang);
+            let keyword_score = if words.is_empty() {
+                0.0
+            } else {
+                let total: usize = words.iter().map(|word| frequencies[word]).sum();
+                total as f64 / words.len() as f64
+            };
+            let position_score = 1.0 - index as f64 / count;
+            (index, keyword_score + position_score)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let mut selected: Vec<usize> = ranked.iter().take(max_sentences).map(|(index, _)| *index).collect();
+    selected.sort_unstable();
+
+    let summary = selected
+        .iter()
+        .map(|&index| sentences[index].text)
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(summary)
+}
+
+/// Shorten `text` to at most `max_chars` characters (0 = no limit)
+///
+/// Whole sentences are kept when they fill at least half the limit;
+/// otherwise the text is cut at the last word boundary and ends with `…`.
+pub fn truncate_excerpt(text: &str, max_chars: usize, lang: Option<&str>) -> String {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let sentence_end = split_sentences(text, abbreviations_for(lang))
+        .iter()
+        .map(|sentence| sentence.range.end)
+        .take_while(|&end| text[..end].chars().count() <= max_chars)
+        .last();
+    if let Some(end) = sentence_end.filter(|&end| text[..end].chars().count() * 2 >= max_chars) {
+        return text[..end].to_string();
+    }
+
+    // Leave room for the ellipsis
+    let limit = text.char_indices().nth(max_chars - 1).map_or(text.len(), |(index, _)| index);
+    let cut = if text[limit..].starts_with(char::is_whitespace) {
+        limit
+    } else {
+        text[..limit].rfind(char::is_whitespace).filter(|&index| index > 0).unwrap_or(limit)
+    };
+    let kept = text[..cut].trim_end().trim_end_matches([',', ';', ':', '-', '–', '—', '、', '，']);
+    format!("{}…", kept)
+}
+
+/// Reading-level formulas supported by [`reading_level`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReadingLevelFormula {
+    /// Flesch Reading Ease (English)
+    FleschReadingEase,
+    /// Flesch-Kincaid Grade Level (English)
+    FleschKincaidGrade,
+    /// Flesch Reading Ease adapted by Amstad (German)
+    Amstad,
+    /// First Wiener Sachtextformel (German)
+    WienerSachtextformel,
+    /// Fernández Huerta (Spanish)
+    FernandezHuerta,
+    /// Kandel-Moles (French)
+    KandelMoles,
+}
+
+/// A reading-level score computed with a specific formula
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReadingLevel {
+    pub formula: ReadingLevelFormula,
+    pub score: f64,
+}
+
+/// Counts used by the reading-level formulas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStatistics {
+    pub sentences: usize,
+    pub words: usize,
+    pub syllables: usize,
+    /// Words with more than six letters
+    pub long_words: usize,
+    /// Words with three or more syllables
+    pub polysyllabic_words: usize,
+    /// Words with exactly one syllable
+    pub monosyllabic_words: usize,
+}
+
+impl TextStatistics {
+    /// Average number of words per sentence
+    pub fn words_per_sentence(&self) -> f64 {
+        self.words as f64 / self.sentences.max(1) as f64
+    }
+
+    /// Average number of syllables per word
+    pub fn syllables_per_word(&self) -> f64 {
+        self.syllables as f64 / self.words.max(1) as f64
+    }
+
+    fn percent_of_words(&self, count: usize) -> f64 {
+        100.0 * count as f64 / self.words.max(1) as f64
+    }
+}
+
+/// Gather sentence, word and syllable counts for `text`
+pub fn text_statistics(text: &str, abbreviations: &[&str]) -> TextStatistics {
+    let mut stats = TextStatistics {
+        sentences: split_sentences(text, abbreviations).len(),
+        ..Default::default()
+    };
+
+    for word in text.unicode_words() {
+        let syllables = count_syllables(word);
+        stats.words += 1;
+        stats.syllables += syllables;
+        if word.chars().count() > 6 {
+            stats.long_words += 1;
+        }
+        if syllables >= 3 {
+            stats.polysyllabic_words += 1;
+        } else if syllables == 1 {
+            stats.monosyllabic_words += 1;
+        }
+    }
+
+    stats
+}
+
+/// Estimate the number of syllables in a word by counting vowel groups
+pub fn count_syllables(word: &str) -> usize {
+    let lower = word.to_lowercase();
+    let mut count = 0;
+    let mut prev_was_vowel = false;
+
+    for ch in lower.chars() {
+        let is_vowel = matches!(
+            ch,
+            'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'ä' | 'ö' | 'ü' | 'à' | 'â' | 'é' | 'è' | 'ê'
+                | 'ë' | 'î' | 'ï' | 'ô' | 'ù' | 'û' | 'á' | 'í' | 'ó' | 'ú'
+        );
+        if is_vowel && !prev_was_vowel {
+            count += 1;
+        }
+        prev_was_vowel = is_vowel;
+    }
+
+    // A trailing silent "e" does not form its own syllable in English
+    if count > 1 && lower.ends_with('e') && !lower.ends_with("le") {
+        count -= 1;
+    }
+
+    count.max(1)
+}
+
+/// Compute the reading-level scores appropriate for `lang`
+///
+/// Returns an empty list for languages without a supported formula or for
+/// text without words.
+pub fn reading_level(text: &str, lang: Option<&str>) -> Vec<ReadingLevel> {
+    let stats = text_statistics(text, abbreviations_for(lang));
+    if stats.words == 0 {
+        return Vec::new();
+    }
+
+    let asl = stats.words_per_sentence();
+    let asw = stats.syllables_per_word();
+    let primary = lang
+        .and_then(|lang| lang.split(['-', '_']).next())
+        .map(|lang| lang.to_lowercase());
+
+    let scores = match primary.as_deref() {
+        Some("en") | None => vec![
+            (ReadingLevelFormula::FleschReadingEase, 206.835 - 1.015 * asl - 84.6 * asw),
+            (ReadingLevelFormula::FleschKincaidGrade, 0.39 * asl + 11.8 * asw - 15.59),
+        ],
+        Some("de") => vec![
+            (ReadingLevelFormula::Amstad, 180.0 - asl - 58.5 * asw),
+            (
+                ReadingLevelFormula::WienerSachtextformel,
+                0.1935 * stats.percent_of_words(stats.polysyllabic_words)
+                    + 0.1672 * asl
+                    + 0.1297 * stats.percent_of_words(stats.long_words)
+                    - 0.0327 * stats.percent_of_words(stats.monosyllabic_words)
+                    - 0.875,
+            ),
+        ],
+        Some("es") => vec![(
+            ReadingLevelFormula::FernandezHuerta,
+            206.84 - 0.60 * (asw * 100.0) - 1.02 * (100.0 / asl),
+        )],
+        Some("fr") => vec![(ReadingLevelFormula::KandelMoles, 207.0 - 1.015 * asl - 73.6 * asw)],
+        _ => Vec::new(),
+    };
+
+    scores
+        .into_iter()
+        .map(|(formula, score)| ReadingLevel { formula, score })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        let text = "The first sentence. The second one! Is this the third?";
+        let sentences = split_sentences(text, &[]);
+        assert_eq!(sentences.len(), 3);
+        assert_eq!(sentences[0].text, "The first sentence.");
+        assert_eq!(sentences[1].text, "The second one!");
+        assert_eq!(&text[sentences[2].range.clone()], "Is this the third?");
+    }
+
+    #[test]
+    fn test_split_sentences_with_abbreviations() {
+        let text = "Dr. Smith arrived. He was late.";
+        let sentences = split_sentences(text, ENGLISH_ABBREVIATIONS);
+        assert_eq!(sentences.len(), 2);
+        assert_eq!(sentences[0].text, "Dr. Smith arrived.");
+    }
+
+    #[test]
+    fn test_truncate_excerpt() {
+        let text = "Dr. Smith crossed the delta by boat. The river had flooded the fields for weeks, and the roads were gone.";
+        assert_eq!(truncate_excerpt(text, 0, None), text);
+        assert_eq!(truncate_excerpt(text, 60, Some("en")), "Dr. Smith crossed the delta by boat.");
+
+        // Too little of the limit in whole sentences: cut at a word instead
+        assert_eq!(
+            truncate_excerpt(text, 90, Some("en")),
+            "Dr. Smith crossed the delta by boat. The river had flooded the fields for weeks, and the…"
+        );
+        assert_eq!(truncate_excerpt(text, 20, None), "Dr. Smith crossed…");
+        assert_eq!(truncate_excerpt("One long clause, then another that runs on", 17, None), "One long clause…");
+        assert_eq!(truncate_excerpt("长江流经许多省份并最终流入东海", 8, Some("zh")), "长江流经许多省…");
+    }
+
+    #[test]
+    fn test_abbreviations_for() {
+        assert_eq!(abbreviations_for(Some("de-AT")), GERMAN_ABBREVIATIONS);
+        assert_eq!(abbreviations_for(None), ENGLISH_ABBREVIATIONS);
+        assert!(abbreviations_for(Some("ja")).is_empty());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let text = "Rust is a systems language. Rust focuses on safety and speed. \
+                    The weather was nice. Many developers enjoy Rust for its safety.";
+        let summary = summarize(text, Some("en"), 2).unwrap();
+        assert!(summary.starts_with("Rust is a systems language."));
+        assert!(!summary.contains("weather"));
+        assert_eq!(summarize(text, Some("en"), 0), None);
+    }
+
+    #[test]
+    fn test_count_syllables() {
+        assert_eq!(count_syllables("cat"), 1);
+        assert_eq!(count_syllables("readable"), 3);
+        assert_eq!(count_syllables("make"), 1);
+        assert_eq!(count_syllables("Wasser"), 2);
+    }
+
+    #[test]
+    fn test_reading_level_by_language() {
+        let simple = reading_level("The cat sat on the mat. The dog ran.", Some("en"));
+        assert_eq!(simple[0].formula, ReadingLevelFormula::FleschReadingEase);
+        assert!(simple[0].score > 90.0);
+
+        let german = reading_level("Der Hund läuft schnell. Die Katze schläft.", Some("de"));
+        assert_eq!(german[0].formula, ReadingLevelFormula::Amstad);
+        assert_eq!(german[1].formula, ReadingLevelFormula::WienerSachtextformel);
+
+        assert!(reading_level("日本語の文章です。", Some("ja")).is_empty());
+        assert!(reading_level("", Some("en")).is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_for_lang_cjk_bigrams() {
+        assert_eq!(tokenize_for_lang("日本語", Some("ja")), vec!["日本", "本語"]);
+        assert_eq!(tokenize_for_lang("Rust言語 guide", Some("ja")), vec!["rust", "言語", "guide"]);
+        assert_eq!(tokenize_for_lang("Hello World", Some("en")), vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_stop_words() {
+        assert!(is_stop_word("The", Some("en")));
+        assert!(is_stop_word("und", Some("de-DE")));
+        assert!(!is_stop_word("readability", Some("en")));
+
+        register_stop_words("xx", ["foo"]);
+        assert!(is_stop_word("FOO", Some("xx")));
+        assert_eq!(keywords_for_lang("the foo readability", Some("xx")), vec!["the", "readability"]);
+        assert_eq!(keywords_for_lang("the foo readability", Some("en")), vec!["foo", "readability"]);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("The river runs"), 4);
+        assert_eq!(estimate_tokens("Readability, extracted."), 8);
+        assert_eq!(estimate_tokens("这是中文"), 4);
+    }
+
+    #[test]
+    fn test_detect_language_from_script() {
+        assert_eq!(detect_language_from_script("これは日本語の文章です"), Some("ja"));
+        assert_eq!(detect_language_from_script("这是中文文章"), Some("zh"));
+        assert_eq!(detect_language_from_script("ภาษาไทย"), Some("th"));
+        assert_eq!(detect_language_from_script("Plain English text"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Borrowed view of an article's text fields
+//!
+//! Servers often take a few large fields of an [`Article`] (content, text,
+//! excerpt), serialize them and drop everything. [`ArticleRef`] holds those
+//! fields as [`Cow`]s, so they can be borrowed from an article, a cache or a
+//! request buffer and written out without copying the strings first.
+
+use std::borrow::Cow;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::Article;
+
+/// The text fields of an [`Article`], borrowed or owned
+///
+/// Serializes with the same field names as [`Article`], leaving out missing
+/// fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ArticleRef<'a> {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub title: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub content: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub text_content: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub length: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub excerpt: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub byline: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub dir: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub site_name: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub lang: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub published_time: Option<Cow<'a, str>>,
+}
+
+impl ArticleRef<'_> {
+    /// An [`Article`] with these fields, copying only the borrowed ones
+    pub fn into_owned(self) -> Article {
+        Article {
+            title: self.title.map(Cow::into_owned),
+            content: self.content.map(Cow::into_owned),
+            text_content: self.text_content.map(Cow::into_owned),
+            length: self.length,
+            excerpt: self.excerpt.map(Cow::into_owned),
+            byline: self.byline.map(Cow::into_owned),
+            dir: self.dir.map(Cow::into_owned),
+            site_name: self.site_name.map(Cow::into_owned),
+            lang: self.lang.map(Cow::into_owned),
+            published_time: self.published_time.map(Cow::into_owned),
+            ..Default::default()
+        }
+    }
+}
+
+impl Article {
+    /// Borrow the text fields, e.g. to serialize them without cloning
+    pub fn as_article_ref(&self) -> ArticleRef<'_> {
+        ArticleRef {
+            title: self.title.as_deref().map(Cow::Borrowed),
+            content: self.content.as_deref().map(Cow::Borrowed),
+            text_content: self.text_content.as_deref().map(Cow::Borrowed),
+            length: self.length,
+            excerpt: self.excerpt.as_deref().map(Cow::Borrowed),
+            byline: self.byline.as_deref().map(Cow::Borrowed),
+            dir: self.dir.as_deref().map(Cow::Borrowed),
+            site_name: self.site_name.as_deref().map(Cow::Borrowed),
+            lang: self.lang.as_deref().map(Cow::Borrowed),
+            published_time: self.published_time.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+/// Takes the text fields over without copying them, dropping the rest
+impl From<Article> for ArticleRef<'static> {
+    fn from(article: Article) -> Self {
+        ArticleRef {
+            title: article.title.map(Cow::Owned),
+            content: article.content.map(Cow::Owned),
+            text_content: article.text_content.map(Cow::Owned),
+            length: article.length,
+            excerpt: article.excerpt.map(Cow::Owned),
+            byline: article.byline.map(Cow::Owned),
+            dir: article.dir.map(Cow::Owned),
+            site_name: article.site_name.map(Cow::Owned),
+            lang: article.lang.map(Cow::Owned),
+            published_time: article.published_time.map(Cow::Owned),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Article {
+        Article {
+            title: Some("Rivers".to_string()),
+            content: Some("<p>Silt</p>".to_string()),
+            text_content: Some("Silt".to_string()),
+            length: Some(4),
+            byline: Some("Jane Doe".to_string()),
+            readerable: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_article_ref() {
+        let article = sample();
+        let view = article.as_article_ref();
+        assert!(matches!(view.content, Some(Cow::Borrowed(content)) if std::ptr::eq(content, article.content.as_deref().unwrap())));
+        assert_eq!(view.title.as_deref(), Some("Rivers"));
+
+        let owned = view.into_owned();
+        assert_eq!((owned.title, owned.content, owned.length), (article.title.clone(), article.content.clone(), Some(4)));
+        assert_eq!(owned.readerable, None);
+
+        let converted = ArticleRef::from(sample());
+        assert!(matches!(converted.text_content, Some(Cow::Owned(_))));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_article_ref_json() {
+        let article = sample();
+        let json = serde_json::to_value(article.as_article_ref()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "title": "Rivers",
+                "content": "<p>Silt</p>",
+                "text_content": "Silt",
+                "length": 4,
+                "byline": "Jane Doe",
+            })
+        );
+        assert_eq!(json["content"], serde_json::to_value(&article).unwrap()["content"]);
+    }
+}
@@ -0,0 +1,66 @@
+//! Non-blocking parsing for async runtimes
+//!
+//! Parsing is CPU-bound and large pages take hundreds of milliseconds, so
+//! calling [`Readability::parse`] inside an async handler (axum, actix-web)
+//! stalls every other task on that runtime thread. These functions run the
+//! parse on Tokio's blocking thread pool and await the result instead.
+//! Requires the `async` feature and a running Tokio runtime.
+
+use tokio::task;
+
+use crate::{Article, Readability, ReadabilityError, ReadabilityOptions};
+
+/// Parse `html` on the blocking thread pool
+///
+/// ```no_run
+/// # async fn handler(html: String) -> Option<String> {
+/// let article = readability_rust::asynch::parse(html, None).await.ok()?;
+/// article.title
+/// # }
+/// ```
+pub async fn parse(html: impl Into<String>, options: Option<ReadabilityOptions>) -> Result<Article, ReadabilityError> {
+    let html = html.into();
+    run(move || Readability::new(&html, options)?.parse_result()).await
+}
+
+/// Like [`parse`], resolving relative links against `base_uri`
+pub async fn parse_with_base_uri(
+    html: impl Into<String>,
+    base_uri: impl Into<String>,
+    options: Option<ReadabilityOptions>,
+) -> Result<Article, ReadabilityError> {
+    let (html, base_uri) = (html.into(), base_uri.into());
+    run(move || Readability::new_with_base_uri(&html, &base_uri, options)?.parse_result()).await
+}
+
+/// The parsed document isn't `Send`, so it is built inside the blocking task
+async fn run<F>(parse: F) -> Result<Article, ReadabilityError>
+where
+    F: FnOnce() -> Result<Article, ReadabilityError> + Send + 'static,
+{
+    match task::spawn_blocking(parse).await {
+        Ok(result) => result,
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(error) => Err(ReadabilityError::ParseError(format!("parse task failed: {}", error))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let html = r#"<html><head><title>Rivers</title></head><body><article>
+            <p>A long paragraph about <a href="/nile">rivers</a>, with commas, and enough text to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let article = tokio_test::block_on(parse(html, None)).unwrap();
+        assert_eq!(article.title.as_deref(), Some("Rivers"));
+
+        let article = tokio_test::block_on(parse_with_base_uri(html, "https://example.com/a/", None)).unwrap();
+        let expected = Readability::new_with_base_uri(html, "https://example.com/a/", None).unwrap().parse().unwrap();
+        assert_eq!(article.content, expected.content);
+    }
+}
@@ -0,0 +1,428 @@
+//! Structured block model for extracted article content
+//!
+//! The block model describes the cleaned article as an ordered list of
+//! headings, paragraphs, lists, quotes, code, images and tables so that
+//! consumers can render native views without parsing HTML themselves.
+
+use scraper::{ElementRef, Html};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::split_sentences;
+use crate::utils::{get_inner_text, has_child_block_element};
+
+/// A single structural block of article content
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum Block {
+    Heading { level: u8, text: String },
+    Paragraph { text: String },
+    List { ordered: bool, items: Vec<String> },
+    Quote { text: String },
+    Code { text: String },
+    Image { src: String, alt: Option<String> },
+    Table { rows: Vec<Vec<String>> },
+}
+
+/// A passage of article content with its markup, for passage-level
+/// indexing and chunking on real paragraph boundaries
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Paragraph {
+    /// Whitespace-normalized text
+    pub text: String,
+    /// Outer HTML of the source element
+    pub html: String,
+    pub is_heading: bool,
+    /// Heading level (1-6), for headings
+    pub level: Option<u8>,
+}
+
+impl Paragraph {
+    /// Split cleaned article HTML into its headings and paragraphs, with list
+    /// items, quotes, code and tables as passages of their own
+    pub fn from_content(html: &str) -> Vec<Paragraph> {
+        let fragment = Html::parse_fragment(html);
+        let mut paragraphs = Vec::new();
+        collect_paragraphs(&fragment.root_element(), &mut paragraphs);
+        paragraphs
+    }
+}
+
+/// A run of article text sized for retrieval pipelines, made of whole
+/// sentences
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Chunk {
+    /// Sentences of the chunk, with paragraphs separated by blank lines
+    pub text: String,
+    /// Heading of the section the chunk's new (non-overlapping) text starts in
+    pub heading: Option<String>,
+}
+
+/// A heading, or a sentence of a paragraph
+struct Piece<'a> {
+    text: &'a str,
+    starts_paragraph: bool,
+    is_heading: bool,
+    heading: Option<&'a str>,
+}
+
+/// Pack the sentences of `paragraphs` into chunks of at most `max_chars`
+///
+/// Headings stay with the start of their section, and each chunk repeats up
+/// to `overlap` characters of whole sentences from the end of the previous one
+/// within the same section. A sentence longer than `max_chars` makes a chunk
+/// of its own.
+pub(crate) fn chunk_paragraphs(paragraphs: &[Paragraph], max_chars: usize, overlap: usize, abbreviations: &[&str]) -> Vec<Chunk> {
+    let mut pieces = Vec::new();
+    let mut heading = None;
+    for paragraph in paragraphs {
+        if paragraph.is_heading {
+            heading = Some(paragraph.text.as_str());
+            pieces.push(Piece { text: &paragraph.text, starts_paragraph: true, is_heading: true, heading });
+            continue;
+        }
+        for (index, sentence) in split_sentences(&paragraph.text, abbreviations).into_iter().enumerate() {
+            pieces.push(Piece { text: sentence.text, starts_paragraph: index == 0, is_heading: false, heading });
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<&Piece> = Vec::new();
+    // Pieces of `current` not carried over from the previous chunk
+    let mut fresh = 0;
+    for (index, piece) in pieces.iter().enumerate() {
+        // A run of headings only fits together with the first sentence after it
+        let needed: usize = if piece.is_heading {
+            let end = pieces[index..].iter().position(|next| !next.is_heading).map_or(pieces.len(), |n| index + n + 1);
+            pieces[index..end].iter().map(|next| next.text.chars().count() + 2).sum()
+        } else {
+            piece.text.chars().count() + 1
+        };
+        let after_heading = current.last().is_some_and(|last| last.is_heading);
+        if fresh > 0 && !after_heading && join_pieces(&current).chars().count() + needed > max_chars {
+            chunks.push(make_chunk(&current, fresh));
+            let carried = if piece.is_heading { 0 } else { overlap_pieces(&current, overlap.min(max_chars.saturating_sub(needed))) };
+            current.drain(..current.len() - carried);
+            fresh = 0;
+        }
+        current.push(piece);
+        fresh += 1;
+    }
+    if fresh > 0 {
+        chunks.push(make_chunk(&current, fresh));
+    }
+    chunks
+}
+
+fn join_pieces(pieces: &[&Piece]) -> String {
+    let mut text = String::new();
+    for piece in pieces {
+        if !text.is_empty() {
+            text.push_str(if piece.starts_paragraph { "\n\n" } else { " " });
+        }
+        text.push_str(piece.text);
+    }
+    text
+}
+
+fn make_chunk(pieces: &[&Piece], fresh: usize) -> Chunk {
+    Chunk {
+        text: join_pieces(pieces),
+        heading: pieces[pieces.len() - fresh].heading.map(str::to_string),
+    }
+}
+
+/// Number of trailing sentences of `pieces` that fit in `budget` characters,
+/// stopping at a heading and always leaving the first piece behind
+fn overlap_pieces(pieces: &[&Piece], budget: usize) -> usize {
+    let mut used = 0;
+    let mut count = 0;
+    for piece in pieces.iter().skip(1).rev() {
+        used += piece.text.chars().count() + 1;
+        if piece.is_heading || used > budget {
+            break;
+        }
+        count += 1;
+    }
+    count
+}
+
+/// The ordered blocks making up an extracted article
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct ArticleBlocks {
+    pub blocks: Vec<Block>,
+}
+
+impl ArticleBlocks {
+    /// Build the block model from cleaned article HTML
+    pub fn from_html(html: &str) -> Self {
+        let fragment = Html::parse_fragment(html);
+        let mut blocks = Vec::new();
+        collect_blocks(&fragment.root_element(), &mut blocks);
+        Self { blocks }
+    }
+
+    /// Number of blocks
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Whether there are no blocks
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Iterate over the blocks in document order
+    pub fn iter(&self) -> std::slice::Iter<'_, Block> {
+        self.blocks.iter()
+    }
+
+    /// Plain text with blocks separated by blank lines, list items and
+    /// table rows on lines of their own, and code verbatim
+    pub fn to_text(&self) -> String {
+        let texts: Vec<String> = self.blocks.iter()
+            .filter_map(|block| match block {
+                Block::Heading { text, .. } | Block::Paragraph { text } | Block::Quote { text } => Some(text.clone()),
+                Block::List { items, .. } => Some(items.join("\n")),
+                Block::Code { text } => Some(text.trim_matches('\n').to_string()),
+                Block::Table { rows } => Some(rows.iter().map(|cells| cells.join("\t")).collect::<Vec<_>>().join("\n")),
+                Block::Image { .. } => None,
+            })
+            .collect();
+        texts.join("\n\n")
+    }
+}
+
+fn collect_blocks(element: &ElementRef, blocks: &mut Vec<Block>) {
+    for child in element.children() {
+        let Some(child_element) = ElementRef::wrap(child) else {
+            continue;
+        };
+
+        let tag_name = child_element.value().name().to_lowercase();
+        match tag_name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = get_inner_text(&child_element, true);
+                if !text.is_empty() {
+                    let level = tag_name[1..].parse().unwrap_or(1);
+                    blocks.push(Block::Heading { level, text });
+                }
+            }
+            "p" => {
+                let text = get_inner_text(&child_element, true);
+                if !text.is_empty() {
+                    blocks.push(Block::Paragraph { text });
+                } else {
+                    collect_images(&child_element, blocks);
+                }
+            }
+            "ul" | "ol" => {
+                let items: Vec<String> = child_element
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|li| li.value().name().eq_ignore_ascii_case("li"))
+                    .map(|li| get_inner_text(&li, true))
+                    .filter(|text| !text.is_empty())
+                    .collect();
+                if !items.is_empty() {
+                    blocks.push(Block::List { ordered: tag_name == "ol", items });
+                }
+            }
+            "blockquote" => {
+                let text = get_inner_text(&child_element, true);
+                if !text.is_empty() {
+                    blocks.push(Block::Quote { text });
+                }
+            }
+            "pre" => {
+                let text = child_element.text().collect::<String>();
+                if !text.trim().is_empty() {
+                    blocks.push(Block::Code { text });
+                }
+            }
+            "img" => collect_images(&child_element, blocks),
+            "table" => {
+                let rows = table_rows(&child_element);
+                if !rows.is_empty() {
+                    blocks.push(Block::Table { rows });
+                }
+            }
+            _ => {
+                if has_child_block_element(&child_element) {
+                    collect_blocks(&child_element, blocks);
+                } else {
+                    let text = get_inner_text(&child_element, true);
+                    if !text.is_empty() {
+                        blocks.push(Block::Paragraph { text });
+                    } else {
+                        collect_images(&child_element, blocks);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_paragraphs(element: &ElementRef, paragraphs: &mut Vec<Paragraph>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let tag_name = child.value().name().to_lowercase();
+        let level = match tag_name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => tag_name[1..].parse().ok(),
+            _ => None,
+        };
+        let is_leaf = match tag_name.as_str() {
+            "ul" | "ol" => false,
+            "p" | "pre" | "table" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => true,
+            _ => !has_child_block_element(&child),
+        };
+        if !is_leaf {
+            collect_paragraphs(&child, paragraphs);
+            continue;
+        }
+        let text = get_inner_text(&child, true);
+        if !text.is_empty() {
+            paragraphs.push(Paragraph { text, html: child.html(), is_heading: level.is_some(), level });
+        }
+    }
+}
+
+fn collect_images(element: &ElementRef, blocks: &mut Vec<Block>) {
+    let images = std::iter::once(*element)
+        .chain(element.descendants().filter_map(ElementRef::wrap))
+        .filter(|e| e.value().name().eq_ignore_ascii_case("img"));
+
+    for image in images {
+        if let Some(src) = image.value().attr("src") {
+            blocks.push(Block::Image {
+                src: src.to_string(),
+                alt: image.value().attr("alt").map(|alt| alt.to_string()),
+            });
+        }
+    }
+}
+
+fn table_rows(table: &ElementRef) -> Vec<Vec<String>> {
+    table
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name().eq_ignore_ascii_case("tr"))
+        .map(|row| {
+            row.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .map(|cell| get_inner_text(&cell, true))
+                .collect::<Vec<_>>()
+        })
+        .filter(|cells| !cells.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_from_html() {
+        let html = r#"
+            <div>
+                <h2>Section</h2>
+                <p>First paragraph.</p>
+                <ul><li>One</li><li>Two</li></ul>
+                <blockquote>Quoted text</blockquote>
+                <pre>let x = 1;</pre>
+                <p><img src="a.png" alt="An image"></p>
+            </div>
+        "#;
+
+        let blocks = ArticleBlocks::from_html(html);
+        assert_eq!(blocks.len(), 6);
+        assert_eq!(blocks.blocks[0], Block::Heading { level: 2, text: "Section".to_string() });
+        assert_eq!(blocks.blocks[1], Block::Paragraph { text: "First paragraph.".to_string() });
+        assert_eq!(
+            blocks.blocks[2],
+            Block::List { ordered: false, items: vec!["One".to_string(), "Two".to_string()] }
+        );
+        assert_eq!(blocks.blocks[4], Block::Code { text: "let x = 1;".to_string() });
+        assert_eq!(
+            blocks.blocks[5],
+            Block::Image { src: "a.png".to_string(), alt: Some("An image".to_string()) }
+        );
+    }
+
+    #[test]
+    fn test_paragraphs_from_content() {
+        let paragraphs = Paragraph::from_content(r#"
+            <h2>Rivers</h2>
+            <p>The <b>Nile</b> is long.</p>
+            <div><ul><li>Nile</li><li>Amazon</li></ul><blockquote><p>Quoted.</p></blockquote></div>
+        "#);
+        let texts: Vec<&str> = paragraphs.iter().map(|paragraph| paragraph.text.as_str()).collect();
+        assert_eq!(texts, vec!["Rivers", "The Nile is long.", "Nile", "Amazon", "Quoted."]);
+        assert_eq!(paragraphs[0].level, Some(2));
+        assert!(paragraphs[0].is_heading && !paragraphs[1].is_heading);
+        assert_eq!(paragraphs[1].html, "<p>The <b>Nile</b> is long.</p>");
+        assert_eq!(paragraphs[2].html, "<li>Nile</li>");
+    }
+
+    #[test]
+    fn test_chunk_paragraphs() {
+        let paragraphs = Paragraph::from_content(r#"
+            <p>Rivers are long. They flow to the sea.</p>
+            <h2>Nile</h2>
+            <p>The Nile is in Africa. It floods every year. Farmers rely on it.</p>
+        "#);
+        let texts = |max_chars, overlap| -> Vec<String> {
+            chunk_paragraphs(&paragraphs, max_chars, overlap, &[]).into_iter().map(|chunk| chunk.text).collect()
+        };
+
+        assert_eq!(texts(1000, 0).len(), 1);
+        assert_eq!(
+            texts(50, 0),
+            vec![
+                "Rivers are long. They flow to the sea.",
+                "Nile\n\nThe Nile is in Africa. It floods every year.",
+                "Farmers rely on it.",
+            ]
+        );
+        assert_eq!(texts(50, 25)[2], "It floods every year. Farmers rely on it.");
+        assert_eq!(texts(5, 0)[0], "Rivers are long.");
+
+        let chunks = chunk_paragraphs(&paragraphs, 50, 25, &[]);
+        assert_eq!(chunks[0].heading, None);
+        assert_eq!(chunks[2].heading.as_deref(), Some("Nile"));
+    }
+
+    #[test]
+    fn test_blocks_to_text() {
+        let blocks = ArticleBlocks::from_html(r#"
+            <h2>Section</h2>
+            <p>First   paragraph,
+               wrapped.</p>
+            <ol><li>One</li><li>Two</li></ol>
+            <pre>
+fn main() {
+    run();
+}
+</pre>
+            <p><img src="a.png"></p>
+            <table><tr><th>Name</th><th>Age</th></tr><tr><td>Ann</td><td>30</td></tr></table>
+        "#);
+        assert_eq!(
+            blocks.to_text(),
+            "Section\n\nFirst paragraph, wrapped.\n\nOne\nTwo\n\nfn main() {\n    run();\n}\n\nName\tAge\nAnn\t30"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_blocks_serialization() {
+        let blocks = ArticleBlocks::from_html("<p>Hello</p>");
+        let json = serde_json::to_string(&blocks).unwrap();
+        assert_eq!(json, r#"[{"type":"paragraph","text":"Hello"}]"#);
+    }
+}
@@ -0,0 +1,512 @@
+//! Reading-list bundles of several articles
+//!
+//! A [`ReadingList`] collects extracted articles and writes them either as a
+//! directory of reader pages with an `index.html` linking them, as a
+//! single EPUB 3 book with one chapter per article, or as an Atom feed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+use scraper::{ElementRef, Selector};
+
+use crate::dom;
+use crate::format::{html_escape, write_article, write_atom_feed, xhtml_fragment, OutputFormat};
+use crate::utils::{format_rfc3339, sha256_hex};
+use crate::Article;
+
+/// An article and where it came from
+#[derive(Debug, Clone)]
+pub struct ReadingListEntry {
+    /// URL or file path of the page
+    pub source: String,
+    pub article: Article,
+}
+
+impl ReadingListEntry {
+    /// The article title, or the source without one
+    pub fn title(&self) -> &str {
+        self.article.title.as_deref().filter(|title| !title.trim().is_empty()).unwrap_or(&self.source)
+    }
+}
+
+/// Articles bundled for reading later
+#[derive(Debug, Clone)]
+pub struct ReadingList {
+    pub title: String,
+    pub entries: Vec<ReadingListEntry>,
+    /// Recorded as the EPUB's modification time
+    pub created: SystemTime,
+}
+
+impl ReadingList {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), entries: Vec::new(), created: SystemTime::now() }
+    }
+
+    pub fn push(&mut self, source: impl Into<String>, article: Article) {
+        self.entries.push(ReadingListEntry { source: source.into(), article });
+    }
+
+    /// File name of the reader page of entry `index`, e.g. `001-river-deltas.html`
+    pub fn page_name(&self, index: usize) -> String {
+        format!("{:03}-{}.html", index + 1, slug(self.entries[index].title()))
+    }
+
+    /// Write one reader page per article and an `index.html` linking them into `dir`
+    pub fn write_html(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut index = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n    <meta charset=\"utf-8\">\n    \
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n    \
+             <title>{title}</title>\n</head>\n<body>\n    <h1>{title}</h1>\n    <ol>\n",
+            title = html_escape(&self.title)
+        );
+        for (position, entry) in self.entries.iter().enumerate() {
+            let page = self.page_name(position);
+            let mut writer = io::BufWriter::new(fs::File::create(dir.join(&page))?);
+            write_article(&mut writer, &entry.article, &OutputFormat::Html)?;
+            writer.flush()?;
+
+            index.push_str(&format!("        <li>\n            <a href=\"{}\">{}</a>\n", page, html_escape(entry.title())));
+            if let Some(byline) = &entry.article.byline {
+                index.push_str(&format!("            <span class=\"byline\">{}</span>\n", html_escape(byline)));
+            }
+            if let Some(excerpt) = &entry.article.excerpt {
+                index.push_str(&format!("            <p class=\"excerpt\">{}</p>\n", html_escape(excerpt)));
+            }
+            index.push_str(&format!(
+                "            <p class=\"source\"><a href=\"{source}\">{source}</a></p>\n        </li>\n",
+                source = html_escape(&entry.source)
+            ));
+        }
+        index.push_str("    </ol>\n</body>\n</html>\n");
+        fs::write(dir.join("index.html"), index)
+    }
+
+    /// Write the articles as an Atom feed, one entry each, linked to their sources
+    pub fn write_atom<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let entries: Vec<(Option<&str>, &Article)> =
+            self.entries.iter().map(|entry| (Some(entry.source.as_str()), &entry.article)).collect();
+        write_atom_feed(writer, &self.title, &entries, self.created)
+    }
+
+    /// Write the articles as an EPUB 3 book, one chapter each, returning the writer
+    ///
+    /// Images with a relative `src` are read from next to their source file
+    /// and stored in the book. Remote images, and images that can't be read,
+    /// are left out, so the book reads offline.
+    pub fn write_epub<W: Write>(&self, writer: W) -> io::Result<W> {
+        let language = self.entries.iter().find_map(|entry| entry.article.lang.clone()).unwrap_or_else(|| "en".to_string());
+        let mut zip = ZipWriter::new(writer);
+        // The mimetype must come first, uncompressed
+        zip.add("mimetype", b"application/epub+zip")?;
+        zip.add(
+            "META-INF/container.xml",
+            br#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#,
+        )?;
+
+        let mut manifest = String::from(
+            "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n",
+        );
+        let mut spine = String::new();
+        let mut toc = String::new();
+        for (position, entry) in self.entries.iter().enumerate() {
+            let (id, file) = (format!("chapter-{:03}", position + 1), format!("chapter-{:03}.xhtml", position + 1));
+            let (content, images) = embed_images(entry.article.content.as_deref().unwrap_or(""), &entry.source, position + 1);
+            for image in images {
+                manifest.push_str(&format!(
+                    "    <item id=\"{}\" href=\"{}\" media-type=\"{}\"/>\n",
+                    image.href.replace(['/', '.'], "-"), image.href, image.media_type
+                ));
+                zip.add(&format!("OEBPS/{}", image.href), &image.data)?;
+            }
+            let content = xhtml_fragment(&content);
+            let remote = if content.contains("src=\"http") { " properties=\"remote-resources\"" } else { "" };
+            manifest.push_str(&format!(
+                "    <item id=\"{}\" href=\"{}\" media-type=\"application/xhtml+xml\"{}/>\n",
+                id, file, remote
+            ));
+            spine.push_str(&format!("    <itemref idref=\"{}\"/>\n", id));
+            toc.push_str(&format!("      <li><a href=\"{}\">{}</a></li>\n", file, xml_escape(entry.title())));
+            zip.add(&format!("OEBPS/{}", file), chapter(entry, &content, &language).as_bytes())?;
+        }
+
+        let sources: Vec<&str> = self.entries.iter().map(|entry| entry.source.as_str()).collect();
+        let package = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">urn:sha256:{id}</dc:identifier>
+    <dc:title>{title}</dc:title>
+    <dc:language>{language}</dc:language>
+    <meta property="dcterms:modified">{modified}</meta>
+  </metadata>
+  <manifest>
+{manifest}  </manifest>
+  <spine>
+{spine}  </spine>
+</package>
+"#,
+            id = sha256_hex(&sources.join("\n")),
+            title = xml_escape(&self.title),
+            language = xml_escape(&language),
+            modified = format_rfc3339(self.created),
+            manifest = manifest,
+            spine = spine,
+        );
+        zip.add("OEBPS/content.opf", package.as_bytes())?;
+
+        let nav = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{language}">
+<head><title>{title}</title></head>
+<body>
+  <nav epub:type="toc">
+    <h1>{title}</h1>
+    <ol>
+{toc}    </ol>
+  </nav>
+</body>
+</html>
+"#,
+            language = xml_escape(&language),
+            title = xml_escape(&self.title),
+            toc = toc,
+        );
+        zip.add("OEBPS/nav.xhtml", nav.as_bytes())?;
+        zip.finish()
+    }
+}
+
+/// An image stored in the book, at `href` relative to the package
+struct BookImage {
+    href: String,
+    media_type: &'static str,
+    data: Vec<u8>,
+}
+
+/// `content` with its images pointed at copies stored in the book, as
+/// `images/{chapter}-{n}.{ext}`, and the images to store
+///
+/// Relative sources are read from the directory of a file `source`; remote
+/// images and the ones that can't be read are removed.
+fn embed_images(content: &str, source: &str, chapter: usize) -> (String, Vec<BookImage>) {
+    let (mut fragment, root) = dom::parse_content(content);
+    let base = (!source.contains("://")).then(|| Path::new(source).parent()).flatten();
+    let img_selector = Selector::parse("img").unwrap();
+
+    let mut images: Vec<BookImage> = Vec::new();
+    let mut stored: HashMap<String, String> = HashMap::new();
+    for id in dom::select_ids(&fragment, &img_selector) {
+        let Some(src) = fragment.tree.get(id).and_then(ElementRef::wrap).map(|img| img.value().attr("src").unwrap_or("").trim().to_string()) else {
+            continue;
+        };
+        dom::remove_attr(&mut fragment, id, "srcset");
+        if src.starts_with("data:") {
+            continue;
+        }
+
+        let path = src.split(['?', '#']).next().unwrap_or("").to_string();
+        let href = match stored.get(&path) {
+            Some(href) => Some(href.clone()),
+            None => read_image(base, &path).map(|(media_type, extension, data)| {
+                let href = format!("images/{:03}-{}.{}", chapter, images.len() + 1, extension);
+                images.push(BookImage { href: href.clone(), media_type, data });
+                stored.insert(path, href.clone());
+                href
+            }),
+        };
+        match href {
+            Some(href) => dom::set_attr(&mut fragment, id, "src", &href),
+            None => dom::remove(&mut fragment, &[id]),
+        }
+    }
+
+    let content = fragment.tree.get(root).and_then(ElementRef::wrap).map(|root| root.inner_html()).unwrap_or_default();
+    (content, images)
+}
+
+/// The media type, extension and bytes of the image at relative `path` under `base`
+fn read_image(base: Option<&Path>, path: &str) -> Option<(&'static str, &'static str, Vec<u8>)> {
+    if path.is_empty() || path.starts_with('/') || path.contains(':') {
+        return None;
+    }
+    let (media_type, extension) = match path.rsplit('.').next()?.to_ascii_lowercase().as_str() {
+        "jpg" | "jpeg" => ("image/jpeg", "jpg"),
+        "png" => ("image/png", "png"),
+        "gif" => ("image/gif", "gif"),
+        "webp" => ("image/webp", "webp"),
+        "svg" => ("image/svg+xml", "svg"),
+        _ => return None,
+    };
+    let data = fs::read(base?.join(path)).ok()?;
+    Some((media_type, extension, data))
+}
+
+/// The XHTML chapter of one article
+fn chapter(entry: &ReadingListEntry, content: &str, language: &str) -> String {
+    let article = &entry.article;
+    let language = article.lang.as_deref().unwrap_or(language);
+    let dir = article.dir.as_deref().map(|dir| format!(" dir=\"{}\"", xml_escape(dir))).unwrap_or_default();
+    let byline = article
+        .byline
+        .as_deref()
+        .map(|byline| format!("<p class=\"byline\">By {}</p>\n", xml_escape(byline)))
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xml:lang="{language}"{dir}>
+<head><title>{title}</title></head>
+<body>
+<h1>{title}</h1>
+{byline}<p class="source"><a href="{source}">{source}</a></p>
+{content}
+</body>
+</html>
+"#,
+        language = xml_escape(language),
+        dir = dir,
+        title = xml_escape(entry.title()),
+        byline = byline,
+        source = xml_escape(&entry.source),
+        content = content,
+    )
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Lowercase ASCII words of `title` joined by hyphens, for file names
+fn slug(title: &str) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+    let mut slug = String::new();
+    for word in words {
+        if slug.len() + word.len() > 60 {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    if slug.is_empty() {
+        slug.push_str("article");
+    }
+    slug
+}
+
+/// A ZIP archive of uncompressed entries, as EPUB containers allow
+struct ZipWriter<W: Write> {
+    writer: W,
+    offset: u32,
+    /// (name, CRC-32, size, offset of the local header)
+    entries: Vec<(String, u32, u32, u32)>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    fn new(writer: W) -> Self {
+        Self { writer, offset: 0, entries: Vec::new() }
+    }
+
+    fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let size = u32::try_from(data.len()).map_err(|_| too_large("entry too large"))?;
+        let crc = crc32(data);
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        Self::push_entry_fields(&mut header, crc, size, name);
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        header.extend_from_slice(name.as_bytes());
+        // Offsets are 32-bit without ZIP64
+        let next_offset = u32::try_from(header.len())
+            .ok()
+            .and_then(|header_size| self.offset.checked_add(header_size))
+            .and_then(|offset| offset.checked_add(size))
+            .ok_or_else(|| too_large("archive too large"))?;
+        self.writer.write_all(&header)?;
+        self.writer.write_all(data)?;
+        self.entries.push((name.to_string(), crc, size, self.offset));
+        self.offset = next_offset;
+        Ok(())
+    }
+
+    /// Write the central directory and return the writer
+    fn finish(mut self) -> io::Result<W> {
+        let mut directory = Vec::new();
+        for (name, crc, size, offset) in &self.entries {
+            directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            Self::push_entry_fields(&mut directory, *crc, *size, name);
+            directory.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            directory.extend_from_slice(&offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+        let count = u16::try_from(self.entries.len()).map_err(|_| too_large("too many entries"))?;
+        let directory_size = u32::try_from(directory.len()).map_err(|_| too_large("archive too large"))?;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        end.extend_from_slice(&[0; 4]); // disk numbers
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&directory_size.to_le_bytes());
+        end.extend_from_slice(&self.offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.writer.write_all(&directory)?;
+        self.writer.write_all(&end)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Fields shared by local and central headers, up to the name length
+    fn push_entry_fields(buffer: &mut Vec<u8>, crc: u32, size: u32, name: &str) {
+        buffer.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        buffer.extend_from_slice(&0u16.to_le_bytes()); // flags
+        buffer.extend_from_slice(&0u16.to_le_bytes()); // stored
+        buffer.extend_from_slice(&0u16.to_le_bytes()); // time
+        buffer.extend_from_slice(&0x21u16.to_le_bytes()); // date: 1980-01-01
+        buffer.extend_from_slice(&crc.to_le_bytes());
+        buffer.extend_from_slice(&size.to_le_bytes());
+        buffer.extend_from_slice(&size.to_le_bytes());
+        buffer.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    }
+}
+
+fn too_large(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading_list() -> ReadingList {
+        let mut list = ReadingList::new("Tabs & Notes");
+        list.push("https://example.com/rivers", Article {
+            title: Some("River Deltas: A Primer".to_string()),
+            byline: Some("Jane Doe".to_string()),
+            content: Some(r#"<p>Deltas &amp; <b>estuaries</b><br>differ.<img src="https://example.com/a.png"></p><!-- note -->"#.to_string()),
+            lang: Some("en".to_string()),
+            ..Default::default()
+        });
+        list.push("notes/untitled.html", Article { content: Some("<p>Untitled</p>".to_string()), ..Default::default() });
+        list
+    }
+
+    #[test]
+    fn test_slug_and_crc32() {
+        assert_eq!(slug("River Deltas: A Primer"), "river-deltas-a-primer");
+        assert_eq!(slug("!!!"), "article");
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_write_html() {
+        let dir = std::env::temp_dir().join(format!("readability-bundle-{}", std::process::id()));
+        let list = reading_list();
+        list.write_html(&dir).unwrap();
+        let index = fs::read_to_string(dir.join("index.html")).unwrap();
+        let page = fs::read_to_string(dir.join("001-river-deltas-a-primer.html")).unwrap();
+        let untitled = dir.join("002-notes-untitled-html.html").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(index.contains("<title>Tabs &amp; Notes</title>"));
+        assert!(index.contains(r#"<a href="001-river-deltas-a-primer.html">River Deltas: A Primer</a>"#));
+        assert!(index.contains(r#"<span class="byline">Jane Doe</span>"#));
+        assert!(page.contains("<h1>River Deltas: A Primer</h1>"));
+        assert!(untitled);
+    }
+
+    #[test]
+    fn test_write_atom() {
+        let mut feed = Vec::new();
+        reading_list().write_atom(&mut feed).unwrap();
+        let feed = String::from_utf8(feed).unwrap();
+        assert!(feed.contains("<title>Tabs &amp; Notes</title>"));
+        assert!(feed.contains(r#"<link rel="alternate" href="https://example.com/rivers"/>"#));
+        assert!(feed.contains("<title>notes/untitled.html</title>"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+    }
+
+    #[test]
+    fn test_write_epub() {
+        let epub = reading_list().write_epub(Vec::new()).unwrap();
+        assert_eq!(&epub[..4], b"PK\x03\x04");
+        assert_eq!(&epub[30..38], b"mimetype");
+        assert_eq!(&epub[38..58], b"application/epub+zip");
+
+        let text = String::from_utf8_lossy(&epub);
+        for name in ["META-INF/container.xml", "OEBPS/nav.xhtml", "OEBPS/chapter-002.xhtml"] {
+            assert_eq!(text.matches(name).count(), 2, "{} in a local and a central header", name);
+        }
+        assert!(text.contains(r#"<rootfile full-path="OEBPS/content.opf""#));
+        assert!(text.contains(r#"<item id="chapter-001" href="chapter-001.xhtml" media-type="application/xhtml+xml"/>"#));
+        assert!(text.contains("<dc:title>Tabs &amp; Notes</dc:title>"));
+        // The remote image is left out
+        assert!(text.contains("Deltas &amp; <b>estuaries</b><br/>differ.</p>"));
+        assert!(text.contains(r#"<li><a href="chapter-002.xhtml">notes/untitled.html</a></li>"#));
+        assert!(!text.contains("note -->"));
+        // End of central directory: two chapters plus four other entries
+        let end = &epub[epub.len() - 22..];
+        assert_eq!(&end[..4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 6);
+    }
+
+    #[test]
+    fn test_write_epub_images() {
+        let dir = std::env::temp_dir().join(format!("readability-bundle-images-{}", std::process::id()));
+        fs::create_dir_all(dir.join("img")).unwrap();
+        fs::write(dir.join("img/river.png"), b"PNG river").unwrap();
+        let mut list = ReadingList::new("Maps");
+        list.push(dir.join("rivers.html").to_string_lossy(), Article {
+            content: Some(concat!(
+                r#"<p>Map<img src="img/river.png" srcset="img/river-2x.png 2x"><img src="img/river.png?v=2"></p>"#,
+                r#"<p><img src="missing.png"><img src="https://example.com/b.png"></p>"#,
+            ).to_string()),
+            ..Default::default()
+        });
+        let epub = list.write_epub(Vec::new()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let text = String::from_utf8_lossy(&epub);
+        assert_eq!(text.matches("OEBPS/images/001-1.png").count(), 2);
+        assert!(text.contains("PNG river"));
+        assert!(text.contains(r#"<item id="images-001-1-png" href="images/001-1.png" media-type="image/png"/>"#));
+        // One copy, whatever the query string
+        assert!(text.contains(r#"<p>Map<img src="images/001-1.png"/><img src="images/001-1.png"/></p>"#));
+        assert!(!text.contains("srcset") && !text.contains("missing.png") && !text.contains("example.com/b.png"));
+        assert!(!text.contains("remote-resources"));
+    }
+
+    #[test]
+    fn test_zip_offset_overflow() {
+        let mut zip = ZipWriter { writer: io::sink(), offset: u32::MAX - 40, entries: Vec::new() };
+        assert_eq!(zip.add("mimetype", b"application/epub+zip").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(zip.entries.is_empty());
+    }
+}
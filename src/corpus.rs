@@ -0,0 +1,197 @@
+//! Parity statistics over a corpus of test pages
+//!
+//! Runs extraction over a directory in the Mozilla test-page layout, one
+//! directory per case holding `source.html`, `expected.html` and
+//! `expected-metadata.json`, and compares the results with the expected
+//! output. Requires the `corpus` feature.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use scraper::Html;
+
+use crate::utils::{get_inner_text, normalize_whitespace};
+use crate::{Article, Readability, ReadabilityOptions};
+
+/// Base URI the Mozilla test pages are extracted with
+pub const TEST_PAGE_URI: &str = "http://fakehost/test/page.html";
+
+/// Metadata fields compared against `expected-metadata.json`
+pub const METADATA_FIELDS: &[&str] = &["title", "byline", "excerpt", "siteName", "lang", "dir", "publishedTime"];
+
+/// Outcome of one test page
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult {
+    /// Directory name of the case
+    pub name: String,
+    /// Whether an article was extracted
+    pub extracted: bool,
+    /// Word-overlap similarity (0.0–1.0) of the extracted and expected text
+    pub text_similarity: f64,
+    /// For each metadata field, whether it matched the expected value
+    pub fields: BTreeMap<&'static str, bool>,
+}
+
+/// Aggregate results of a corpus run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorpusStats {
+    /// Per-case results, in directory name order
+    pub cases: Vec<CaseResult>,
+}
+
+impl CorpusStats {
+    /// Fraction of cases an article was extracted from
+    pub fn success_rate(&self) -> f64 {
+        self.fraction(|case| case.extracted)
+    }
+
+    /// Mean text similarity over all cases; failed extractions count as 0
+    pub fn mean_text_similarity(&self) -> f64 {
+        if self.cases.is_empty() {
+            return 0.0;
+        }
+        self.cases.iter().map(|case| case.text_similarity).sum::<f64>() / self.cases.len() as f64
+    }
+
+    /// Fraction of cases whose `field` (e.g. `"title"`) matched
+    pub fn field_accuracy(&self, field: &str) -> f64 {
+        self.fraction(|case| case.fields.get(field).copied().unwrap_or(false))
+    }
+
+    /// Names of the cases no article was extracted from
+    pub fn failures(&self) -> Vec<&str> {
+        self.cases.iter().filter(|case| !case.extracted).map(|case| case.name.as_str()).collect()
+    }
+
+    fn fraction(&self, predicate: impl Fn(&CaseResult) -> bool) -> f64 {
+        if self.cases.is_empty() {
+            return 0.0;
+        }
+        self.cases.iter().filter(|case| predicate(case)).count() as f64 / self.cases.len() as f64
+    }
+}
+
+/// Extract every case under `dir` with `options` and compare with the expected output
+///
+/// Directories missing any of the three case files are skipped.
+pub fn run_corpus(dir: &Path, options: &ReadabilityOptions) -> io::Result<CorpusStats> {
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .flatten()
+        .filter(|entry| entry.file_type().map(|file_type| file_type.is_dir()).unwrap_or(false))
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect();
+    names.sort();
+
+    let mut stats = CorpusStats::default();
+    for name in names {
+        let case_dir = dir.join(&name);
+        let (Ok(source), Ok(expected_html), Ok(metadata)) = (
+            fs::read_to_string(case_dir.join("source.html")),
+            fs::read_to_string(case_dir.join("expected.html")),
+            fs::read_to_string(case_dir.join("expected-metadata.json")),
+        ) else {
+            continue;
+        };
+        let metadata: serde_json::Value = serde_json::from_str(&metadata)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", name, e)))?;
+        stats.cases.push(run_case(name, &source, &expected_html, &metadata, options));
+    }
+    Ok(stats)
+}
+
+fn run_case(
+    name: String,
+    source: &str,
+    expected_html: &str,
+    metadata: &serde_json::Value,
+    options: &ReadabilityOptions,
+) -> CaseResult {
+    let article = Readability::new_with_base_uri(source, TEST_PAGE_URI, Some(options.clone()))
+        .ok()
+        .and_then(|mut parser| parser.parse());
+    let Some(article) = article else {
+        return CaseResult { name, extracted: false, text_similarity: 0.0, fields: BTreeMap::new() };
+    };
+
+    let expected_text = get_inner_text(&Html::parse_fragment(expected_html).root_element(), true);
+    let text_similarity = text_similarity(article.text_content.as_deref().unwrap_or(""), &expected_text);
+    let fields = METADATA_FIELDS
+        .iter()
+        .map(|&field| {
+            let normalize = |value: &str| normalize_whitespace(value).trim().to_string();
+            let expected = metadata[field].as_str().map(normalize);
+            (field, field_value(&article, field).map(normalize) == expected)
+        })
+        .collect();
+    CaseResult { name, extracted: true, text_similarity, fields }
+}
+
+fn field_value<'a>(article: &'a Article, field: &str) -> Option<&'a str> {
+    match field {
+        "title" => article.title.as_deref(),
+        "byline" => article.byline.as_deref(),
+        "excerpt" => article.excerpt.as_deref(),
+        "siteName" => article.site_name.as_deref(),
+        "lang" => article.lang.as_deref(),
+        "dir" => article.dir.as_deref(),
+        "publishedTime" => article.published_time.as_deref(),
+        _ => None,
+    }
+}
+
+/// Dice coefficient of the lowercase word multisets of `a` and `b`
+pub fn text_similarity(a: &str, b: &str) -> f64 {
+    let count = |text: &str| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for word in text.split_whitespace() {
+            *counts.entry(word.to_lowercase()).or_default() += 1;
+        }
+        counts
+    };
+    let (a, b) = (count(a), count(b));
+    let total: usize = a.values().sum::<usize>() + b.values().sum::<usize>();
+    if total == 0 {
+        return 1.0;
+    }
+    let common: usize = a.iter().map(|(word, n)| (*n).min(b.get(word).copied().unwrap_or(0))).sum();
+    2.0 * common as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_similarity() {
+        assert_eq!(text_similarity("The river runs", "the river runs"), 1.0);
+        assert_eq!(text_similarity("a b", "c d"), 0.0);
+        assert_eq!(text_similarity("a b c d", "a b"), 2.0 * 2.0 / 6.0);
+    }
+
+    #[test]
+    fn test_run_corpus() {
+        let dir = std::env::temp_dir().join(format!("readability-corpus-{}", std::process::id()));
+        let case = dir.join("001");
+        fs::create_dir_all(&case).unwrap();
+        fs::create_dir_all(dir.join("incomplete")).unwrap();
+        let paragraph = "<p>A long paragraph about rivers, with commas, and enough text to pass.</p>";
+        fs::write(case.join("source.html"), format!(
+            "<html lang=\"en\"><head><title>Rivers</title></head><body><article>{}</article></body></html>", paragraph
+        )).unwrap();
+        fs::write(case.join("expected.html"), paragraph).unwrap();
+        fs::write(case.join("expected-metadata.json"), r#"{"title": "Rivers", "byline": "Jane Doe", "lang": "en"}"#).unwrap();
+
+        let stats = run_corpus(&dir, &ReadabilityOptions::default()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(stats.cases.len(), 1);
+        assert_eq!(stats.success_rate(), 1.0);
+        assert_eq!(stats.mean_text_similarity(), 1.0);
+        assert_eq!(stats.field_accuracy("title"), 1.0);
+        assert_eq!(stats.field_accuracy("lang"), 1.0);
+        assert_eq!(stats.field_accuracy("byline"), 0.0);
+        assert!(stats.failures().is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! In-place edits of the parsed document
+//!
+//! `scraper::Html` keeps its nodes in an `ego_tree` arena that can be edited
+//! through `Html::tree`. These helpers make the edits the preparation passes
+//! need, so they remove and replace nodes the way Readability.js does on its
+//! DOM instead of leaving the work to string cleanup of the serialized HTML.
+
+use ego_tree::NodeId;
+use scraper::node::Element;
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::utils::is_phrasing_content;
+
+/// A new element without attributes, e.g. to wrap nodes into a `<p>`
+pub(crate) fn new_element(tag: &str) -> Element {
+    let fragment = Html::parse_fragment(&format!("<{}></{}>", tag, tag));
+    let selector = Selector::parse(tag).unwrap();
+    fragment.select(&selector).next().unwrap().value().clone()
+}
+
+/// Parse content HTML as the children of a `<template>`, where table parts
+/// such as a stray `<td>` survive; returns the document and the template's
+/// id, the root of the content
+pub(crate) fn parse_content(html: &str) -> (Html, NodeId) {
+    let fragment = Html::parse_fragment(&format!("<template>{}</template>", html));
+    let root = fragment.select(&Selector::parse("template").unwrap()).next().map(|template| template.id());
+    let root = root.unwrap_or_else(|| fragment.root_element().id());
+    (fragment, root)
+}
+
+/// Ids of the elements matching `selector`, in document order
+pub(crate) fn select_ids(document: &Html, selector: &Selector) -> Vec<NodeId> {
+    document.select(selector).map(|element| element.id()).collect()
+}
+
+/// Detach `ids` with their subtrees; ids inside already removed subtrees are fine
+pub(crate) fn remove(document: &mut Html, ids: &[NodeId]) {
+    for &id in ids {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// Move `ids` into a new `tag` element, placed where the first of them was;
+/// returns the new element's id
+pub(crate) fn wrap(document: &mut Html, ids: &[NodeId], tag: &str) -> NodeId {
+    let wrapper = document.tree.orphan(Node::Element(new_element(tag))).id();
+    if let Some(mut first) = ids.first().and_then(|&id| document.tree.get_mut(id)) {
+        first.insert_id_before(wrapper);
+    }
+    if let Some(mut node) = document.tree.get_mut(wrapper) {
+        for &id in ids {
+            node.append_id(id);
+        }
+    }
+    wrapper
+}
+
+/// Replace element `id` with its children
+pub(crate) fn unwrap(document: &mut Html, id: NodeId) {
+    let Some(node) = document.tree.get(id).filter(|node| node.parent().is_some()) else {
+        return;
+    };
+    let children: Vec<NodeId> = node.children().map(|child| child.id()).collect();
+    if let Some(mut node) = document.tree.get_mut(id) {
+        for child in children {
+            node.insert_id_before(child);
+        }
+        node.detach();
+    }
+}
+
+/// Change the tag of element `id`, keeping its attributes and children
+pub(crate) fn rename(document: &mut Html, id: NodeId, tag: &str) {
+    let name = new_element(tag).name;
+    if let Some(mut node) = document.tree.get_mut(id) {
+        if let Node::Element(element) = node.value() {
+            element.name = name;
+        }
+    }
+}
+
+/// Set attribute `name` of element `id` to `value`
+pub(crate) fn set_attr(document: &mut Html, id: NodeId, name: &str, value: &str) {
+    // Attribute names are only built by the parser
+    let template = Html::parse_fragment(&format!("<b {}></b>", name));
+    let b_selector = Selector::parse("b").unwrap();
+    let Some(key) = template.select(&b_selector).next().and_then(|b| b.value().attrs.keys().next().cloned()) else {
+        return;
+    };
+    if let Some(mut node) = document.tree.get_mut(id) {
+        if let Node::Element(element) = node.value() {
+            element.attrs.insert(key, value.into());
+        }
+    }
+}
+
+/// Remove attribute `name` of element `id`, if it has one
+pub(crate) fn remove_attr(document: &mut Html, id: NodeId, name: &str) {
+    if let Some(mut node) = document.tree.get_mut(id) {
+        if let Node::Element(element) = node.value() {
+            element.attrs.retain(|key, _| &*key.local != name);
+        }
+    }
+}
+
+/// Remove every attribute of element `id`
+pub(crate) fn clear_attrs(document: &mut Html, id: NodeId) {
+    if let Some(mut node) = document.tree.get_mut(id) {
+        if let Node::Element(element) = node.value() {
+            element.attrs.clear();
+        }
+    }
+}
+
+/// Whether `id` is an element named `tag`
+fn is_tag(document: &Html, id: NodeId, tag: &str) -> bool {
+    document.tree.get(id).and_then(|node| node.value().as_element()).is_some_and(|element| element.name() == tag)
+}
+
+/// Whether `id` is a text node of only whitespace
+fn is_blank_text(document: &Html, id: NodeId) -> bool {
+    document.tree.get(id).and_then(|node| node.value().as_text()).is_some_and(|text| text.trim().is_empty())
+}
+
+/// `id` or the first following sibling that is not blank text
+fn next_significant(document: &Html, id: Option<NodeId>) -> Option<NodeId> {
+    let mut next = id;
+    while let Some(id) = next {
+        if !is_blank_text(document, id) {
+            return Some(id);
+        }
+        next = document.tree.get(id).and_then(|node| node.next_sibling()).map(|node| node.id());
+    }
+    None
+}
+
+fn next_sibling(document: &Html, id: NodeId) -> Option<NodeId> {
+    document.tree.get(id).and_then(|node| node.next_sibling()).map(|node| node.id())
+}
+
+/// Replace each chain of two or more `<br>`s with a `<p>` holding the
+/// phrasing content that follows it, up to the next chain, as
+/// Readability.js's `_replaceBrs` does; returns how many were replaced
+///
+/// Whitespace between the `<br>`s of a chain is ignored.
+pub(crate) fn replace_br_chains(document: &mut Html) -> usize {
+    let br_selector = Selector::parse("body br").unwrap();
+    let brs = select_ids(document, &br_selector);
+    let mut replaced = 0;
+    for br in brs {
+        // Removed as part of an earlier chain
+        if document.tree.get(br).and_then(|node| node.parent()).is_none() {
+            continue;
+        }
+        let mut chain = false;
+        let mut next = next_significant(document, next_sibling(document, br));
+        while let Some(id) = next.filter(|&id| is_tag(document, id, "br")) {
+            chain = true;
+            let after = next_sibling(document, id);
+            remove(document, &[id]);
+            next = next_significant(document, after);
+        }
+        if !chain {
+            continue;
+        }
+        replaced += 1;
+
+        // The first <br> of the chain becomes the paragraph
+        rename(document, br, "p");
+        let mut next = next_sibling(document, br);
+        while let Some(id) = next {
+            if is_tag(document, id, "br") {
+                let following = next_significant(document, next_sibling(document, id));
+                if following.is_some_and(|following| is_tag(document, following, "br")) {
+                    break;
+                }
+            }
+            let is_phrasing = match document.tree.get(id).map(|node| node.value()) {
+                Some(Node::Text(_)) => true,
+                Some(Node::Element(element)) => is_phrasing_content(element.name()) || matches!(element.name(), "a" | "del" | "ins"),
+                _ => false,
+            };
+            if !is_phrasing {
+                break;
+            }
+            next = next_sibling(document, id);
+            if let Some(mut paragraph) = document.tree.get_mut(br) {
+                paragraph.append_id(id);
+            }
+        }
+        // Trailing whitespace of the paragraph is dropped
+        while let Some(last) = document.tree.get(br).and_then(|node| node.last_child()).map(|node| node.id()) {
+            if !is_blank_text(document, last) {
+                break;
+            }
+            remove(document, &[last]);
+        }
+        // Paragraphs can't nest
+        if let Some(parent) = document.tree.get(br).and_then(|node| node.parent()).map(|node| node.id()) {
+            if is_tag(document, parent, "p") {
+                rename(document, parent, "div");
+            }
+        }
+    }
+    replaced
+}
+
+/// Ids of the paragraphs without text or embedded media
+pub(crate) fn empty_paragraphs(document: &Html) -> Vec<NodeId> {
+    let paragraph_selector = Selector::parse("p").unwrap();
+    let media_selector = Selector::parse("img, embed, object, iframe").unwrap();
+    document
+        .select(&paragraph_selector)
+        .filter(|paragraph: &ElementRef| {
+            paragraph.text().all(|text| text.trim().is_empty()) && paragraph.select(&media_selector).next().is_none()
+        })
+        .map(|paragraph| paragraph.id())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body_html(document: &Html) -> String {
+        document.select(&Selector::parse("body").unwrap()).next().unwrap().inner_html()
+    }
+
+    #[test]
+    fn test_replace_br_chains() {
+        let mut document = Html::parse_document("<body><div>First line<br>still first<br> <br>Second <b>part</b><br><br><div>Block</div></div></body>");
+        assert_eq!(replace_br_chains(&mut document), 2);
+        assert_eq!(
+            body_html(&document),
+            "<div>First line<br>still first<p> Second <b>part</b></p><p></p><div>Block</div></div>"
+        );
+
+        let mut nested = Html::parse_document("<body><p>One<br><br>Two</p></body>");
+        replace_br_chains(&mut nested);
+        assert_eq!(body_html(&nested), "<div>One<p>Two</p></div>");
+    }
+
+    #[test]
+    fn test_edits() {
+        let mut document = Html::parse_document(r#"<body><font color="red">Red</font><p> </p><p><img src="a.png"></p></body>"#);
+        let fonts = select_ids(&document, &Selector::parse("font").unwrap());
+        rename(&mut document, fonts[0], "span");
+        let empty = empty_paragraphs(&document);
+        assert_eq!(empty.len(), 1);
+        remove(&mut document, &empty);
+        assert_eq!(body_html(&document), r#"<span color="red">Red</span><p><img src="a.png"></p>"#);
+
+        let paragraphs = select_ids(&document, &Selector::parse("span, p").unwrap());
+        wrap(&mut document, &paragraphs, "div");
+        assert_eq!(body_html(&document), r#"<div><span color="red">Red</span><p><img src="a.png"></p></div>"#);
+
+        set_attr(&mut document, paragraphs[1], "data-kind", "photo");
+        assert!(body_html(&document).contains(r#"<p data-kind="photo">"#));
+
+        let wrapper = select_ids(&document, &Selector::parse("div").unwrap());
+        unwrap(&mut document, wrapper[0]);
+        assert_eq!(body_html(&document), r#"<span color="red">Red</span><p data-kind="photo"><img src="a.png"></p>"#);
+    }
+}
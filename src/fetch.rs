@@ -0,0 +1,673 @@
+//! Fetching pages over HTTP before extraction
+//!
+//! A [`Fetcher`] spaces out requests to the same host and retries rate-limited
+//! (429) and failed (5xx) responses with exponential backoff, honoring
+//! `Retry-After`, as set by its [`FetchPolicy`]. It can also obey each
+//! host's robots.txt. Requests go through a [`Transport`], by default
+//! [`UreqTransport`]. Requires the `fetch` feature.
+//!
+//! [`Fetcher::fetch_if_modified`] revalidates a page fetched before with its
+//! ETag or Last-Modified value, and stamps the article with provenance for
+//! archiving. Dead pages can fall back to their latest Wayback Machine capture.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+use crate::robots::RobotsRules;
+use crate::utils::format_rfc3339;
+use crate::{Article, Readability, ReadabilityError, ReadabilityOptions};
+
+/// The Wayback Machine's availability API, which finds the latest capture of a URL
+const WAYBACK_AVAILABILITY_API: &str = "https://archive.org/wayback/available";
+
+/// Errors from fetching a page
+#[derive(Error, Debug)]
+pub enum FetchError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("Request failed: {0}")]
+    Transport(String),
+    #[error("HTTP status {0}")]
+    Status(u16),
+    #[error("Disallowed by robots.txt: {0}")]
+    RobotsDisallowed(String),
+    #[error(transparent)]
+    Readability(#[from] ReadabilityError),
+}
+
+/// How politely a [`Fetcher`] treats hosts
+#[derive(Debug, Clone)]
+pub struct FetchPolicy {
+    /// Shortest time between two requests to the same host
+    pub min_host_interval: Duration,
+    /// Retries after a 429 or 5xx response or a failed connection
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub initial_backoff: Duration,
+    /// Longest wait before a retry; a longer `Retry-After` gives up instead
+    pub max_backoff: Duration,
+    /// Timeout of a single request
+    pub timeout: Duration,
+    /// Sent with every request; its product token (before any `/`) selects
+    /// the robots.txt group
+    pub user_agent: String,
+    /// Refuse URLs that the host's robots.txt disallows
+    pub respect_robots_txt: bool,
+    /// Extract pages that are gone (404, 410) or unreachable from their latest
+    /// Wayback Machine capture instead
+    pub fallback_wayback: bool,
+}
+
+impl Default for FetchPolicy {
+    fn default() -> Self {
+        Self {
+            min_host_interval: Duration::from_secs(1),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(60),
+            timeout: Duration::from_secs(30),
+            user_agent: format!("readability-rust/{}", env!("CARGO_PKG_VERSION")),
+            respect_robots_txt: false,
+            fallback_wayback: false,
+        }
+    }
+}
+
+/// A response as returned by a [`Transport`], whatever its status
+#[derive(Debug, Clone, Default)]
+pub struct HttpResponse {
+    /// Final URL, after redirects
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Value of the first header named `name`, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Cache validators of a fetched page, sent back to ask whether it changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// A Wayback Machine capture that stood in for a dead page
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WaybackSnapshot {
+    pub url: String,
+    /// Capture time as `YYYYMMDDhhmmss` (UTC)
+    pub timestamp: String,
+}
+
+/// An article with the details of the response it was extracted from
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FetchedArticle {
+    pub article: Article,
+    /// Final URL, after redirects; the requested URL for a Wayback capture
+    pub url: String,
+    pub status: u16,
+    /// When the response arrived, in RFC 3339 (UTC)
+    pub fetched_at: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// The capture the article was extracted from, with
+    /// [`FetchPolicy::fallback_wayback`]
+    pub wayback_snapshot: Option<WaybackSnapshot>,
+}
+
+impl FetchedArticle {
+    /// Validators for revalidating this page later
+    pub fn validators(&self) -> Validators {
+        Validators { etag: self.etag.clone(), last_modified: self.last_modified.clone() }
+    }
+}
+
+/// Result of a conditional fetch
+#[derive(Debug, Clone)]
+pub enum FetchOutcome {
+    /// The page is new or changed
+    Modified(Box<FetchedArticle>),
+    /// The server answered 304: the page is unchanged since the validators
+    NotModified,
+}
+
+/// Performs HTTP GET requests for a [`Fetcher`]
+///
+/// Implement it to route requests through another client, a proxy or a cache.
+pub trait Transport: Send + Sync {
+    /// GET `url` with extra request `headers`; error statuses are responses,
+    /// not errors
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError>;
+}
+
+/// The default [`Transport`], built on `ureq`
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    pub fn new(policy: &FetchPolicy) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(policy.timeout)
+            .user_agent(&policy.user_agent)
+            .build();
+        Self { agent }
+    }
+}
+
+impl Transport for UreqTransport {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        let response = match request.call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(error)) => return Err(FetchError::Transport(error.to_string())),
+        };
+
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = response.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+        let (url, status) = (response.get_url().to_string(), response.status());
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|error| FetchError::Transport(error.to_string()))?;
+        Ok(HttpResponse { url, status, headers, body })
+    }
+}
+
+/// Fetches pages according to a [`FetchPolicy`]
+///
+/// Share one fetcher between threads so that per-host spacing applies
+/// across all of them.
+pub struct Fetcher {
+    policy: FetchPolicy,
+    transport: Box<dyn Transport>,
+    /// Earliest time of the next request to each host
+    next_request: Mutex<HashMap<String, Instant>>,
+    /// robots.txt rules of each origin fetched from so far
+    robots: Mutex<HashMap<String, Arc<RobotsRules>>>,
+}
+
+impl Fetcher {
+    pub fn new(policy: FetchPolicy) -> Self {
+        let transport = UreqTransport::new(&policy);
+        Self::with_transport(policy, transport)
+    }
+
+    pub fn with_transport(policy: FetchPolicy, transport: impl Transport + 'static) -> Self {
+        Self {
+            policy,
+            transport: Box::new(transport),
+            next_request: Mutex::new(HashMap::new()),
+            robots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn policy(&self) -> &FetchPolicy {
+        &self.policy
+    }
+
+    /// Fetch `url`, waiting for its host's turn and retrying as the policy allows
+    ///
+    /// With [`FetchPolicy::respect_robots_txt`], the host's robots.txt is
+    /// fetched once and disallowed URLs fail with
+    /// [`FetchError::RobotsDisallowed`].
+    pub fn fetch(&self, url: &str) -> Result<HttpResponse, FetchError> {
+        self.fetch_with_headers(url, &[])
+    }
+
+    /// Fetch `url` and extract its article, resolving links against the final URL
+    pub fn fetch_and_parse(&self, url: &str, options: Option<ReadabilityOptions>) -> Result<Article, FetchError> {
+        match self.fetch_if_modified(url, &Validators::default(), options)? {
+            FetchOutcome::Modified(fetched) => Ok(fetched.article),
+            // Only answered to requests with validators
+            FetchOutcome::NotModified => Err(FetchError::Status(304)),
+        }
+    }
+
+    /// Fetch `url` unless it is unchanged since `validators` were taken
+    ///
+    /// Sends `If-None-Match` and `If-Modified-Since` for the known
+    /// validators; with none, the page is always fetched.
+    pub fn fetch_if_modified(
+        &self,
+        url: &str,
+        validators: &Validators,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<FetchOutcome, FetchError> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &validators.etag {
+            headers.push(("If-None-Match", etag.as_str()));
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            headers.push(("If-Modified-Since", last_modified.as_str()));
+        }
+        let (response, wayback_snapshot) = match self.fetch_with_headers(url, &headers) {
+            Err(error @ (FetchError::Status(404 | 410) | FetchError::Transport(_))) if self.policy.fallback_wayback => {
+                // The lookup failing too is reported as the original failure
+                match self.wayback_snapshot(url) {
+                    Ok(Some((snapshot, response))) => (response, Some(snapshot)),
+                    _ => return Err(error),
+                }
+            }
+            result => (result?, None),
+        };
+        if response.status == 304 {
+            return Ok(FetchOutcome::NotModified);
+        }
+
+        let fetched_at = format_rfc3339(SystemTime::now());
+        // A capture's links resolve against the page it captured
+        let base_uri = if wayback_snapshot.is_some() { url } else { response.url.as_str() };
+        let mut parser = Readability::from_bytes_with_base_uri(&response.body, base_uri, options)?;
+        let article = parser.parse_result()?;
+        let (etag, last_modified) = match wayback_snapshot {
+            Some(_) => (None, None),
+            None => (
+                response.header("etag").map(str::to_string),
+                response.header("last-modified").map(str::to_string),
+            ),
+        };
+        Ok(FetchOutcome::Modified(Box::new(FetchedArticle {
+            article,
+            url: if wayback_snapshot.is_some() { url.to_string() } else { response.url },
+            status: response.status,
+            fetched_at,
+            etag,
+            last_modified,
+            wayback_snapshot,
+        })))
+    }
+
+    /// The latest Wayback Machine capture of `url` and its response, if any
+    ///
+    /// Requests the capture with the `id_` flag, which serves the page as
+    /// captured rather than wrapped in the Wayback toolbar.
+    fn wayback_snapshot(&self, url: &str) -> Result<Option<(WaybackSnapshot, HttpResponse)>, FetchError> {
+        let lookup = Url::parse_with_params(WAYBACK_AVAILABILITY_API, &[("url", url)])
+            .map_err(|_| FetchError::InvalidUrl(url.to_string()))?;
+        let response = self.request(lookup.as_str(), &[])?;
+        let availability: serde_json::Value =
+            serde_json::from_slice(&response.body).map_err(|error| FetchError::Transport(error.to_string()))?;
+        let closest = &availability["archived_snapshots"]["closest"];
+        let (Some(true), Some(snapshot_url), Some(timestamp)) =
+            (closest["available"].as_bool(), closest["url"].as_str(), closest["timestamp"].as_str())
+        else {
+            return Ok(None);
+        };
+
+        let raw_url = snapshot_url.replacen(&format!("/{}/", timestamp), &format!("/{}id_/", timestamp), 1);
+        let response = self.request(&raw_url, &[])?;
+        let snapshot = WaybackSnapshot { url: snapshot_url.to_string(), timestamp: timestamp.to_string() };
+        Ok(Some((snapshot, response)))
+    }
+
+    pub(crate) fn fetch_with_headers(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+        if self.policy.respect_robots_txt && !self.robots_allow(url)? {
+            return Err(FetchError::RobotsDisallowed(url.to_string()));
+        }
+        self.request(url, headers)
+    }
+
+    /// Whether the robots.txt of `url`'s origin allows fetching it
+    ///
+    /// A missing robots.txt (4xx) allows everything; one that can't be
+    /// fetched (5xx, network errors) disallows everything, as RFC 9309 asks.
+    fn robots_allow(&self, url: &str) -> Result<bool, FetchError> {
+        let parsed = Url::parse(url).map_err(|_| FetchError::InvalidUrl(url.to_string()))?;
+        if parsed.path() == "/robots.txt" {
+            return Ok(true);
+        }
+        let origin = parsed.origin().ascii_serialization();
+        let cached = self.robots.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(&origin).cloned();
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let agent = self.policy.user_agent.split('/').next().unwrap_or("").trim();
+                let rules = match self.request(&format!("{}/robots.txt", origin), &[]) {
+                    Ok(response) => RobotsRules::parse(&String::from_utf8_lossy(&response.body), agent),
+                    Err(FetchError::Status(status)) if (400..500).contains(&status) => RobotsRules::default(),
+                    Err(_) => RobotsRules::disallow_all(),
+                };
+                let rules = Arc::new(rules);
+                self.robots.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(origin, rules.clone());
+                rules
+            }
+        };
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        Ok(rules.is_allowed(&path))
+    }
+
+    /// GET `url`, retrying 429, 5xx and failed connections
+    fn request(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+        let host = host_of(url)?;
+        let mut attempt = 0;
+        loop {
+            self.wait_for_host(&host);
+            let result = self.transport.get(url, headers);
+            let retryable = match &result {
+                Ok(response) => response.status == 429 || response.status >= 500,
+                Err(FetchError::Transport(_)) => true,
+                Err(_) => false,
+            };
+            if !retryable || attempt >= self.policy.max_retries {
+                return match result? {
+                    response if response.status >= 400 => Err(FetchError::Status(response.status)),
+                    response => Ok(response),
+                };
+            }
+
+            let backoff = self.policy.initial_backoff.saturating_mul(2u32.saturating_pow(attempt));
+            let retry_after = result.as_ref().ok().and_then(|response| Some((response.status, response.header("retry-after")?)));
+            let delay = match retry_after {
+                Some((status, retry_after)) => match parse_retry_after(retry_after, SystemTime::now()) {
+                    // Asked to stay away for longer than we are willing to wait
+                    Some(delay) if delay > self.policy.max_backoff => return Err(FetchError::Status(status)),
+                    Some(delay) => delay,
+                    None => backoff,
+                },
+                None => backoff,
+            };
+            thread::sleep(delay.min(self.policy.max_backoff));
+            attempt += 1;
+        }
+    }
+
+    /// Sleep until `host` may be requested again, reserving the next slot
+    fn wait_for_host(&self, host: &str) {
+        let now = Instant::now();
+        let start = {
+            let mut next_request = self.next_request.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let start = next_request.get(host).copied().filter(|&next| next > now).unwrap_or(now);
+            next_request.insert(host.to_string(), start + self.policy.min_host_interval);
+            start
+        };
+        thread::sleep(start.saturating_duration_since(now));
+    }
+}
+
+/// Fetch `url` and extract its article with a process-wide [`Fetcher`]
+/// using the default [`FetchPolicy`]
+pub fn fetch_and_parse(url: &str, options: Option<ReadabilityOptions>) -> Result<Article, FetchError> {
+    static FETCHER: OnceLock<Fetcher> = OnceLock::new();
+    FETCHER.get_or_init(|| Fetcher::new(FetchPolicy::default())).fetch_and_parse(url, options)
+}
+
+fn host_of(url: &str) -> Result<String, FetchError> {
+    let parsed = Url::parse(url).map_err(|_| FetchError::InvalidUrl(url.to_string()))?;
+    match parsed.host_str() {
+        Some(host) if matches!(parsed.scheme(), "http" | "https") => Ok(host.to_lowercase()),
+        _ => Err(FetchError::InvalidUrl(url.to_string())),
+    }
+}
+
+/// Delay requested by a `Retry-After` value: seconds, or an HTTP date
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = parse_http_date(value)?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+/// Parse an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [_, day, month, year, time, "GMT"] = parts[..] else {
+        return None;
+    };
+    let day: i64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|name| *name == month)? as i64 + 1;
+    let year: i64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (clock.next()??, clock.next()??, clock.next()??);
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(seconds).ok()?))
+}
+
+/// Days since the epoch of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+
+    type Headers = Vec<(String, String)>;
+
+    /// Replays canned responses and records when each request was made
+    #[derive(Clone, Default)]
+    struct MockTransport {
+        responses: Arc<Mutex<VecDeque<Result<HttpResponse, FetchError>>>>,
+        requests: Arc<Mutex<Vec<(String, Instant)>>>,
+        headers: Arc<Mutex<Vec<Headers>>>,
+    }
+
+    impl MockTransport {
+        fn push(&self, status: u16, headers: &[(&str, &str)], body: &str) {
+            let headers = headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+            let response = HttpResponse { url: String::new(), status, headers, body: body.as_bytes().to_vec() };
+            self.responses.lock().unwrap().push_back(Ok(response));
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+            self.requests.lock().unwrap().push((url.to_string(), Instant::now()));
+            let headers = headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+            self.headers.lock().unwrap().push(headers);
+            let mut response = self.responses.lock().unwrap().pop_front().expect("unexpected request")?;
+            response.url = url.to_string();
+            Ok(response)
+        }
+    }
+
+    fn policy() -> FetchPolicy {
+        FetchPolicy {
+            min_host_interval: Duration::ZERO,
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retries_with_backoff() {
+        let transport = MockTransport::default();
+        transport.push(503, &[], "");
+        transport.push(429, &[("Retry-After", "0")], "");
+        transport.push(200, &[], "<html><body><p>Fetched</p></body></html>");
+        let fetcher = Fetcher::with_transport(policy(), transport.clone());
+
+        let response = fetcher.fetch("https://example.com/a").unwrap();
+        assert_eq!(response.body, b"<html><body><p>Fetched</p></body></html>");
+        assert_eq!(transport.requests.lock().unwrap().len(), 3);
+
+        transport.push(500, &[], "");
+        transport.push(500, &[], "");
+        let fetcher = Fetcher::with_transport(FetchPolicy { max_retries: 1, ..policy() }, transport.clone());
+        assert!(matches!(fetcher.fetch("https://example.com/a"), Err(FetchError::Status(500))));
+
+        transport.push(429, &[("Retry-After", "3600")], "");
+        assert!(matches!(fetcher.fetch("https://example.com/a"), Err(FetchError::Status(429))));
+        transport.push(503, &[("Retry-After", "3600")], "");
+        assert!(matches!(fetcher.fetch("https://example.com/a"), Err(FetchError::Status(503))));
+        transport.push(404, &[], "");
+        assert!(matches!(fetcher.fetch("https://example.com/a"), Err(FetchError::Status(404))));
+        assert!(matches!(fetcher.fetch("ftp://example.com/a"), Err(FetchError::InvalidUrl(_))));
+    }
+
+    #[test]
+    fn test_per_host_interval() {
+        let transport = MockTransport::default();
+        for _ in 0..3 {
+            transport.push(200, &[], "");
+        }
+        let interval = Duration::from_millis(50);
+        let fetcher = Fetcher::with_transport(FetchPolicy { min_host_interval: interval, ..policy() }, transport.clone());
+        for url in ["https://example.com/a", "https://other.org/", "https://EXAMPLE.com/b"] {
+            fetcher.fetch(url).unwrap();
+        }
+
+        let requests = transport.requests.lock().unwrap();
+        assert!(requests[2].1 - requests[0].1 >= interval);
+        assert!(requests[1].1 - requests[0].1 < interval);
+    }
+
+    #[test]
+    fn test_fetch_and_parse() {
+        let transport = MockTransport::default();
+        transport.push(503, &[("Retry-After", "0")], "");
+        transport.push(200, &[], r#"<html><head><title>Rivers</title></head><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass the threshold.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass the threshold.</p>
+        </article></body></html>"#);
+        let fetcher = Fetcher::with_transport(policy(), transport);
+        let article = fetcher.fetch_and_parse("https://example.com/rivers", None).unwrap();
+        assert_eq!(article.title.as_deref(), Some("Rivers"));
+    }
+
+    #[test]
+    fn test_fetch_if_modified() {
+        let html = r#"<html><head><title>Rivers</title></head><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass the threshold.</p>
+        </article></body></html>"#;
+        let transport = MockTransport::default();
+        transport.push(200, &[("ETag", "\"v1\""), ("Last-Modified", "Sun, 06 Nov 1994 08:49:37 GMT")], html);
+        transport.push(304, &[], "");
+        let fetcher = Fetcher::with_transport(policy(), transport.clone());
+
+        let FetchOutcome::Modified(fetched) =
+            fetcher.fetch_if_modified("https://example.com/rivers", &Validators::default(), None).unwrap()
+        else {
+            panic!("expected a fetched article");
+        };
+        assert_eq!(fetched.article.title.as_deref(), Some("Rivers"));
+        assert_eq!((fetched.status, fetched.url.as_str()), (200, "https://example.com/rivers"));
+        assert_eq!(fetched.etag.as_deref(), Some("\"v1\""));
+        assert!(fetched.fetched_at.ends_with('Z') && fetched.fetched_at.len() == 20);
+
+        let outcome = fetcher.fetch_if_modified("https://example.com/rivers", &fetched.validators(), None).unwrap();
+        assert!(matches!(outcome, FetchOutcome::NotModified));
+        let headers = transport.headers.lock().unwrap();
+        assert!(headers[0].is_empty());
+        assert_eq!(
+            headers[1],
+            vec![
+                ("If-None-Match".to_string(), "\"v1\"".to_string()),
+                ("If-Modified-Since".to_string(), "Sun, 06 Nov 1994 08:49:37 GMT".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fallback_wayback() {
+        let transport = MockTransport::default();
+        transport.push(404, &[], "");
+        transport.push(200, &[], r#"{"archived_snapshots": {"closest": {"available": true, "status": "200",
+            "url": "http://web.archive.org/web/20200102030405/https://example.com/gone",
+            "timestamp": "20200102030405"}}}"#);
+        transport.push(200, &[("ETag", "\"archive\"")], r#"<html><head><title>Gone</title></head><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass the threshold.</p>
+        </article></body></html>"#);
+        let policy = FetchPolicy { fallback_wayback: true, ..policy() };
+        let fetcher = Fetcher::with_transport(policy.clone(), transport.clone());
+
+        let outcome = fetcher.fetch_if_modified("https://example.com/gone", &Validators::default(), None).unwrap();
+        let FetchOutcome::Modified(fetched) = outcome else {
+            panic!("expected a fetched article");
+        };
+        assert_eq!(fetched.article.title.as_deref(), Some("Gone"));
+        assert_eq!(fetched.url, "https://example.com/gone");
+        assert_eq!(fetched.etag, None);
+        let snapshot = fetched.wayback_snapshot.unwrap();
+        assert_eq!(snapshot.timestamp, "20200102030405");
+        let urls: Vec<String> = transport.requests.lock().unwrap().iter().map(|(url, _)| url.clone()).collect();
+        assert_eq!(urls[1], "https://archive.org/wayback/available?url=https%3A%2F%2Fexample.com%2Fgone");
+        assert_eq!(urls[2], "http://web.archive.org/web/20200102030405id_/https://example.com/gone");
+
+        // Without a capture, the original failure stands
+        transport.push(404, &[], "");
+        transport.push(200, &[], r#"{"archived_snapshots": {}}"#);
+        assert!(matches!(fetcher.fetch_and_parse("https://example.com/never", None), Err(FetchError::Status(404))));
+        transport.push(500, &[], "");
+        let fetcher = Fetcher::with_transport(FetchPolicy { max_retries: 0, ..policy }, transport.clone());
+        assert!(matches!(fetcher.fetch_and_parse("https://example.com/broken", None), Err(FetchError::Status(500))));
+    }
+
+    #[test]
+    fn test_robots_txt() {
+        let transport = MockTransport::default();
+        transport.push(200, &[], "User-agent: *\nDisallow: /private\n");
+        transport.push(200, &[], "");
+        let fetcher = Fetcher::with_transport(FetchPolicy { respect_robots_txt: true, ..policy() }, transport.clone());
+
+        fetcher.fetch("https://example.com/public").unwrap();
+        let disallowed = fetcher.fetch("https://example.com/private?id=1");
+        assert!(matches!(disallowed, Err(FetchError::RobotsDisallowed(url)) if url == "https://example.com/private?id=1"));
+        // robots.txt is fetched once per origin
+        let urls: Vec<String> = transport.requests.lock().unwrap().iter().map(|(url, _)| url.clone()).collect();
+        assert_eq!(urls, vec!["https://example.com/robots.txt", "https://example.com/public"]);
+
+        transport.push(404, &[], "");
+        transport.push(200, &[], "");
+        fetcher.fetch("https://other.org/private").unwrap();
+        transport.push(503, &[], "");
+        let fetcher = Fetcher::with_transport(
+            FetchPolicy { respect_robots_txt: true, max_retries: 0, ..policy() },
+            transport.clone(),
+        );
+        assert!(matches!(fetcher.fetch("https://down.net/"), Err(FetchError::RobotsDisallowed(_))));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_777); // Sun, 06 Nov 1994 08:49:37 GMT
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:51:37 GMT", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
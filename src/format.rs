@@ -0,0 +1,663 @@
+//! Rendering of extracted articles, shared with the `readability-rust` CLI
+//!
+//! Server applications can use these to produce output identical to the
+//! CLI's. The JSON formats require the `json` feature.
+
+use crate::utils::{format_rfc3339, isolate_ltr_runs, sha256_hex};
+#[cfg(feature = "json")]
+use crate::ArticleBlocks;
+use crate::Article;
+use scraper::{Html, Node};
+use std::io::{self, Write};
+use std::time::SystemTime;
+
+pub use crate::utils::escape_html as html_escape;
+
+/// Elements without content, self-closed in XHTML
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Output formats supported by the CLI
+#[derive(Debug, Clone)]
+pub enum OutputFormat {
+    #[cfg(feature = "json")]
+    Json,
+    Text,
+    Html,
+    #[cfg(feature = "json")]
+    BlocksJson,
+    /// An Atom feed with the article as its only entry
+    Atom,
+    /// An AMP page; valid AMP links its canonical (non-AMP) URL
+    Amp { canonical_url: Option<String> },
+}
+
+impl From<&str> for OutputFormat {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "text" => OutputFormat::Text,
+            "html" => OutputFormat::Html,
+            "atom" => OutputFormat::Atom,
+            "amp" => OutputFormat::Amp { canonical_url: None },
+            #[cfg(feature = "json")]
+            "blocks-json" => OutputFormat::BlocksJson,
+            #[cfg(feature = "json")]
+            _ => OutputFormat::Json, // Default
+            #[cfg(not(feature = "json"))]
+            _ => OutputFormat::Text,
+        }
+    }
+}
+
+/// Render an article in the given output format
+pub fn format_output(
+    article: &Article,
+    format: &OutputFormat,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    write_article(&mut output, article, format)?;
+    Ok(String::from_utf8(output)?)
+}
+
+/// Stream an article in the given output format to `writer`
+///
+/// Produces the same bytes as `format_output` without building the whole
+/// output in memory first, so pipes start receiving data immediately.
+pub fn write_article<W: Write>(writer: &mut W, article: &Article, format: &OutputFormat) -> io::Result<()> {
+    match format {
+        #[cfg(feature = "json")]
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, article)?;
+        }
+        OutputFormat::Text => {
+            // Isolate left-to-right fragments so right-to-left text keeps its order
+            let is_rtl = article.dir.as_deref() == Some("rtl");
+            let bidi = |text: &str| if is_rtl { isolate_ltr_runs(text) } else { text.to_string() };
+
+            if let Some(title) = &article.title {
+                write!(writer, "Title: {}\n\n", bidi(title))?;
+            }
+
+            if let Some(byline) = &article.byline {
+                write!(writer, "By: {}\n\n", bidi(byline))?;
+            }
+
+            if let Some(text_content) = &article.text_content {
+                writer.write_all(bidi(text_content).as_bytes())?;
+            }
+        }
+        OutputFormat::Html => {
+            writer.write_all(b"<!DOCTYPE html>\n<html>\n<head>\n")?;
+
+            if let Some(title) = &article.title {
+                writeln!(writer, "    <title>{}</title>", html_escape(title))?;
+            }
+
+            writer.write_all(b"    <meta charset=\"utf-8\">\n")?;
+            writer.write_all(b"    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n")?;
+            writer.write_all(b"</head>\n<body>\n")?;
+
+            if let Some(title) = &article.title {
+                writeln!(writer, "    <h1>{}</h1>", html_escape(title))?;
+            }
+
+            if let Some(byline) = &article.byline {
+                writeln!(writer, "    <p class=\"byline\">By {}</p>", html_escape(byline))?;
+            }
+
+            if let Some(content) = &article.content {
+                writer.write_all(b"    <div class=\"content\">\n")?;
+                writer.write_all(content.as_bytes())?;
+                writer.write_all(b"\n    </div>\n")?;
+            }
+
+            writer.write_all(b"</body>\n</html>\n")?;
+        }
+        #[cfg(feature = "json")]
+        OutputFormat::BlocksJson => {
+            let blocks = article.blocks.clone().unwrap_or_else(|| {
+                ArticleBlocks::from_html(article.content.as_deref().unwrap_or(""))
+            });
+            serde_json::to_writer_pretty(&mut *writer, &serde_json::json!({
+                "title": article.title,
+                "byline": article.byline,
+                "dir": article.dir,
+                "lang": article.lang,
+                "blocks": blocks,
+            }))?;
+        }
+        OutputFormat::Atom => {
+            let title = article.title.as_deref().unwrap_or("Untitled");
+            write_atom_feed(writer, title, &[(None, article)], SystemTime::now())?;
+        }
+        OutputFormat::Amp { canonical_url } => {
+            write_amp(writer, article, canonical_url.as_deref())?;
+        }
+    }
+    Ok(())
+}
+
+/// Write an Atom feed of `entries`, each an article with its link when known
+///
+/// Entries are dated by their `published_time`, falling back to `updated`,
+/// the feed's own timestamp. The content is included as escaped HTML.
+pub fn write_atom_feed<W: Write>(
+    writer: &mut W,
+    title: &str,
+    entries: &[(Option<&str>, &Article)],
+    updated: SystemTime,
+) -> io::Result<()> {
+    let updated = format_rfc3339(updated);
+    let ids: Vec<String> = entries.iter().map(|(link, article)| atom_id(*link, article)).collect();
+    writer.write_all(b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n")?;
+    writeln!(writer, "  <id>urn:sha256:{}</id>", sha256_hex(&ids.join("\n")))?;
+    writeln!(writer, "  <title>{}</title>", html_escape(title))?;
+    writeln!(writer, "  <updated>{}</updated>", updated)?;
+    // Atom requires an author for every entry; the feed's stands in for missing bylines
+    if entries.iter().any(|(_, article)| article.byline.is_none()) {
+        writeln!(writer, "  <author><name>{}</name></author>", html_escape(title))?;
+    }
+    writer.write_all(b"  <generator>readability-rust</generator>\n")?;
+
+    for ((link, article), id) in entries.iter().zip(&ids) {
+        let published = article.published_time.as_deref().and_then(atom_date);
+        writer.write_all(b"  <entry>\n")?;
+        writeln!(writer, "    <id>{}</id>", html_escape(id))?;
+        writeln!(writer, "    <title>{}</title>", html_escape(article.title.as_deref().or(*link).unwrap_or("Untitled")))?;
+        if let Some(link) = link {
+            writeln!(writer, "    <link rel=\"alternate\" href=\"{}\"/>", html_escape(link))?;
+        }
+        writeln!(writer, "    <updated>{}</updated>", published.as_deref().unwrap_or(&updated))?;
+        if let Some(published) = &published {
+            writeln!(writer, "    <published>{}</published>", published)?;
+        }
+        if let Some(byline) = &article.byline {
+            writeln!(writer, "    <author><name>{}</name></author>", html_escape(byline))?;
+        }
+        if let Some(excerpt) = &article.excerpt {
+            writeln!(writer, "    <summary>{}</summary>", html_escape(excerpt))?;
+        }
+        if let Some(content) = &article.content {
+            writeln!(writer, "    <content type=\"html\">{}</content>", html_escape(content))?;
+        }
+        writer.write_all(b"  </entry>\n")?;
+    }
+    writer.write_all(b"</feed>\n")
+}
+
+/// Boilerplate every AMP page carries to hide it until the AMP runtime loads
+const AMP_BOILERPLATE: &str = "<style amp-boilerplate>body{-webkit-animation:-amp-start 8s steps(1,end) 0s 1 normal both;\
+-moz-animation:-amp-start 8s steps(1,end) 0s 1 normal both;-ms-animation:-amp-start 8s steps(1,end) 0s 1 normal both;\
+animation:-amp-start 8s steps(1,end) 0s 1 normal both}@-webkit-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}\
+@-moz-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}@-ms-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}\
+@-o-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}@keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}</style>\
+<noscript><style amp-boilerplate>body{-webkit-animation:none;-moz-animation:none;-ms-animation:none;animation:none}</style></noscript>";
+
+/// Write an article as an AMP page linking `canonical_url`, its non-AMP version
+///
+/// The content goes through [`amp_fragment`]; the `amp-iframe` extension
+/// script is included when the content embeds frames.
+pub fn write_amp<W: Write>(writer: &mut W, article: &Article, canonical_url: Option<&str>) -> io::Result<()> {
+    let content = article.content.as_deref().map(amp_fragment);
+    let mut html_attributes = String::new();
+    if let Some(lang) = &article.lang {
+        html_attributes.push_str(&format!(" lang=\"{}\"", html_escape(lang)));
+    }
+    if let Some(dir) = &article.dir {
+        html_attributes.push_str(&format!(" dir=\"{}\"", html_escape(dir)));
+    }
+    writeln!(writer, "<!doctype html>\n<html amp{}>\n<head>", html_attributes)?;
+    writer.write_all(b"    <meta charset=\"utf-8\">\n")?;
+    writer.write_all(b"    <script async src=\"https://cdn.ampproject.org/v0.js\"></script>\n")?;
+    if content.as_deref().is_some_and(|content| content.contains("<amp-iframe")) {
+        writer.write_all(
+            b"    <script async custom-element=\"amp-iframe\" src=\"https://cdn.ampproject.org/v0/amp-iframe-0.1.js\"></script>\n",
+        )?;
+    }
+    writeln!(writer, "    <title>{}</title>", html_escape(article.title.as_deref().unwrap_or("Untitled")))?;
+    if let Some(canonical_url) = canonical_url {
+        writeln!(writer, "    <link rel=\"canonical\" href=\"{}\">", html_escape(canonical_url))?;
+    }
+    writer.write_all(b"    <meta name=\"viewport\" content=\"width=device-width\">\n")?;
+    writeln!(writer, "    {}", AMP_BOILERPLATE)?;
+    writer.write_all(b"</head>\n<body>\n")?;
+
+    if let Some(title) = &article.title {
+        writeln!(writer, "    <h1>{}</h1>", html_escape(title))?;
+    }
+    if let Some(byline) = &article.byline {
+        writeln!(writer, "    <p class=\"byline\">By {}</p>", html_escape(byline))?;
+    }
+    if let Some(content) = &content {
+        writer.write_all(b"    <div class=\"content\">\n")?;
+        writer.write_all(content.as_bytes())?;
+        writer.write_all(b"\n    </div>\n")?;
+    }
+    writer.write_all(b"</body>\n</html>\n")
+}
+
+/// The entry id: its link when that is a URL, otherwise a digest of the article
+fn atom_id(link: Option<&str>, article: &Article) -> String {
+    match link {
+        Some(link) if link.contains("://") => link.to_string(),
+        _ => format!(
+            "urn:sha256:{}",
+            sha256_hex(&format!("{}\n{}", article.title.as_deref().unwrap_or(""), article.content.as_deref().unwrap_or("")))
+        ),
+    }
+}
+
+/// A published time as an RFC 3339 timestamp, as Atom requires
+///
+/// Accepts dates (`2024-03-01`) and ISO 8601 date-times with or without
+/// an offset, assumed UTC without one; anything else is dropped.
+fn atom_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    let date = value.get(..10)?;
+    let is_date = date.bytes().enumerate().all(|(i, b)| if i == 4 || i == 7 { b == b'-' } else { b.is_ascii_digit() });
+    if !is_date {
+        return None;
+    }
+    let rest = &value[10..];
+    if rest.is_empty() {
+        return Some(format!("{}T00:00:00Z", date));
+    }
+    let rest = rest.strip_prefix(['T', ' '])?;
+    let time = rest.get(..8)?;
+    let is_time = time.bytes().enumerate().all(|(i, b)| if i == 2 || i == 5 { b == b':' } else { b.is_ascii_digit() });
+    if !is_time {
+        return None;
+    }
+    let mut zone = &rest[8..];
+    let mut fraction = "";
+    if zone.starts_with('.') {
+        let end = zone[1..].find(|c: char| !c.is_ascii_digit()).map_or(zone.len(), |end| end + 1);
+        (fraction, zone) = zone.split_at(end);
+    }
+    // Offsets are ASCII; anything else can't be sliced by byte below
+    let zone = match zone {
+        "" | "Z" | "z" => "Z".to_string(),
+        _ if !zone.is_ascii() => return None,
+        _ if zone.len() == 6 && zone.as_bytes()[3] == b':' => zone.to_string(),
+        _ if zone.len() == 5 => format!("{}:{}", &zone[..3], &zone[3..]),
+        _ => return None,
+    };
+    let is_offset = zone.bytes().enumerate().all(|(i, b)| match i {
+        0 => b == b'+' || b == b'-',
+        3 => b == b':',
+        _ => b.is_ascii_digit(),
+    });
+    if zone != "Z" && !is_offset {
+        return None;
+    }
+    Some(format!("{}T{}{}{}", date, time, fraction, zone))
+}
+
+/// Serialize an HTML fragment as well-formed XHTML: void elements
+/// self-closed, text and attributes escaped, comments and characters XML
+/// doesn't allow dropped
+pub fn xhtml_fragment(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut xhtml = String::with_capacity(html.len());
+    for child in fragment.root_element().children() {
+        push_xhtml(child, &mut xhtml);
+    }
+    xhtml
+}
+
+fn push_xhtml(node: ego_tree::NodeRef<Node>, xhtml: &mut String) {
+    match node.value() {
+        Node::Text(text) => xhtml.push_str(&xml_text(text)),
+        Node::Element(element) => {
+            let name = element.name();
+            xhtml.push('<');
+            xhtml.push_str(name);
+            for (attribute, value) in element.attrs() {
+                // Names that aren't valid XML (e.g. from broken markup) can't be kept
+                if attribute.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')) {
+                    xhtml.push_str(&format!(" {}=\"{}\"", attribute, xml_text(value)));
+                }
+            }
+            if VOID_ELEMENTS.contains(&name) {
+                xhtml.push_str("/>");
+                return;
+            }
+            xhtml.push('>');
+            for child in node.children() {
+                push_xhtml(child, xhtml);
+            }
+            xhtml.push_str(&format!("</{}>", name));
+        }
+        _ => {}
+    }
+}
+
+/// Elements AMP pages can't contain, dropped with their content
+const AMP_DROPPED_ELEMENTS: &[&str] = &[
+    "applet", "audio", "base", "button", "embed", "form", "frame", "frameset", "input", "link", "meta", "noscript",
+    "object", "param", "script", "select", "source", "style", "textarea", "video",
+];
+
+/// Height of images and frames whose size isn't declared
+const AMP_DEFAULT_HEIGHT: u32 = 300;
+
+/// Rewrite an HTML fragment for AMP: images become `amp-img` and https
+/// frames `amp-iframe`, with their declared size or a fixed height; inline
+/// styles, event handlers, `javascript:` links and elements AMP forbids
+/// (scripts, forms, plain video and audio, plain-http frames) are dropped
+pub fn amp_fragment(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut amp = String::with_capacity(html.len());
+    for child in fragment.root_element().children() {
+        push_amp(child, &mut amp);
+    }
+    amp
+}
+
+fn push_amp(node: ego_tree::NodeRef<Node>, amp: &mut String) {
+    let element = match node.value() {
+        Node::Text(text) => {
+            amp.push_str(&html_escape(text));
+            return;
+        }
+        Node::Element(element) => element,
+        _ => return,
+    };
+    let name = element.name();
+    let attr = |name: &str| element.attr(name).map(str::trim).filter(|value| !value.is_empty());
+    match name {
+        _ if AMP_DROPPED_ELEMENTS.contains(&name) => {}
+        // The <img> inside is all AMP needs
+        "picture" => node.children().for_each(|child| push_amp(child, amp)),
+        "img" => {
+            let Some(src) = attr("src") else {
+                return;
+            };
+            amp.push_str(&format!("<amp-img src=\"{}\"", html_escape(src)));
+            for name in ["srcset", "sizes", "alt", "title"] {
+                if let Some(value) = element.attr(name) {
+                    amp.push_str(&format!(" {}=\"{}\"", name, html_escape(value)));
+                }
+            }
+            amp.push_str(&amp_size(attr("width"), attr("height")));
+            amp.push_str("></amp-img>");
+        }
+        "iframe" => {
+            let Some(src) = attr("src").filter(|src| src.starts_with("https://")) else {
+                return;
+            };
+            amp.push_str(&format!(
+                "<amp-iframe src=\"{}\" sandbox=\"allow-scripts allow-same-origin allow-popups\" frameborder=\"0\"",
+                html_escape(src)
+            ));
+            if element.attr("allowfullscreen").is_some() {
+                amp.push_str(" allowfullscreen");
+            }
+            amp.push_str(&amp_size(attr("width"), attr("height")));
+            amp.push_str("></amp-iframe>");
+        }
+        _ => {
+            amp.push('<');
+            amp.push_str(name);
+            for (attribute, value) in element.attrs() {
+                let is_script_url = matches!(attribute, "href" | "src")
+                    && value.trim_start().get(..11).is_some_and(|scheme| scheme.eq_ignore_ascii_case("javascript:"));
+                if attribute == "style" || attribute.starts_with("on") || is_script_url {
+                    continue;
+                }
+                amp.push_str(&format!(" {}=\"{}\"", attribute, html_escape(value)));
+            }
+            amp.push('>');
+            if VOID_ELEMENTS.contains(&name) {
+                return;
+            }
+            for child in node.children() {
+                push_amp(child, amp);
+            }
+            amp.push_str(&format!("</{}>", name));
+        }
+    }
+}
+
+/// `width`, `height` and `layout` attributes of an AMP media element: its
+/// declared size scaled to the page width, or a fixed height without one
+fn amp_size(width: Option<&str>, height: Option<&str>) -> String {
+    let pixels = |value: Option<&str>| value?.trim_end_matches("px").parse::<u32>().ok().filter(|&pixels| pixels > 0);
+    match (pixels(width), pixels(height)) {
+        (Some(width), Some(height)) => format!(" width=\"{}\" height=\"{}\" layout=\"responsive\"", width, height),
+        (_, height) => format!(" height=\"{}\" layout=\"fixed-height\"", height.unwrap_or(AMP_DEFAULT_HEIGHT)),
+    }
+}
+
+/// `text` escaped for XML, without the control characters XML 1.0 forbids
+fn xml_text(text: &str) -> String {
+    text.chars()
+        .filter(|&c| (c >= ' ' || matches!(c, '\t' | '\n' | '\r')) && !matches!(c, '\u{FFFE}' | '\u{FFFF}'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_output_format_from_str() {
+        assert!(matches!(OutputFormat::from("json"), OutputFormat::Json));
+        assert!(matches!(OutputFormat::from("text"), OutputFormat::Text));
+        assert!(matches!(OutputFormat::from("html"), OutputFormat::Html));
+        assert!(matches!(OutputFormat::from("blocks-json"), OutputFormat::BlocksJson));
+        assert!(matches!(OutputFormat::from("invalid"), OutputFormat::Json)); // Default
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let article = Article {
+            title: Some("Rivers & Deltas".to_string()),
+            content: Some("<p>Silt</p>".to_string()),
+            byline: Some("Jane Doe".to_string()),
+            published_time: Some("2024-03-01T09:30:00+0100".to_string()),
+            ..Default::default()
+        };
+        let untitled = Article { content: Some("<p>Notes</p>".to_string()), ..Default::default() };
+        let mut output = Vec::new();
+        let updated = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_709_300_000);
+        write_atom_feed(&mut output, "Saved", &[(Some("https://example.com/rivers"), &article), (None, &untitled)], updated).unwrap();
+        let feed = String::from_utf8(output).unwrap();
+
+        assert!(feed.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(feed.contains("  <updated>2024-03-01T13:33:20Z</updated>"));
+        assert!(feed.contains("  <author><name>Saved</name></author>"));
+        assert!(feed.contains("<id>https://example.com/rivers</id>"));
+        assert!(feed.contains("<title>Rivers &amp; Deltas</title>"));
+        assert!(feed.contains("<published>2024-03-01T09:30:00+01:00</published>"));
+        assert!(feed.contains("<author><name>Jane Doe</name></author>"));
+        assert!(feed.contains("<content type=\"html\">&lt;p&gt;Silt&lt;/p&gt;</content>"));
+        assert!(feed.contains("<id>urn:sha256:"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert!(matches!(OutputFormat::from("atom"), OutputFormat::Atom));
+    }
+
+    #[test]
+    fn test_atom_date() {
+        assert_eq!(atom_date("2024-03-01").as_deref(), Some("2024-03-01T00:00:00Z"));
+        assert_eq!(atom_date("2024-03-01 09:30:00").as_deref(), Some("2024-03-01T09:30:00Z"));
+        assert_eq!(atom_date("2024-03-01T09:30:00.250-05:00").as_deref(), Some("2024-03-01T09:30:00.250-05:00"));
+        assert_eq!(atom_date("March 1, 2024"), None);
+        assert_eq!(atom_date("2024-03-01T09:30"), None);
+        assert_eq!(atom_date("2024-03-01T09:30:00+0100").as_deref(), Some("2024-03-01T09:30:00+01:00"));
+        assert_eq!(atom_date("2024-03-01T09:30:00+0€"), None);
+        assert_eq!(atom_date("2024-03-01T09:30:00+ab:cd"), None);
+
+        // A malformed page date falls back to the feed's timestamp instead of failing the feed
+        let article = Article { published_time: Some("2024-03-01T09:30:00+0€".to_string()), ..Default::default() };
+        let feed = format_output(&article, &OutputFormat::Atom).unwrap();
+        assert!(!feed.contains("<published>"));
+    }
+
+    #[test]
+    fn test_xhtml_fragment() {
+        assert_eq!(
+            xhtml_fragment(r#"<p title="a &quot;b&quot;">x &lt; y &amp; z<br>z<img src="i.png"></p><!-- c -->"#),
+            r#"<p title="a &quot;b&quot;">x &lt; y &amp; z<br/>z<img src="i.png"/></p>"#
+        );
+        assert_eq!(xhtml_fragment("<p>Bell\u{7}</p><hr>"), "<p>Bell</p><hr/>");
+    }
+
+    #[test]
+    fn test_amp_fragment() {
+        let html = r#"<p style="color:red" onclick="x()">Rivers <a href="javascript:void(0)">carry</a> silt.</p>
+            <picture><source srcset="d.webp"><img src="delta.jpg" alt="Delta" width="800" height="600px"></picture>
+            <img src="map.png"><img alt="no source">
+            <iframe src="https://www.youtube.com/embed/x" width="560" height="315" allowfullscreen></iframe>
+            <iframe src="http://example.com/widget"></iframe>
+            <video src="v.mp4"></video><script>track()</script><form><input name="q"></form><hr>"#;
+        let amp = amp_fragment(html);
+        assert!(amp.starts_with("<p>Rivers <a>carry</a> silt.</p>"), "{}", amp);
+        assert!(amp.contains(r#"<amp-img src="delta.jpg" alt="Delta" width="800" height="600" layout="responsive"></amp-img>"#));
+        assert!(amp.contains(r#"<amp-img src="map.png" height="300" layout="fixed-height"></amp-img>"#));
+        assert!(amp.contains(
+            r#"<amp-iframe src="https://www.youtube.com/embed/x" sandbox="allow-scripts allow-same-origin allow-popups" frameborder="0" allowfullscreen width="560" height="315" layout="responsive"></amp-iframe>"#
+        ));
+        assert_eq!(amp.matches("<amp-img").count(), 2);
+        for dropped in ["d.webp", "example.com", "<video", "<script", "<form", "<input", "style=", "onclick"] {
+            assert!(!amp.contains(dropped), "{} in {}", dropped, amp);
+        }
+        assert!(amp.ends_with("<hr>"));
+    }
+
+    #[test]
+    fn test_write_amp() {
+        let article = Article {
+            title: Some("Rivers & Deltas".to_string()),
+            content: Some(r#"<p>Silt</p><iframe src="https://maps.example.com/nile"></iframe>"#.to_string()),
+            lang: Some("en".to_string()),
+            ..Default::default()
+        };
+        let format = OutputFormat::Amp { canonical_url: Some("https://example.com/rivers".to_string()) };
+        let page = format_output(&article, &format).unwrap();
+        assert!(page.starts_with("<!doctype html>\n<html amp lang=\"en\">\n<head>\n    <meta charset=\"utf-8\">\n"));
+        assert!(page.contains(r#"<link rel="canonical" href="https://example.com/rivers">"#));
+        assert!(page.contains(r#"custom-element="amp-iframe""#));
+        assert!(page.contains("<style amp-boilerplate>") && page.contains("<title>Rivers &amp; Deltas</title>"));
+        assert!(matches!(OutputFormat::from("amp"), OutputFormat::Amp { canonical_url: None }));
+
+        let plain = Article { content: Some("<p>Silt</p>".to_string()), ..Default::default() };
+        let page = format_output(&plain, &OutputFormat::from("amp")).unwrap();
+        assert!(!page.contains("amp-iframe") && !page.contains("canonical"));
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(html_escape("Hello & <World>"), "Hello &amp; &lt;World&gt;");
+        assert_eq!(html_escape("\"Test\""), "&quot;Test&quot;");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_format_output_json() {
+        let article = Article {
+            title: Some("Test Title".to_string()),
+            content: Some("<p>Test content</p>".to_string()),
+            text_content: Some("Test content".to_string()),
+            length: Some(12),
+            excerpt: None,
+            byline: Some("Test Author".to_string()),
+            readerable: Some(true),
+            ..Default::default()
+        };
+
+        let result = format_output(&article, &OutputFormat::Json).unwrap();
+        assert!(result.contains("Test Title"));
+        assert!(result.contains("Test content"));
+        assert!(result.contains("Test Author"));
+    }
+
+    #[test]
+    fn test_format_output_text() {
+        let article = Article {
+            title: Some("Test Title".to_string()),
+            content: Some("<p>Test content</p>".to_string()),
+            text_content: Some("Test content".to_string()),
+            length: Some(12),
+            excerpt: None,
+            byline: Some("Test Author".to_string()),
+            readerable: Some(true),
+            ..Default::default()
+        };
+
+        let result = format_output(&article, &OutputFormat::Text).unwrap();
+        assert!(result.contains("Title: Test Title"));
+        assert!(result.contains("By: Test Author"));
+        assert!(result.contains("Test content"));
+    }
+
+    #[test]
+    fn test_format_output_text_rtl() {
+        let article = Article {
+            text_content: Some("קראו את Rust Book היום".to_string()),
+            dir: Some("rtl".to_string()),
+            ..Default::default()
+        };
+
+        let result = format_output(&article, &OutputFormat::Text).unwrap();
+        assert_eq!(result, "קראו את \u{2066}Rust Book\u{2069} היום");
+    }
+
+    #[test]
+    fn test_format_output_html() {
+        let article = Article {
+            title: Some("Test Title".to_string()),
+            content: Some("<p>Test content</p>".to_string()),
+            text_content: Some("Test content".to_string()),
+            length: Some(12),
+            excerpt: None,
+            byline: Some("Test Author".to_string()),
+            readerable: Some(true),
+            ..Default::default()
+        };
+
+        let result = format_output(&article, &OutputFormat::Html).unwrap();
+        assert!(result.contains("<!DOCTYPE html>"));
+        assert!(result.contains("<title>Test Title</title>"));
+        assert!(result.contains("<h1>Test Title</h1>"));
+        assert!(result.contains("By Test Author"));
+        assert!(result.contains("<p>Test content</p>"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_format_output_blocks_json() {
+        let article = Article {
+            title: Some("Test Title".to_string()),
+            content: Some("<h2>Heading</h2><p>Test content</p>".to_string()),
+            ..Default::default()
+        };
+
+        let result = format_output(&article, &OutputFormat::BlocksJson).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(value["title"], "Test Title");
+        assert_eq!(value["blocks"][0]["type"], "heading");
+        assert_eq!(value["blocks"][1]["text"], "Test content");
+    }
+
+    #[test]
+    fn test_write_article_matches_format_output() {
+        let article = Article {
+            title: Some("Streamed".to_string()),
+            content: Some("<p>Body</p>".to_string()),
+            text_content: Some("Body".to_string()),
+            ..Default::default()
+        };
+        for format in [OutputFormat::Text, OutputFormat::Html] {
+            let mut streamed = Vec::new();
+            write_article(&mut streamed, &article, &format).unwrap();
+            assert_eq!(String::from_utf8(streamed).unwrap(), format_output(&article, &format).unwrap());
+        }
+    }
+}
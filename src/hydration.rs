@@ -0,0 +1,112 @@
+//! Article content from server-rendered hydration payloads
+//!
+//! Next.js and Nuxt pages can ship the article only as JSON for the client
+//! to render (`__NEXT_DATA__`, `__NUXT_DATA__`). When the rendered DOM is
+//! empty, the longest article-like string in that JSON stands in for it.
+//! Requires the `hydration` feature.
+
+use scraper::Html;
+#[cfg(feature = "hydration")]
+use scraper::Selector;
+#[cfg(feature = "hydration")]
+use serde_json::Value;
+
+#[cfg(feature = "hydration")]
+use crate::utils::escape_html;
+
+/// Hydration payload scripts of known frameworks
+pub(crate) const PAYLOAD_SELECTOR: &str = "script#__NEXT_DATA__, script#__NUXT_DATA__";
+
+/// Shortest string considered article text
+#[cfg(feature = "hydration")]
+const MIN_ARTICLE_CHARS: usize = 200;
+
+/// Article HTML from the document's hydration payload, if it has one
+#[cfg(feature = "hydration")]
+pub(crate) fn hydration_html(document: &Html) -> Option<String> {
+    let selector = Selector::parse(PAYLOAD_SELECTOR).unwrap();
+    let mut best: Option<String> = None;
+    for script in document.select(&selector) {
+        let Ok(payload) = serde_json::from_str::<Value>(&script.text().collect::<String>()) else {
+            continue;
+        };
+        let mut strings = Vec::new();
+        collect_strings(&payload, &mut strings);
+        for text in strings {
+            let better = best.as_ref().is_none_or(|best| rank(text) > rank(best));
+            if text.chars().count() >= MIN_ARTICLE_CHARS && better {
+                best = Some(text.to_string());
+            }
+        }
+    }
+    best.map(|text| if is_html(&text) { text } else { paragraphs(&text) })
+}
+
+#[cfg(not(feature = "hydration"))]
+pub(crate) fn hydration_html(_document: &Html) -> Option<String> {
+    None
+}
+
+#[cfg(feature = "hydration")]
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => strings.push(text),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(fields) => fields.values().for_each(|field| collect_strings(field, strings)),
+        _ => {}
+    }
+}
+
+/// HTML beats plain text, then longer beats shorter
+#[cfg(feature = "hydration")]
+fn rank(text: &str) -> (bool, usize) {
+    (is_html(text), text.len())
+}
+
+#[cfg(feature = "hydration")]
+fn is_html(text: &str) -> bool {
+    text.contains("<p>") || text.contains("<p ")
+}
+
+/// Wrap the blank-line separated paragraphs of plain text in `<p>` tags
+#[cfg(feature = "hydration")]
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>", escape_html(paragraph)))
+        .collect()
+}
+
+#[cfg(all(test, feature = "hydration"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_data_html() {
+        let body = "<p>A long paragraph about rivers, with commas, and enough text to pass.</p>".repeat(4);
+        let payload = serde_json::json!({
+            "props": {"pageProps": {"post": {"title": "Rivers", "excerpt": "x".repeat(300), "bodyHtml": body}}}
+        });
+        let document = Html::parse_document(&format!(
+            r#"<html><body><div id="__next"></div><script id="__NEXT_DATA__" type="application/json">{}</script></body></html>"#,
+            payload
+        ));
+        assert_eq!(hydration_html(&document), Some(body));
+    }
+
+    #[test]
+    fn test_nuxt_data_plain_text() {
+        let text = "First paragraph about rivers, with commas & details. ".repeat(3) + "\n\n" + &"Second paragraph. ".repeat(8);
+        let payload = serde_json::json!([{"data": 1}, "Rivers", text]);
+        let document = Html::parse_document(&format!(
+            r#"<html><body><script id="__NUXT_DATA__" type="application/json">{}</script></body></html>"#,
+            payload
+        ));
+        let html = hydration_html(&document).unwrap();
+        assert!(html.starts_with("<p>First paragraph about rivers, with commas &amp; details."));
+        assert_eq!(html.matches("<p>").count(), 2);
+
+        assert_eq!(hydration_html(&Html::parse_document("<p>No payload</p>")), None);
+    }
+}
@@ -0,0 +1,170 @@
+//! Share images and site icons declared in the page head
+//!
+//! Pages often declare several `og:image`s (with `og:image:width`/`height`)
+//! and icons in several sizes. All of them are kept, in document order, so
+//! consumers can pick artwork of the size they need.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use scraper::{Html, Selector};
+
+use crate::utils::to_absolute_uri;
+
+/// An image URL with its declared size, when known
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImageCandidate {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// `rel` values of `<link>`s that declare a site icon
+const ICON_RELS: &[&str] = &["icon", "apple-touch-icon", "apple-touch-icon-precomposed"];
+
+/// Open Graph and Twitter card images, each with the size declared after it
+pub(crate) fn share_images(document: &Html, base_uri: Option<&str>) -> Vec<ImageCandidate> {
+    let meta_selector = Selector::parse("meta[content]").unwrap();
+    let mut images: Vec<ImageCandidate> = Vec::new();
+    for meta in document.select(&meta_selector) {
+        let Some(key) = meta.value().attr("property").or(meta.value().attr("name")) else {
+            continue;
+        };
+        let content = meta.value().attr("content").unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+        match key.to_ascii_lowercase().as_str() {
+            "og:image" | "og:image:url" | "twitter:image" | "twitter:image:src" => images.push(ImageCandidate {
+                url: resolve(content, base_uri),
+                ..Default::default()
+            }),
+            // The https variant of the image just declared
+            "og:image:secure_url" => match images.last_mut() {
+                Some(image) => image.url = resolve(content, base_uri),
+                None => images.push(ImageCandidate { url: resolve(content, base_uri), ..Default::default() }),
+            },
+            "og:image:width" | "twitter:image:width" => {
+                if let Some(image) = images.last_mut() {
+                    image.width = image.width.or(parse_dimension(content));
+                }
+            }
+            "og:image:height" | "twitter:image:height" => {
+                if let Some(image) = images.last_mut() {
+                    image.height = image.height.or(parse_dimension(content));
+                }
+            }
+            _ => {}
+        }
+    }
+    dedupe(images)
+}
+
+/// Icons from `<link rel="icon">` and Apple touch icons, with the largest of their `sizes`
+pub(crate) fn site_icons(document: &Html, base_uri: Option<&str>) -> Vec<ImageCandidate> {
+    let link_selector = Selector::parse("link[rel][href]").unwrap();
+    let icons = document
+        .select(&link_selector)
+        .filter(|link| {
+            let rel = link.value().attr("rel").unwrap_or("");
+            rel.split_ascii_whitespace().any(|token| ICON_RELS.iter().any(|icon| token.eq_ignore_ascii_case(icon)))
+        })
+        .filter_map(|link| {
+            let href = link.value().attr("href")?.trim();
+            if href.is_empty() {
+                return None;
+            }
+            let (width, height) = link
+                .value()
+                .attr("sizes")
+                .unwrap_or("")
+                .split_ascii_whitespace()
+                .filter_map(|size| {
+                    let (width, height) = size.split_once(['x', 'X'])?;
+                    Some((parse_dimension(width)?, parse_dimension(height)?))
+                })
+                .max_by_key(|(width, height)| u64::from(*width) * u64::from(*height))
+                .map_or((None, None), |(width, height)| (Some(width), Some(height)));
+            Some(ImageCandidate { url: resolve(href, base_uri), width, height })
+        })
+        .collect();
+    dedupe(icons)
+}
+
+fn resolve(url: &str, base_uri: Option<&str>) -> String {
+    match base_uri {
+        Some(base_uri) => to_absolute_uri(url, base_uri),
+        None => url.to_string(),
+    }
+}
+
+fn parse_dimension(value: &str) -> Option<u32> {
+    value.trim().trim_end_matches("px").parse().ok().filter(|&value| value > 0)
+}
+
+/// Drop repeated URLs, filling in sizes the first declaration lacked
+fn dedupe(images: Vec<ImageCandidate>) -> Vec<ImageCandidate> {
+    let mut unique: Vec<ImageCandidate> = Vec::with_capacity(images.len());
+    for image in images {
+        match unique.iter_mut().find(|seen| seen.url == image.url) {
+            Some(seen) => {
+                seen.width = seen.width.or(image.width);
+                seen.height = seen.height.or(image.height);
+            }
+            None => unique.push(image),
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_images() {
+        let document = Html::parse_document(
+            r#"<html><head>
+            <meta property="og:image" content="/large.jpg">
+            <meta property="og:image:width" content="1200"><meta property="og:image:height" content="630">
+            <meta property="og:image" content="http://example.com/square.jpg">
+            <meta property="og:image:secure_url" content="https://example.com/square.jpg">
+            <meta property="og:image:width" content="600">
+            <meta name="twitter:image" content="https://example.com/large.jpg">
+            <meta property="og:image:height" content="not a number">
+            </head><body></body></html>"#,
+        );
+        let images = share_images(&document, Some("https://example.com/post/"));
+        assert_eq!(
+            images,
+            vec![
+                ImageCandidate { url: "https://example.com/large.jpg".to_string(), width: Some(1200), height: Some(630) },
+                ImageCandidate { url: "https://example.com/square.jpg".to_string(), width: Some(600), height: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_site_icons() {
+        let document = Html::parse_document(
+            r#"<html><head>
+            <link rel="shortcut icon" href="/favicon.ico">
+            <link rel="icon" sizes="16x16 32x32" href="/icon.png">
+            <link rel="apple-touch-icon" sizes="180x180" href="/touch.png">
+            <link rel="icon" sizes="any" href="/icon.svg">
+            <link rel="stylesheet" href="/site.css">
+            </head><body></body></html>"#,
+        );
+        let icons = site_icons(&document, None);
+        let summary: Vec<_> = icons.iter().map(|icon| (icon.url.as_str(), icon.width, icon.height)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/favicon.ico", None, None),
+                ("/icon.png", Some(32), Some(32)),
+                ("/touch.png", Some(180), Some(180)),
+                ("/icon.svg", None, None),
+            ]
+        );
+    }
+}
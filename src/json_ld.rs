@@ -0,0 +1,315 @@
+//! JSON-LD (schema.org) metadata extraction
+
+#[cfg(feature = "json-ld")]
+use serde_json::Value;
+
+#[cfg(feature = "json-ld")]
+use crate::regexps::is_json_ld_article_type;
+#[cfg(feature = "json-ld")]
+use crate::utils::escape_html;
+#[cfg(feature = "json-ld")]
+use crate::RecipeTimes;
+use crate::{Author, LiveUpdate, Recipe};
+
+/// Metadata gathered from the `application/ld+json` scripts of a document
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(feature = "json-ld"), allow(dead_code))]
+pub(crate) struct JsonLdMetadata {
+    /// Every schema.org `@type` declared, in document order
+    pub types: Vec<String>,
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    pub excerpt: Option<String>,
+    pub site_name: Option<String>,
+    pub published_time: Option<String>,
+    pub authors: Vec<Author>,
+    /// The first `Recipe` object, if any
+    pub recipe: Option<Recipe>,
+    /// `liveBlogUpdate` entries of the first `LiveBlogPosting`
+    pub live_updates: Vec<LiveUpdate>,
+}
+
+impl JsonLdMetadata {
+    /// Merge one JSON-LD script body into the collected metadata
+    ///
+    /// Invalid JSON is ignored. Only the first article-typed object fills in
+    /// the article fields.
+    #[cfg(feature = "json-ld")]
+    pub fn add_script(&mut self, text: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(text.trim()) else {
+            return;
+        };
+
+        let mut objects = Vec::new();
+        collect_objects(&value, &mut objects);
+
+        for object in objects {
+            let types = object_types(object);
+            let is_article = types.iter().any(|t| is_json_ld_article_type(t));
+            let is_recipe = types.iter().any(|t| t == "Recipe");
+            let is_live_blog = types.iter().any(|t| t == "LiveBlogPosting");
+            self.types.extend(types);
+
+            if is_article && self.title.is_none() && self.authors.is_empty() {
+                self.fill_from_article(object);
+            }
+            if is_recipe && self.recipe.is_none() {
+                self.recipe = Some(parse_recipe(object));
+            }
+            if is_live_blog && self.live_updates.is_empty() {
+                self.live_updates = parse_live_updates(object);
+            }
+        }
+    }
+
+    /// JSON-LD support is compiled out; scripts are ignored
+    #[cfg(not(feature = "json-ld"))]
+    pub fn add_script(&mut self, _text: &str) {}
+
+    #[cfg(feature = "json-ld")]
+    fn fill_from_article(&mut self, object: &Value) {
+        self.title = string_field(object, "name").or_else(|| string_field(object, "headline"));
+        self.excerpt = string_field(object, "description");
+        self.published_time = string_field(object, "datePublished");
+        self.site_name = object.get("publisher").and_then(|publisher| string_field(publisher, "name"));
+
+        if let Some(author) = object.get("author") {
+            self.authors = parse_authors(author);
+        }
+        if !self.authors.is_empty() {
+            let names: Vec<&str> = self.authors.iter().map(|author| author.name.as_str()).collect();
+            self.byline = Some(names.join(", "));
+        }
+    }
+}
+
+#[cfg(feature = "json-ld")]
+fn collect_objects<'a>(value: &'a Value, objects: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_objects(item, objects)),
+        Value::Object(map) => {
+            if map.contains_key("@type") {
+                objects.push(value);
+            }
+            if let Some(graph) = map.get("@graph") {
+                collect_objects(graph, objects);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(feature = "json-ld")]
+fn object_types(object: &Value) -> Vec<String> {
+    match object.get("@type") {
+        Some(Value::String(t)) => vec![t.clone()],
+        Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "json-ld")]
+fn string_field(object: &Value, key: &str) -> Option<String> {
+    object
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Image values may be a URL string or an `ImageObject`
+#[cfg(feature = "json-ld")]
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(url) => Some(url.clone()),
+        Value::Object(_) => string_field(value, "url"),
+        Value::Array(items) => items.iter().find_map(image_url),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "json-ld")]
+fn parse_authors(value: &Value) -> Vec<Author> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(parse_authors).collect(),
+        Value::String(name) if !name.trim().is_empty() => vec![Author {
+            name: name.trim().to_string(),
+            ..Default::default()
+        }],
+        Value::Object(_) => match string_field(value, "name") {
+            Some(name) => vec![Author {
+                name,
+                url: string_field(value, "url"),
+                image: value.get("image").and_then(image_url),
+            }],
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "json-ld")]
+fn parse_recipe(object: &Value) -> Recipe {
+    let ingredients = match object.get("recipeIngredient").or_else(|| object.get("ingredients")) {
+        Some(Value::Array(items)) => items.iter().filter_map(text_value).collect(),
+        Some(value) => text_value(value).into_iter().collect(),
+        None => Vec::new(),
+    };
+
+    let mut instructions = Vec::new();
+    if let Some(value) = object.get("recipeInstructions") {
+        collect_instructions(value, &mut instructions);
+    }
+
+    let recipe_yield = match object.get("recipeYield") {
+        Some(Value::Array(items)) => items.iter().find_map(text_value),
+        Some(value) => text_value(value),
+        None => None,
+    };
+
+    Recipe {
+        name: string_field(object, "name"),
+        ingredients,
+        instructions,
+        recipe_yield,
+        times: RecipeTimes {
+            prep: string_field(object, "prepTime"),
+            cook: string_field(object, "cookTime"),
+            total: string_field(object, "totalTime"),
+        },
+    }
+}
+
+#[cfg(feature = "json-ld")]
+fn parse_live_updates(object: &Value) -> Vec<LiveUpdate> {
+    let updates = match object.get("liveBlogUpdate") {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(item @ Value::Object(_)) => vec![item],
+        _ => Vec::new(),
+    };
+
+    updates
+        .into_iter()
+        .filter_map(|update| {
+            let text = string_field(update, "articleBody").or_else(|| string_field(update, "headline"))?;
+            Some(LiveUpdate {
+                time: string_field(update, "datePublished"),
+                html: format!("<p>{}</p>", escape_html(&text)),
+                text,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "json-ld")]
+fn text_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Instructions may be a string, a list of strings, `HowToStep`s, or
+/// `HowToSection`s containing steps
+#[cfg(feature = "json-ld")]
+fn collect_instructions(value: &Value, instructions: &mut Vec<String>) {
+    match value {
+        Value::String(text) => instructions.extend(
+            text.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string),
+        ),
+        Value::Array(items) => items.iter().for_each(|item| collect_instructions(item, instructions)),
+        Value::Object(_) => {
+            if let Some(steps) = value.get("itemListElement") {
+                collect_instructions(steps, instructions);
+            } else if let Some(text) = string_field(value, "text").or_else(|| string_field(value, "name")) {
+                instructions.push(text);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(all(test, feature = "json-ld"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_article_json_ld() {
+        let mut metadata = JsonLdMetadata::default();
+        metadata.add_script(r#"{
+            "@context": "https://schema.org",
+            "@graph": [
+                {"@type": "WebSite", "name": "Example"},
+                {
+                    "@type": "NewsArticle",
+                    "headline": "Big News",
+                    "datePublished": "2024-01-02",
+                    "author": [
+                        {"@type": "Person", "name": "Jane Doe", "url": "https://example.com/jane",
+                         "image": {"@type": "ImageObject", "url": "https://example.com/jane.png"}},
+                        "John Roe"
+                    ]
+                }
+            ]
+        }"#);
+
+        assert_eq!(metadata.types, vec!["WebSite", "NewsArticle"]);
+        assert_eq!(metadata.title.as_deref(), Some("Big News"));
+        assert_eq!(metadata.byline.as_deref(), Some("Jane Doe, John Roe"));
+        assert_eq!(metadata.authors[0].url.as_deref(), Some("https://example.com/jane"));
+        assert_eq!(metadata.authors[0].image.as_deref(), Some("https://example.com/jane.png"));
+        assert_eq!(metadata.authors[1].url, None);
+    }
+
+    #[test]
+    fn test_invalid_json_ld_is_ignored() {
+        let mut metadata = JsonLdMetadata::default();
+        metadata.add_script("{not json");
+        assert!(metadata.types.is_empty());
+    }
+
+    #[test]
+    fn test_parse_recipe_json_ld() {
+        let mut metadata = JsonLdMetadata::default();
+        metadata.add_script(r#"{
+            "@type": "Recipe",
+            "name": "Pancakes",
+            "recipeIngredient": ["2 eggs", "1 cup flour"],
+            "recipeYield": ["4", "4 servings"],
+            "prepTime": "PT10M",
+            "recipeInstructions": [
+                {"@type": "HowToSection", "itemListElement": [
+                    {"@type": "HowToStep", "text": "Mix everything."},
+                    {"@type": "HowToStep", "text": "Fry in a pan."}
+                ]}
+            ]
+        }"#);
+
+        let recipe = metadata.recipe.unwrap();
+        assert_eq!(recipe.name.as_deref(), Some("Pancakes"));
+        assert_eq!(recipe.ingredients, vec!["2 eggs", "1 cup flour"]);
+        assert_eq!(recipe.instructions, vec!["Mix everything.", "Fry in a pan."]);
+        assert_eq!(recipe.recipe_yield.as_deref(), Some("4"));
+        assert_eq!(recipe.times.prep.as_deref(), Some("PT10M"));
+        assert_eq!(recipe.times.cook, None);
+    }
+
+    #[test]
+    fn test_parse_live_blog_json_ld() {
+        let mut metadata = JsonLdMetadata::default();
+        metadata.add_script(r#"{
+            "@type": "LiveBlogPosting",
+            "liveBlogUpdate": [
+                {"@type": "BlogPosting", "datePublished": "2024-05-01T10:00Z", "articleBody": "Polls <open>."},
+                {"@type": "BlogPosting", "headline": "Turnout is high"}
+            ]
+        }"#);
+
+        assert_eq!(metadata.live_updates.len(), 2);
+        assert_eq!(metadata.live_updates[0].time.as_deref(), Some("2024-05-01T10:00Z"));
+        assert_eq!(metadata.live_updates[0].html, "<p>Polls &lt;open&gt;.</p>");
+        assert_eq!(metadata.live_updates[1].text, "Turnout is high");
+    }
+}
@@ -28,33 +28,89 @@
 //! }
 //! ```
 
-use regex::Regex;
+use regexps::{CommaStyle, Regex};
 use scraper::{Html, Selector, ElementRef};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 // ContentScorer import removed as it's not currently used
 
+pub mod analysis;
+mod article_ref;
+#[cfg(feature = "async")]
+pub mod asynch;
+mod blocks;
+pub mod bundle;
+#[cfg(feature = "corpus")]
+pub mod corpus;
+mod dom;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod format;
+mod hydration;
+mod images;
+mod json_ld;
+mod memory;
+mod pdf;
+pub mod plugins;
+#[cfg(feature = "accounting")]
+mod metrics;
+#[cfg(feature = "oracle")]
+pub mod oracle;
+mod print;
+mod profiles;
 mod regexps;
+mod report;
+#[cfg(feature = "fetch")]
+mod robots;
+#[cfg(feature = "fetch")]
+pub mod sitemap;
+mod source_map;
+mod text;
+pub mod tune;
 mod utils;
 
+pub use article_ref::ArticleRef;
+pub use blocks::{ArticleBlocks, Block, Chunk, Paragraph};
+pub use images::ImageCandidate;
+use json_ld::JsonLdMetadata;
+use print::PrintHints;
+pub use plugins::{CleanerPlugin, CleanerRegistry};
+pub use profiles::{ExtractionProfile, InfoboxField};
+pub use report::{MetadataProvenance, MetadataSource, ParseReport, ParseWarning, ReportEntry};
+pub use source_map::{DomSpan, SourceMapEntry};
+pub use text::{TextNormalization, TextNormalizationPreset};
+pub use memory::ReadabilityEngine;
+#[cfg(feature = "accounting")]
+pub use memory::ParseMetrics;
+#[cfg(feature = "accounting")]
+pub use metrics::MetricsRegistry;
+
 // Re-export specific functions to avoid naming conflicts
 pub use regexps::{
     is_unlikely_candidate, has_positive_indicators, has_negative_indicators,
     is_byline, is_video_url, is_whitespace, has_content, contains_ad_words, contains_loading_words,
     is_extraneous_content, is_share_element, is_next_link, is_prev_link, is_hash_url,
     is_b64_data_url, is_json_ld_article_type, replace_font_tags, normalize_whitespace,
-    tokenize_text, count_commas
+    tokenize_text, count_commas, count_commas_for_lang, is_correction_notice,
+    has_correction_class, is_recipe_list, is_live_update, is_slide, is_admonition,
+    count_prices, is_purchase_button, is_ad_container, is_recommendation_widget,
+    is_floating_bar, is_author_bio
 };
 
 pub use utils::{
     to_absolute_uri, is_url, get_inner_text, get_char_count, is_phrasing_content,
-    is_single_image, is_node_visible, has_ancestor_tag, get_node_ancestors,
+    is_single_image, is_node_visible, is_node_visible_for, is_fixed_position, has_ancestor_tag, is_data_table, get_node_ancestors,
     is_element_without_content, has_single_tag_inside_element, has_child_block_element,
     should_clean_attribute, extract_text_content, word_count, is_title_candidate,
-    unescape_html_entities, clean_text, get_link_density
+    unescape_html_entities, clean_text, get_link_density, uses_vertical_writing,
+    isolate_ltr_runs, escape_html, css_path, decode_lossy, strip_leading_garbage, is_html_file
 };
 
+/// Shortest visible text of an `<iframe srcdoc>` document to treat it as content
+const MIN_SRCDOC_CHARS: usize = 200;
+
 /// Errors that can occur during readability parsing
 #[derive(Error, Debug)]
 pub enum ReadabilityError {
@@ -64,6 +120,47 @@ pub enum ReadabilityError {
     NoContent,
     #[error("Parsing failed: {0}")]
     ParseError(String),
+    #[error("Not an article: {0}")]
+    NotAnArticle(NotArticleReason),
+    #[error("Memory limit exceeded: about {used} bytes needed, limit is {limit}")]
+    MemoryLimitExceeded { used: usize, limit: usize },
+    #[error("Unsupported input: {0}")]
+    UnsupportedInput(UnsupportedInputReason),
+}
+
+/// Why a page was recognized as something other than an article
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NotArticleReason {
+    /// A shop product or listing page
+    ProductPage,
+}
+
+impl std::fmt::Display for NotArticleReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProductPage => write!(f, "product page"),
+        }
+    }
+}
+
+/// Why a document can't be extracted as it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UnsupportedInputReason {
+    /// PDF-to-HTML converter output (pdf2htmlEX, `pdftohtml -c`) of absolutely
+    /// positioned words or lines; see `ReadabilityOptions::reconstruct_pdf_text`
+    PdfConversion,
+}
+
+impl std::fmt::Display for UnsupportedInputReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PdfConversion => write!(f, "PDF conversion output"),
+        }
+    }
 }
 
 /// Feature flags for controlling readability behavior
@@ -91,10 +188,14 @@ pub struct ReadabilityOptions {
     pub debug: bool,
     /// Maximum number of elements to parse (0 = no limit)
     pub max_elems_to_parse: usize,
-    /// Number of top candidates to consider
+    /// Number of top candidates to consider; when three of them score near
+    /// the best, their common ancestor with it becomes the content
     pub nb_top_candidates: usize,
     /// Minimum character threshold for content
-    pub char_threshold: usize,
+    pub char_threshold: CharThreshold,
+    /// Minimum content score for `is_probably_readerable` (`None` = scaled
+    /// with `char_threshold`)
+    pub readerable_min_score: Option<f64>,
     /// CSS classes to preserve during cleanup
     pub classes_to_preserve: Vec<String>,
     /// Whether to keep CSS classes
@@ -103,10 +204,86 @@ pub struct ReadabilityOptions {
     pub disable_json_ld: bool,
     /// Custom allowed video regex pattern
     pub allowed_video_regex: Option<Regex>,
-    /// Link density modifier
+    /// Added to the link density above which conditional cleaning drops an
+    /// element, as Readability.js's `linkDensityModifier` (positive keeps more links)
     pub link_density_modifier: f64,
     /// Feature flags for controlling algorithm behavior
     pub flags: ReadabilityFlags,
+    /// Whether to build the structured block model in `Article::blocks`
+    pub include_blocks: bool,
+    /// Whether to split the content into `Article::paragraphs`
+    pub include_paragraphs: bool,
+    /// Number of sentences in the extractive `Article::summary` (0 = disabled)
+    pub summary_sentences: usize,
+    /// Whether to populate `Article::recipe` from JSON-LD `Recipe` data
+    pub extract_recipe: bool,
+    /// Whether to split live-blog updates into `Article::live_updates`
+    pub extract_live_updates: bool,
+    /// Whether to stitch all slides of a slideshow/listicle into the content
+    pub stitch_slides: bool,
+    /// Site-specific cleanup profile (`None` = detect from the document)
+    pub profile: Option<ExtractionProfile>,
+    /// Whether to keep a MediaWiki infobox as `Article::infobox`
+    pub keep_infobox: bool,
+    /// Whether to collapse runs of paragraphs repeating earlier ones in the
+    /// same order (infinite-scroll snapshots); off by default, as songs and
+    /// poems repeat lines on purpose
+    pub collapse_duplicates: bool,
+    /// CSS selector bounding candidate scoring to the first matching subtree
+    pub scope_selector: Option<String>,
+    /// Number of ancestor levels above each paragraph that receive its score
+    pub max_candidate_depth: usize,
+    /// Whether to map each content block back to its source element in `Article::source_map`
+    pub include_source_map: bool,
+    /// Estimated peak memory budget in bytes (0 = no limit)
+    pub max_memory_bytes: usize,
+    /// Cleanups applied to the plain-text fields (`title`, `excerpt`,
+    /// `text_content`, `text_content_raw`)
+    pub text_normalization: TextNormalization,
+    /// Whether to clean up OCR artifacts in the text: words hyphenated across
+    /// line breaks, lines of a single stray character, and ligatures
+    pub clean_ocr_artifacts: bool,
+    /// Whether to rebuild the text flow of PDF-to-HTML converter output by
+    /// position instead of failing with `UnsupportedInputReason::PdfConversion`
+    pub reconstruct_pdf_text: bool,
+    /// Extraction algorithm; pin `V1Legacy` to keep output stable across upgrades
+    pub algorithm_version: AlgorithmVersion,
+    /// Whether to keep screen-reader-only text (`.sr-only`, `.visually-hidden`)
+    pub keep_sr_only: bool,
+    /// Handling of table-of-contents boxes (lists of `#hash` links)
+    pub toc_policy: TocPolicy,
+    /// Whether to score the contents of `<template>` elements, which are
+    /// otherwise inert and ignored as in browsers
+    pub lift_templates: bool,
+    /// Whether to take the article from a Next.js/Nuxt hydration payload when
+    /// the rendered page has too little text (requires the `hydration` feature)
+    pub extract_hydration: bool,
+    /// Experimental: clean like the document's print view (drop `.noprint` and
+    /// print-hidden rules, keep `.printonly`) when it has print hints
+    pub use_print_hints: bool,
+    /// Whether to serialize `Article::content` as well-formed XHTML (void
+    /// elements self-closed, no raw `&` or `<` in text), for XML pipelines
+    pub xhtml_output: bool,
+    /// Tags the content may use (e.g. the subset a target such as Telegram
+    /// Instant View accepts); others get `disallowed_tag_action`. `None`
+    /// allows every tag
+    pub allowed_tags: Option<Vec<String>>,
+    /// What to do with elements whose tag isn't in `allowed_tags`
+    pub disallowed_tag_action: DisallowedTagAction,
+    /// Whether `Article::excerpt_html` drops links, keeping their text
+    pub excerpt_strip_links: bool,
+    /// Longest `Article::excerpt` in characters, trimmed at a sentence or
+    /// word boundary (0 = no limit)
+    pub excerpt_max_chars: usize,
+    /// Whether to move an author bio box out of the content into `Article::author_bio`
+    pub extract_author_bio: bool,
+    /// Whether to fill `Article::tokens_estimate` for LLM context budgeting
+    pub estimate_tokens: bool,
+    /// Exact token counter (e.g. wrapping a tiktoken tokenizer) used instead
+    /// of the built-in estimate
+    pub token_counter: Option<fn(&str) -> usize>,
+    /// Third-party cleaners run on the content before the generic cleanup
+    pub cleaners: CleanerRegistry,
 }
 
 impl Default for ReadabilityOptions {
@@ -115,23 +292,206 @@ impl Default for ReadabilityOptions {
             debug: false,
             max_elems_to_parse: 0,
             nb_top_candidates: 5,
-            char_threshold: 25,  // Lowered from 500 to be more lenient for testing
+            char_threshold: CharThreshold::Chars(25),  // Lowered from 500 to be more lenient for testing
+            readerable_min_score: None,
             classes_to_preserve: Vec::new(),
             keep_classes: false,
             disable_json_ld: false,
             allowed_video_regex: None,
-            link_density_modifier: 1.0,
+            link_density_modifier: 0.0,
             flags: ReadabilityFlags::default(),
+            include_blocks: false,
+            include_paragraphs: false,
+            summary_sentences: 0,
+            extract_recipe: false,
+            extract_live_updates: false,
+            stitch_slides: true,
+            profile: None,
+            keep_infobox: false,
+            collapse_duplicates: false,
+            scope_selector: None,
+            max_candidate_depth: 2,
+            include_source_map: false,
+            max_memory_bytes: 0,
+            text_normalization: TextNormalization::default(),
+            clean_ocr_artifacts: false,
+            reconstruct_pdf_text: false,
+            algorithm_version: AlgorithmVersion::default(),
+            keep_sr_only: false,
+            toc_policy: TocPolicy::default(),
+            lift_templates: false,
+            extract_hydration: false,
+            use_print_hints: false,
+            allowed_tags: None,
+            disallowed_tag_action: DisallowedTagAction::default(),
+            xhtml_output: false,
+            excerpt_strip_links: false,
+            excerpt_max_chars: 200,
+            extract_author_bio: false,
+            estimate_tokens: false,
+            token_counter: None,
+            cleaners: CleanerRegistry::default(),
+        }
+    }
+}
+
+/// Version of the extraction algorithm, recorded in `Article::algorithm_version`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AlgorithmVersion {
+    /// The original port; output stays byte-identical to earlier releases
+    #[default]
+    V1Legacy,
+    /// Output shaped like Mozilla's Readability.js, e.g. content wrapped in
+    /// `<div id="readability-page-1" class="page">`
+    V2MozillaParity,
+}
+
+/// Minimum length of extracted content, in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CharThreshold {
+    /// The same minimum for every document
+    Chars(usize),
+    /// A minimum for the document's language or script: 500 characters for
+    /// alphabetic scripts, 150 for Chinese, Japanese and Korean
+    Auto,
+}
+
+impl CharThreshold {
+    /// The minimum for a document in `lang` (a language tag)
+    pub fn resolve(self, lang: Option<&str>) -> usize {
+        match self {
+            Self::Chars(chars) => chars,
+            Self::Auto => match analysis::primary_language(lang).as_deref() {
+                Some("zh" | "ja" | "ko") => 150,
+                _ => 500,
+            },
+        }
+    }
+}
+
+impl Default for CharThreshold {
+    fn default() -> Self {
+        Self::Chars(25)
+    }
+}
+
+impl From<usize> for CharThreshold {
+    fn from(chars: usize) -> Self {
+        Self::Chars(chars)
+    }
+}
+
+/// What to do with in-article table-of-contents boxes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TocPolicy {
+    /// Drop them, as reader views do
+    #[default]
+    Remove,
+    /// Leave them as they are
+    Keep,
+    /// Replace them with a list built from the content's headings
+    Rebuild,
+}
+
+/// What to do with elements whose tag isn't in `allowed_tags`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DisallowedTagAction {
+    /// Replace them with their children
+    #[default]
+    Unwrap,
+    /// Drop them with their children
+    Drop,
+}
+
+/// An article author with optional profile link and avatar
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Author {
+    pub name: String,
+    pub url: Option<String>,
+    pub image: Option<String>,
+}
+
+/// Preparation times of a recipe, as ISO 8601 durations
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecipeTimes {
+    pub prep: Option<String>,
+    pub cook: Option<String>,
+    pub total: Option<String>,
+}
+
+/// Structured recipe data extracted alongside the article
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Recipe {
+    pub name: Option<String>,
+    pub ingredients: Vec<String>,
+    pub instructions: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "yield"))]
+    pub recipe_yield: Option<String>,
+    pub times: RecipeTimes,
+}
+
+/// A single timestamped entry of a live blog
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LiveUpdate {
+    pub time: Option<String>,
+    pub html: String,
+    pub text: String,
+}
+
+/// Broad category of an extracted page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ArticleKind {
+    News,
+    Blog,
+    Recipe,
+    Forum,
+    Docs,
+    /// A homepage or section front listing teasers rather than one article
+    IndexPage,
+}
+
+impl ArticleKind {
+    /// Map a schema.org `@type` (or microdata `itemtype`) to an article kind
+    pub fn from_schema_type(schema_type: &str) -> Option<Self> {
+        let schema_type = schema_type.rsplit('/').next().unwrap_or(schema_type);
+        match schema_type {
+            "NewsArticle" | "AnalysisNewsArticle" | "AskPublicNewsArticle" | "BackgroundNewsArticle"
+            | "OpinionNewsArticle" | "ReportageNewsArticle" | "ReviewNewsArticle" | "LiveBlogPosting" => {
+                Some(Self::News)
+            }
+            "BlogPosting" | "Blog" => Some(Self::Blog),
+            "Recipe" => Some(Self::Recipe),
+            "DiscussionForumPosting" | "QAPage" | "Question" => Some(Self::Forum),
+            "TechArticle" | "APIReference" => Some(Self::Docs),
+            _ => None,
         }
     }
 }
 
 /// Represents an extracted article
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Article {
     pub title: Option<String>,
     pub content: Option<String>,
     pub text_content: Option<String>,
+    /// Plain text keeping its structure: blank lines between blocks, list items on lines of their own
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub text_content_raw: Option<String>,
     pub length: Option<usize>,
     pub excerpt: Option<String>,
     pub byline: Option<String>,
@@ -139,8 +499,132 @@ pub struct Article {
     pub site_name: Option<String>,
     pub lang: Option<String>,
     pub published_time: Option<String>,
+    /// `og:image` and `twitter:image` declarations with their sizes, in document order
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub images: Vec<ImageCandidate>,
+    /// Favicons and touch icons with the largest of their declared `sizes`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub site_icons: Vec<ImageCandidate>,
     // Add readerable field to match JavaScript output
     pub readerable: Option<bool>,
+    /// Structured block model of the content, when `include_blocks` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub blocks: Option<ArticleBlocks>,
+    /// Headings and paragraphs of the content with their HTML, when `include_paragraphs` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub paragraphs: Vec<Paragraph>,
+    /// Extractive summary, when `summary_sentences` is non-zero
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub summary: Option<String>,
+    /// Whether the source laid out text vertically via CSS `writing-mode`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub vertical_text: bool,
+    /// Whether the content carries `<ins>`/`<del>` edits or a correction notice
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub has_corrections: bool,
+    /// Structured authors with profile links and avatars, when known
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub authors: Vec<Author>,
+    /// Author bio taken out of the content, when `extract_author_bio` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub author_bio: Option<String>,
+    /// Inferred page category, when the signals are clear enough
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub kind: Option<ArticleKind>,
+    /// Structured recipe, when `extract_recipe` is set and the page has one
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub recipe: Option<Recipe>,
+    /// Live-blog updates in document order, when `extract_live_updates` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub live_updates: Vec<LiveUpdate>,
+    /// Infobox rows of a MediaWiki page, when `keep_infobox` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub infobox: Vec<InfoboxField>,
+    /// Block index to source element mapping, when `include_source_map` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub source_map: Vec<SourceMapEntry>,
+    /// Algorithm that produced this article
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub algorithm_version: AlgorithmVersion,
+    /// Text and `datetime` attribute of each `<time>` element in the content
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub times: Vec<(String, Option<String>)>,
+    /// Hex SHA-256 of `content`
+    pub content_hash: Option<String>,
+    /// Hex SHA-256 of `text_content` with whitespace collapsed, stable across markup-only changes
+    pub text_hash: Option<String>,
+    /// Invalid UTF-8 sequences replaced with U+FFFD when parsing from bytes
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_zero"))]
+    pub decoding_errors: usize,
+    /// Opening paragraphs of `content` with their inline formatting, for preview cards
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub excerpt_html: Option<String>,
+    /// Tokens in `text_content`, when `estimate_tokens` is set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub tokens_estimate: Option<usize>,
+    /// Non-fatal data-quality issues found while extracting
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub warnings: Vec<ParseWarning>,
+}
+
+#[cfg(feature = "serde")]
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+impl Article {
+    /// Split `text_content` into sentences, honoring abbreviations for `lang`
+    pub fn sentences(&self) -> Vec<analysis::Sentence<'_>> {
+        let abbreviations = analysis::abbreviations_for(self.lang.as_deref());
+        analysis::split_sentences(self.text_content.as_deref().unwrap_or(""), abbreviations)
+    }
+
+    /// Map a byte range of `text_content` to the text nodes of `content` it covers,
+    /// e.g. to highlight a quote or anchor an annotation
+    pub fn locate(&self, range: std::ops::Range<usize>) -> Vec<DomSpan> {
+        match (&self.content, &self.text_content) {
+            (Some(content), Some(text_content)) => source_map::locate(content, text_content, range),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Split the content into chunks of at most `max_chars` characters for
+    /// retrieval pipelines, overlapping by up to `overlap` characters
+    ///
+    /// Chunks end on sentence boundaries and headings stay attached to their
+    /// sections; see [`Chunk`]. Uses `paragraphs` when they were collected.
+    pub fn chunks(&self, max_chars: usize, overlap: usize) -> Vec<Chunk> {
+        let abbreviations = analysis::abbreviations_for(self.lang.as_deref());
+        if !self.paragraphs.is_empty() {
+            return blocks::chunk_paragraphs(&self.paragraphs, max_chars, overlap, abbreviations);
+        }
+        let paragraphs = Paragraph::from_content(self.content.as_deref().unwrap_or(""));
+        blocks::chunk_paragraphs(&paragraphs, max_chars, overlap, abbreviations)
+    }
+
+    /// `text_content` with `normalization` applied, e.g. a
+    /// `TextNormalizationPreset::SearchIndex` copy for indexing next to the
+    /// text shown to readers
+    pub fn normalized_text(&self, normalization: impl Into<TextNormalization>) -> String {
+        normalization.into().apply(self.text_content.as_deref().unwrap_or(""))
+    }
+
+    /// Reading-level scores for `text_content`, chosen by `lang`
+    pub fn reading_level(&self) -> Vec<analysis::ReadingLevel> {
+        analysis::reading_level(self.text_content.as_deref().unwrap_or(""), self.lang.as_deref())
+    }
+}
+
+/// Where the main content lives in the source document
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContentLocation {
+    /// CSS selector path of the top candidate element
+    pub css_path: String,
+    /// Length of the candidate's normalized text, in characters
+    pub char_len: usize,
+    /// Content score of the candidate (0 when a fallback selector was used)
+    pub score: f64,
 }
 
 /// The main Readability parser
@@ -148,44 +632,229 @@ pub struct Readability {
     document: Html,
     options: ReadabilityOptions,
     base_uri: Option<String>,
+    /// `<base href>` resolved against `base_uri`, or else `base_uri`
+    effective_base_uri: Option<String>,
     article_title: Option<String>,
     article_byline: Option<String>,
     article_dir: Option<String>,
     article_site_name: Option<String>,
     metadata: HashMap<String, String>,
+    json_ld: JsonLdMetadata,
+    profile: ExtractionProfile,
+    report: ParseReport,
+    peak_bytes: usize,
+    print_hints: Option<PrintHints>,
+    decoding_errors: usize,
+    warnings: Vec<ParseWarning>,
+    /// `options.flags` as relaxed by the retries of the current parse
+    flags: ReadabilityFlags,
 }
 
 impl Readability {
     /// Create a new Readability parser from HTML content
     pub fn new(html: &str, options: Option<ReadabilityOptions>) -> Result<Self, ReadabilityError> {
-        let document = Html::parse_document(html);
+        let document = Html::parse_document(strip_leading_garbage(html));
         let options = options.unwrap_or_default();
+        let effective_base_uri = effective_base_uri(&document, None);
+        let flags = options.flags;
         
         Ok(Self {
             document,
             options,
             base_uri: None,
+            effective_base_uri,
             article_title: None,
             article_byline: None,
             article_dir: None,
             article_site_name: None,
             metadata: HashMap::new(),
+            json_ld: JsonLdMetadata::default(),
+            profile: ExtractionProfile::Generic,
+            report: ParseReport::default(),
+            peak_bytes: 0,
+            print_hints: None,
+            decoding_errors: 0,
+            warnings: Vec::new(),
+            flags,
         })
     }
 
+    /// Create a parser from raw bytes, replacing invalid UTF-8 sequences
+    ///
+    /// Pages in legacy encodings such as windows-1251 or GBK decode lossily
+    /// instead of failing; the number of replaced sequences is reported in
+    /// `Article::decoding_errors`.
+    pub fn from_bytes(bytes: &[u8], options: Option<ReadabilityOptions>) -> Result<Self, ReadabilityError> {
+        let (html, decoding_errors) = decode_lossy(bytes);
+        let mut parser = Self::new(&html, options)?;
+        parser.decoding_errors = decoding_errors;
+        Ok(parser)
+    }
+
+    /// Like [`Readability::from_bytes`], with a base URI for resolving relative URLs
+    pub fn from_bytes_with_base_uri(bytes: &[u8], base_uri: &str, options: Option<ReadabilityOptions>) -> Result<Self, ReadabilityError> {
+        let mut parser = Self::from_bytes(bytes, options)?;
+        parser.set_base_uri(base_uri);
+        Ok(parser)
+    }
+
     /// Create a new Readability parser with a base URI for resolving relative URLs
     pub fn new_with_base_uri(html: &str, base_uri: &str, options: Option<ReadabilityOptions>) -> Result<Self, ReadabilityError> {
         let mut parser = Self::new(html, options)?;
-        parser.base_uri = Some(base_uri.to_string());
+        parser.set_base_uri(base_uri);
         Ok(parser)
     }
 
+    fn set_base_uri(&mut self, base_uri: &str) {
+        self.base_uri = Some(base_uri.to_string());
+        self.effective_base_uri = effective_base_uri(&self.document, Some(base_uri));
+    }
+
+    /// The URI relative URLs are resolved against: the document's first
+    /// `<base href>`, resolved against the base URI given to the parser, or
+    /// else that base URI, as in browsers
+    ///
+    /// A relative `<base href>` without a base URI resolves to nothing.
+    pub fn effective_base_uri(&self) -> Option<&str> {
+        self.effective_base_uri.as_deref()
+    }
+
     /// Parse the document and extract the main article content
     pub fn parse(&mut self) -> Option<Article> {
+        self.parse_result().ok()
+    }
+
+    /// Parse the document, reporting why no article could be extracted
+    pub fn parse_result(&mut self) -> Result<Article, ReadabilityError> {
+        self.reset();
+        // Preparation edits the document in place; the next parse starts from the source again
+        let source = self.document.clone();
+        let result = self.parse_document();
+        self.document = source;
+        result
+    }
+
+    fn parse_document(&mut self) -> Result<Article, ReadabilityError> {
+        if let Some(scope) = &self.options.scope_selector {
+            if Selector::parse(scope).is_err() {
+                return Err(ReadabilityError::ParseError(format!("invalid scope_selector `{}`", scope)));
+            }
+        }
+        if is_product_page(&self.document, !self.options.disable_json_ld) {
+            if self.options.debug {
+                println!("Skipping product page");
+            }
+            return Err(ReadabilityError::NotAnArticle(NotArticleReason::ProductPage));
+        }
+        if pdf::is_pdf_conversion(&self.document) {
+            if !self.options.reconstruct_pdf_text {
+                return Err(ReadabilityError::UnsupportedInput(UnsupportedInputReason::PdfConversion));
+            }
+            if self.options.debug {
+                println!("Rebuilding the text flow of PDF conversion output");
+            }
+            self.document = pdf::reconstruct(&self.document);
+        }
+
+        let document_bytes = memory::estimate_document_bytes(&self.document);
+        self.record_memory(document_bytes)?;
+        let article = self.extract_article();
+        // Extraction stops early once a retry goes over the budget
+        self.check_memory()?;
+        let article = article.ok_or(ReadabilityError::NoContent)?;
+        self.record_memory(document_bytes + memory::estimate_article_bytes(&article))?;
+        Ok(article)
+    }
+
+    /// Whether the document is likely to be readerable, as the free
+    /// [`is_probably_readerable`] judges it with this parser's options,
+    /// without parsing the HTML a second time
+    pub fn is_probably_readerable(&self) -> bool {
+        document_is_probably_readerable(&self.document, &self.options)
+    }
+
+    /// Track estimated memory use, failing once it exceeds `max_memory_bytes`
+    fn record_memory(&mut self, bytes: usize) -> Result<(), ReadabilityError> {
+        self.peak_bytes = self.peak_bytes.max(bytes);
+        self.check_memory()
+    }
+
+    /// Fail if the peak so far exceeds `max_memory_bytes`
+    fn check_memory(&self) -> Result<(), ReadabilityError> {
+        let limit = self.options.max_memory_bytes;
+        if limit > 0 && self.peak_bytes > limit {
+            return Err(ReadabilityError::MemoryLimitExceeded { used: self.peak_bytes, limit });
+        }
+        Ok(())
+    }
+
+    /// Resource usage of the most recent parse
+    #[cfg(feature = "accounting")]
+    pub fn metrics(&self) -> ParseMetrics {
+        ParseMetrics { peak_bytes: self.peak_bytes }
+    }
+
+    /// Replace the children of the first element matching `selector` with `html`
+    ///
+    /// Only the patch is parsed; the rest of the document tree is reused, so a
+    /// live preview can call this and then `parse()` again on every edit. The
+    /// tree is rebuilt once replaced nodes make up most of it.
+    pub fn apply_patch(&mut self, selector: &str, html: &str) -> Result<(), ReadabilityError> {
+        let selector = Selector::parse(selector)
+            .map_err(|_| ReadabilityError::ParseError(format!("invalid patch selector `{}`", selector)))?;
+        let target = self.document.select(&selector).next()
+            .ok_or(ReadabilityError::ParseError("patch target not found".to_string()))?
+            .id();
+
+        let old_children: Vec<_> = self.document.tree.get(target)
+            .map(|node| node.children().map(|child| child.id()).collect())
+            .unwrap_or_default();
+        for child in old_children {
+            if let Some(mut child) = self.document.tree.get_mut(child) {
+                child.detach();
+            }
+        }
+
+        let fragment = Html::parse_fragment(html);
+        for child in fragment.root_element().children() {
+            append_subtree(&mut self.document.tree, target, child);
+        }
+
+        // Detached nodes stay in the tree's arena until it is rebuilt
+        if self.document.tree.nodes().count() > 2 * self.document.tree.root().descendants().count() {
+            self.document = compact(&self.document);
+        }
+        Ok(())
+    }
+
+    /// Clear everything a previous parse gathered, so the document can be parsed again
+    fn reset(&mut self) {
+        self.article_title = None;
+        self.article_byline = None;
+        self.article_dir = None;
+        self.article_site_name = None;
+        self.metadata.clear();
+        self.json_ld = JsonLdMetadata::default();
+        self.report = ParseReport::default();
+        self.peak_bytes = 0;
+        self.print_hints = None;
+        self.warnings.clear();
+        self.flags = self.options.flags;
+    }
+
+    /// Parse the document and also return a report of what the parser did
+    pub fn parse_with_report(&mut self) -> (Result<Article, ReadabilityError>, ParseReport) {
+        let result = self.parse_result();
+        (result, std::mem::take(&mut self.report))
+    }
+
+    fn extract_article(&mut self) -> Option<Article> {
         if self.options.debug {
             println!("Starting readability parsing...");
         }
 
+        self.profile = self.options.profile.unwrap_or_else(|| ExtractionProfile::detect(&self.document));
+
         // Unwrap noscript images first
         self.unwrap_noscript_images();
         
@@ -194,6 +863,12 @@ impl Readability {
             self.extract_json_ld_metadata();
         }
 
+        // Read stylesheets before they are removed
+        if self.options.use_print_hints {
+            self.print_hints = PrintHints::from_document(&self.document);
+        }
+        let vertical_text = self.detect_vertical_writing();
+
         // Remove script tags
         self.remove_scripts();
         
@@ -207,14 +882,34 @@ impl Readability {
         self.get_article_title();
 
         // Store values we need before borrowing
-        let char_threshold = self.options.char_threshold;
+        let char_threshold = self.options.char_threshold.resolve(self.scoring_language().as_deref());
+
+        // Client-rendered apps may ship the article only in their hydration payload
+        if self.options.extract_hydration && rendered_text_length(&self.document) < char_threshold {
+            self.lift_hydration_content();
+        }
         let debug = self.options.debug;
-        let has_description = self.metadata.get("description").is_some();
+        let has_description = self.metadata.contains_key("description");
         let description = self.metadata.get("description").cloned();
+        let extract_live_updates = self.options.extract_live_updates;
+        let stitch_slides = self.options.stitch_slides;
 
         // Try to grab the article content
         let article_content = self.grab_article()?;
-        let raw_content_html = article_content.inner_html();
+        let article_content = if extract_live_updates {
+            widen_past(article_content, is_live_update_element)
+        } else {
+            article_content
+        };
+        let article_content = if stitch_slides {
+            widen_past(article_content, is_slide_element)
+        } else {
+            article_content
+        };
+        let article_content = unwrap_lone_wrappers(article_content);
+        let content_path = css_path(&article_content);
+        let article_id = article_content.id();
+        let mut raw_content_html = article_content.inner_html();
         let text_content = get_inner_text(&article_content, true);
         
         // Extract excerpt if not already present (before cleaning)
@@ -229,37 +924,248 @@ impl Readability {
             description
         };
         
-        let content_html = self.clean_article_content(&raw_content_html);
+        // Split live-blog updates out so the main content is just the intro
+        let full_text_length = text_content.chars().count();
+        let live_updates = if extract_live_updates {
+            self.collect_live_updates()
+        } else {
+            Vec::new()
+        };
+        let dropped_updates: Vec<ego_tree::NodeId> = live_updates.iter()
+            .filter_map(|(id, _)| *id)
+            .filter(|&id| {
+                self.document.tree.get(id).is_some_and(|node| node.ancestors().any(|ancestor| ancestor.id() == article_id))
+            })
+            .collect();
+        // A slide wrapping the article can't be moved into it
+        let slides: Vec<ego_tree::NodeId> = if stitch_slides {
+            self.collect_slides().into_iter()
+                .filter(|&slide| {
+                    self.document.tree.get(article_id).is_some_and(|article| article.id() != slide && !article.ancestors().any(|ancestor| ancestor.id() == slide))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let live_updates: Vec<LiveUpdate> = live_updates.into_iter().map(|(_, update)| update).collect();
+
+        // Work on a copy (node ids carry over) so the prepared document stays intact
+        if !dropped_updates.is_empty() || !slides.is_empty() {
+            let mut working = self.document.clone();
+            dom::remove(&mut working, &dropped_updates);
+
+            // Stitch all slideshow panels, in slide order, where the first one was
+            let slides: Vec<ego_tree::NodeId> = slides.into_iter()
+                .filter(|&slide| working.tree.get(slide).is_some_and(|node| node.parent().is_some()))
+                .collect();
+            if !slides.is_empty() {
+                let anchor = working.tree.get(article_id)
+                    .and_then(|article| article.descendants().map(|node| node.id()).find(|id| slides.contains(id)));
+                let holder = working.tree.orphan(scraper::Node::Element(dom::new_element("div"))).id();
+                match anchor.and_then(|anchor| working.tree.get_mut(anchor)) {
+                    Some(mut anchor) => { anchor.insert_id_before(holder); }
+                    None => {
+                        if let Some(mut article) = working.tree.get_mut(article_id) {
+                            article.append_id(holder);
+                        }
+                    }
+                }
+                if let Some(mut holder) = working.tree.get_mut(holder) {
+                    for &slide in &slides {
+                        holder.append_id(slide);
+                    }
+                }
+                dom::unwrap(&mut working, holder);
+            }
+
+            if let Some(article) = working.tree.get(article_id).and_then(ElementRef::wrap) {
+                raw_content_html = article.inner_html();
+            }
+        }
+
+        // Drop content hidden by styles, attributes or utility classes (or from printouts)
+        let print_hints = self.print_hints.as_ref();
+        if let Some(visible) = remove_hidden_elements(&raw_content_html, self.options.keep_sr_only, stitch_slides, print_hints) {
+            raw_content_html = visible;
+        }
+
+        // Drop ad slots, recommendation widgets and floating share bars interleaved with the paragraphs
+        let is_injected = |element: &ElementRef| {
+            !in_data_table(element)
+                && (is_inline_ad(element) || is_recommendation_widget_element(element) || is_floating_element(element))
+        };
+        if let Some(content) = remove_elements(&raw_content_html, is_injected) {
+            raw_content_html = content;
+        }
+
+        if let Some(content) = apply_toc_policy(&raw_content_html, self.options.toc_policy) {
+            raw_content_html = content;
+        }
+
+        // Apply site-specific cleanup before the generic pass
+        let profile = self.profile;
+        if profile == ExtractionProfile::MediaWiki {
+            raw_content_html = profiles::clean_mediawiki(&raw_content_html, self.effective_base_uri.as_deref());
+        }
+        if !self.options.cleaners.is_empty() {
+            let context = plugins::Context {
+                url: self.base_uri.as_deref(),
+                profile,
+                title: self.article_title.as_deref(),
+                site_name: self.article_site_name.as_deref(),
+                document: &self.document,
+            };
+            raw_content_html = self.options.cleaners.clean(&raw_content_html, &context);
+        }
+        let infobox = if profile == ExtractionProfile::MediaWiki && self.options.keep_infobox {
+            profiles::mediawiki_infobox(&self.document)
+        } else {
+            Vec::new()
+        };
+
+        let mut content_html = self.clean_article_content(&raw_content_html);
+        if let Some(content) = fix_relative_uris(&content_html, self.effective_base_uri.as_deref(), self.base_uri.as_deref()) {
+            content_html = content;
+        }
+        // Text of what survived cleaning, so dropped boilerplate doesn't count
+        let mut text_content = get_inner_text(&Html::parse_fragment(&content_html).root_element(), true);
+        let recipe = if self.options.extract_recipe { self.json_ld.recipe.clone() } else { None };
+        if recipe.is_some() {
+            self.append_missing_recipe_lists(&mut content_html, &text_content);
+            text_content = get_inner_text(&Html::parse_fragment(&content_html).root_element(), true);
+        }
+        if self.options.collapse_duplicates {
+            let (collapsed, removed) = collapse_repeated_blocks(&content_html);
+            if removed > 0 {
+                if debug {
+                    println!("Collapsed {} repeated blocks", removed);
+                }
+                content_html = collapsed;
+                text_content = get_inner_text(&Html::parse_fragment(&content_html).root_element(), true);
+                self.report.push(ReportEntry::DuplicateBlocksCollapsed { removed });
+            }
+        }
+        let authors = self.collect_authors();
+        let author_bio = if self.options.extract_author_bio {
+            let mut names: Vec<&str> = authors.iter().map(|author| author.name.as_str()).collect();
+            names.extend(self.article_byline.as_deref());
+            split_author_bio(&content_html, &names).map(|(content, bio)| {
+                if debug {
+                    println!("Moved author bio out of the content");
+                }
+                content_html = content;
+                text_content = get_inner_text(&Html::parse_fragment(&content_html).root_element(), true);
+                bio
+            })
+        } else {
+            None
+        };
+        let normalization = self.options.text_normalization;
+        let text_content = normalization.apply(&text_content);
         let text_length = text_content.len();
+        let include_blocks = self.options.include_blocks;
 
         // Check if content meets minimum requirements
-        if text_length < char_threshold {
+        if full_text_length < char_threshold {
             if debug {
-                println!("Content too short: {} chars (minimum: {})", text_length, char_threshold);
+                println!("Content too short: {} chars (minimum: {})", full_text_length, char_threshold);
             }
             return None;
         }
 
+        let content_blocks = ArticleBlocks::from_html(&content_html);
+        let text_content_raw = Some(normalization.apply(&content_blocks.to_text()));
+        let blocks = include_blocks.then(|| content_blocks.clone());
+        let paragraphs = if self.options.include_paragraphs {
+            Paragraph::from_content(&content_html)
+        } else {
+            Vec::new()
+        };
+        let source_map = if self.options.include_source_map {
+            self.build_source_map(&content_path, content_blocks)
+        } else {
+            Vec::new()
+        };
+        let lang = self.metadata.get("lang").cloned();
+        let summary = analysis::summarize(&text_content, lang.as_deref(), self.options.summary_sentences);
+        let has_corrections = detect_corrections(&content_html);
+        let kind = self.detect_article_kind();
+        let algorithm_version = self.options.algorithm_version;
+        let content_html = match algorithm_version {
+            AlgorithmVersion::V1Legacy => content_html,
+            AlgorithmVersion::V2MozillaParity => {
+                format!(r#"<div id="readability-page-1" class="page">{}</div>"#, content_html)
+            }
+        };
+        let content_html = match &self.options.allowed_tags {
+            Some(allowed_tags) => {
+                apply_allowed_tags(&content_html, allowed_tags, self.options.disallowed_tag_action).unwrap_or(content_html)
+            }
+            None => content_html,
+        };
+        let content_html = if self.options.xhtml_output { format::xhtml_fragment(&content_html) } else { content_html };
+
+        if !has_description && excerpt.is_some() {
+            self.report.provenance.record("excerpt", MetadataSource::FirstParagraph);
+        }
+        let times = collect_times(&content_html);
+        let excerpt_html = excerpt_html(&content_html, self.options.excerpt_strip_links);
+        let count_tokens = self.options.token_counter.unwrap_or(analysis::estimate_tokens);
+        let tokens_estimate = self.options.estimate_tokens.then(|| count_tokens(&text_content));
+        let lazy_images = count_lazy_images(&content_html);
+        if lazy_images > 0 {
+            self.warnings.push(ParseWarning::LazyImagesUnresolved { count: lazy_images });
+        }
+        if let Some(value) = self.metadata.get("publishedTime").filter(|value| is_ambiguous_date(value)) {
+            self.warnings.push(ParseWarning::AmbiguousDate { value: value.clone() });
+        }
+        let base_uri = self.effective_base_uri.clone();
+        let content_hash = Some(utils::sha256_hex(&content_html));
+        let text_hash = Some(utils::sha256_hex(utils::normalize_whitespace(&text_content).trim()));
+
         Some(Article {
-            title: self.article_title.clone(),
+            title: self.article_title.as_deref().map(|title| normalization.apply(title)),
             content: Some(content_html),
             text_content: Some(text_content),
+            text_content_raw,
             length: Some(text_length),
-            excerpt,
+            excerpt: excerpt.map(|excerpt| analysis::truncate_excerpt(&normalization.apply(&excerpt), self.options.excerpt_max_chars, lang.as_deref())),
             byline: self.article_byline.clone(),
             dir: self.article_dir.clone(),
             site_name: self.article_site_name.clone(),
-            lang: self.metadata.get("lang").cloned(),
+            lang,
             published_time: self.metadata.get("publishedTime").cloned(),
+            images: images::share_images(&self.document, base_uri.as_deref()),
+            site_icons: images::site_icons(&self.document, base_uri.as_deref()),
             readerable: Some(true), // If we got here, it's readerable
+            blocks,
+            paragraphs,
+            summary,
+            vertical_text,
+            has_corrections,
+            authors,
+            author_bio,
+            kind,
+            recipe,
+            live_updates,
+            infobox,
+            source_map,
+            algorithm_version,
+            times,
+            content_hash,
+            text_hash,
+            decoding_errors: self.decoding_errors,
+            excerpt_html,
+            tokens_estimate,
+            warnings: std::mem::take(&mut self.warnings),
         })
     }
 
 
 
     fn remove_scripts(&mut self) {
-        // This would require mutable DOM manipulation
-        // For now, we'll handle this in the HTML preprocessing
+        self.remove_nodes_by_tag("script");
+        self.remove_nodes_by_tag("noscript");
     }
 
 
@@ -275,9 +1181,13 @@ impl Readability {
                     
                     // Handle specific Open Graph properties
                     match property {
-                        "og:site_name" => self.article_site_name = Some(content.to_string()),
+                        "og:site_name" => {
+                            self.article_site_name = Some(content.to_string());
+                            self.report.provenance.record("site_name", MetadataSource::OpenGraph);
+                        }
                         "article:published_time" => {
                             self.metadata.insert("publishedTime".to_string(), content.to_string());
+                            self.report.provenance.record("published_time", MetadataSource::OpenGraph);
                         },
                         _ => {}
                     }
@@ -289,7 +1199,11 @@ impl Readability {
                     
                     // Handle specific meta name properties
                     match name {
-                        "author" => self.article_byline = Some(content.to_string()),
+                        "author" => {
+                            self.article_byline = Some(content.to_string());
+                            self.report.provenance.record("byline", MetadataSource::MetaTag);
+                        }
+                        "description" => self.report.provenance.record("excerpt", MetadataSource::MetaTag),
                         _ => {}
                     }
                 }
@@ -298,12 +1212,58 @@ impl Readability {
 
         // Extract byline from DOM elements
         self.extract_byline_from_dom();
+
+        // Fall back to JSON-LD for anything the document markup didn't provide
+        if self.article_byline.is_none() && self.json_ld.byline.is_some() {
+            self.article_byline = self.json_ld.byline.clone();
+            self.report.provenance.record("byline", MetadataSource::JsonLd);
+        }
+        if self.article_site_name.is_none() && self.json_ld.site_name.is_some() {
+            self.article_site_name = self.json_ld.site_name.clone();
+            self.report.provenance.record("site_name", MetadataSource::JsonLd);
+        }
+        if let Some(published_time) = &self.json_ld.published_time {
+            if !self.metadata.contains_key("publishedTime") {
+                self.metadata.insert("publishedTime".to_string(), published_time.clone());
+                self.report.provenance.record("published_time", MetadataSource::JsonLd);
+            }
+        }
+        // Then to the first machine-readable date in the page
+        if !self.metadata.contains_key("publishedTime") {
+            let time_selector = Selector::parse("time[datetime]").unwrap();
+            let datetime = self.document.select(&time_selector)
+                .filter_map(|time| time.value().attr("datetime"))
+                .map(str::trim)
+                .find(|datetime| !datetime.is_empty());
+            if let Some(datetime) = datetime {
+                self.metadata.insert("publishedTime".to_string(), datetime.to_string());
+                self.report.provenance.record("published_time", MetadataSource::TimeElement);
+            }
+        }
+        if let Some(excerpt) = &self.json_ld.excerpt {
+            if !self.metadata.contains_key("description") {
+                self.metadata.insert("description".to_string(), excerpt.clone());
+                self.report.provenance.record("excerpt", MetadataSource::JsonLd);
+            }
+        }
         
         // Extract language from html element
         if let Ok(html_selector) = Selector::parse("html") {
             if let Some(html_element) = self.document.select(&html_selector).next() {
                 if let Some(lang) = html_element.value().attr("lang") {
                     self.metadata.insert("lang".to_string(), lang.to_string());
+                    self.report.provenance.record("lang", MetadataSource::HtmlAttribute);
+                }
+            }
+        }
+
+        // Extract text direction from the html or body element
+        if let Ok(dir_selector) = Selector::parse("html[dir], body[dir]") {
+            if let Some(element) = self.document.select(&dir_selector).next() {
+                let dir = element.value().attr("dir").unwrap_or("").trim().to_lowercase();
+                if matches!(dir.as_str(), "rtl" | "ltr" | "auto") {
+                    self.article_dir = Some(dir);
+                    self.report.provenance.record("dir", MetadataSource::HtmlAttribute);
                 }
             }
         }
@@ -324,12 +1284,18 @@ impl Readability {
             "[rel=\"author\"]",
             ".by-author",
             ".writer",
+            // Per HTML, an article's <address> holds its author's contact details
+            "article header address",
         ];
 
         for selector_str in &byline_selectors {
             if let Ok(selector) = Selector::parse(selector_str) {
                 if let Some(element) = self.document.select(&selector).next() {
-                    let byline_text = self.get_inner_text_from_ref(&element, false);
+                    let byline_text = if element.value().name() == "address" {
+                        address_byline(&element)
+                    } else {
+                        self.get_inner_text_from_ref(&element, false)
+                    };
                     let cleaned_byline = byline_text.trim();
                     
                     // Clean up common prefixes
@@ -339,21 +1305,254 @@ impl Readability {
                         .or_else(|| cleaned_byline.strip_prefix("BY "))
                         .or_else(|| cleaned_byline.strip_prefix("Author: "))
                         .or_else(|| cleaned_byline.strip_prefix("Written by "))
-                        .unwrap_or(cleaned_byline);
+                        .unwrap_or(cleaned_byline)
+                        .trim();
 
                     if !cleaned_byline.is_empty() && cleaned_byline.len() < 100 {
                         self.article_byline = Some(cleaned_byline.to_string());
+                        self.report.provenance.record("byline", MetadataSource::BylineElement);
                         break;
                     }
+                    if cleaned_byline.len() >= 100 {
+                        self.warnings.push(ParseWarning::BylineDiscarded { length: cleaned_byline.len() });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Find slideshow/listicle slides in slide order
+    ///
+    /// Slides are marked by `data-slide` attributes or slide-like class/id
+    /// names. At least two sibling slides with an image or text are required.
+    fn collect_slides(&self) -> Vec<ego_tree::NodeId> {
+        let candidate_selector = Selector::parse("[data-slide], [data-slide-index], [class], [id]").unwrap();
+        let image_selector = Selector::parse("img, picture").unwrap();
+        let mut slides: Vec<(usize, ElementRef)> = Vec::new();
+
+        for element in self.document.select(&candidate_selector) {
+            let index = element.value().attr("data-slide")
+                .or_else(|| element.value().attr("data-slide-index"))
+                .and_then(|index| index.trim().parse::<usize>().ok());
+            if !is_slide_element(&element) || slides.iter().any(|(_, slide)| element.ancestors().any(|a| a.id() == slide.id())) {
+                continue;
+            }
+
+            let in_unlikely_container = element.ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|ancestor| self.is_unlikely_candidate(&ancestor));
+            let has_content = element.select(&image_selector).next().is_some()
+                || get_inner_text(&element, true).len() >= 25;
+            if !in_unlikely_container && has_content {
+                slides.push((index.unwrap_or(usize::MAX), element));
+            }
+        }
+
+        // Require a real slideshow: several slides sharing one container
+        let parent_id = slides.first().and_then(|(_, slide)| slide.parent()).map(|parent| parent.id());
+        slides.retain(|(_, slide)| slide.parent().map(|parent| parent.id()) == parent_id);
+        if slides.len() < 2 {
+            return Vec::new();
+        }
+
+        slides.sort_by_key(|(index, _)| *index);
+        slides.into_iter()
+            .map(|(_, slide)| slide.id())
+            .collect()
+    }
+
+    /// Find live-blog update entries, paired with their source element
+    ///
+    /// DOM entries (by class or `itemprop="liveBlogUpdate"`) are preferred;
+    /// JSON-LD `liveBlogUpdate` data is used when the markup has none, in
+    /// which case there is no source element.
+    fn collect_live_updates(&self) -> Vec<(Option<ego_tree::NodeId>, LiveUpdate)> {
+        let candidate_selector = Selector::parse("[class], [itemprop='liveBlogUpdate']").unwrap();
+        let time_selector = Selector::parse("time").unwrap();
+        let mut updates = Vec::new();
+        let mut update_ids = Vec::new();
+
+        for element in self.document.select(&candidate_selector) {
+            if !is_live_update_element(&element) || element.ancestors().any(|ancestor| update_ids.contains(&ancestor.id())) {
+                continue;
+            }
+            update_ids.push(element.id());
+
+            let time = element.select(&time_selector).next().map(|time| {
+                time.value().attr("datetime")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| get_inner_text(&time, true))
+            });
+            updates.push((Some(element.id()), LiveUpdate {
+                time,
+                html: element.inner_html().trim().to_string(),
+                text: get_inner_text(&element, true),
+            }));
+        }
+
+        if updates.is_empty() {
+            updates = self.json_ld.live_updates.iter()
+                .map(|update| (None, update.clone()))
+                .collect();
+        }
+        updates
+    }
+
+    /// Append ingredient and instruction lists the candidate selection missed
+    ///
+    /// Recipe pages often split ingredients and steps into separate
+    /// containers, so only one of them ends up in the top candidate.
+    fn append_missing_recipe_lists(&self, content_html: &mut String, text_content: &str) {
+        let list_selector = Selector::parse("ul, ol").unwrap();
+        for list in self.document.select(&list_selector) {
+            let class_and_id = format!(
+                "{} {}",
+                list.value().attr("class").unwrap_or(""),
+                list.value().attr("id").unwrap_or("")
+            );
+            let has_recipe_items = list.children()
+                .filter_map(ElementRef::wrap)
+                .any(|item| matches!(item.value().attr("itemprop"), Some("recipeIngredient" | "recipeInstructions")));
+            if !is_recipe_list(&class_and_id) && !has_recipe_items {
+                continue;
+            }
+
+            let list_text = get_inner_text(&list, true);
+            if !list_text.is_empty() && !text_content.contains(&list_text) {
+                content_html.push_str(&list.html());
+            }
+        }
+    }
+
+    /// Infer the page category from schema.org types, generator meta and URL
+    fn detect_article_kind(&self) -> Option<ArticleKind> {
+        // Section fronts often mark up every teaser as an article, so check
+        // the page structure before trusting schema.org types
+        if is_index_page(&self.document) {
+            return Some(ArticleKind::IndexPage);
+        }
+
+        // Explicit schema.org declarations: JSON-LD first, then microdata
+        if let Some(kind) = self.json_ld.types.iter().find_map(|t| ArticleKind::from_schema_type(t)) {
+            return Some(kind);
+        }
+        let itemtype_selector = Selector::parse("[itemtype]").unwrap();
+        if let Some(kind) = self.document.select(&itemtype_selector)
+            .filter_map(|element| element.value().attr("itemtype"))
+            .flat_map(str::split_whitespace)
+            .find_map(ArticleKind::from_schema_type)
+        {
+            return Some(kind);
+        }
+
+        // Documentation generators announce themselves in meta generator
+        if let Some(generator) = self.metadata.get("generator") {
+            let generator = generator.to_lowercase();
+            if profiles::DOCS_GENERATORS.iter().any(|name| generator.contains(name)) {
+                return Some(ArticleKind::Docs);
+            }
+        }
+
+        // URL path conventions
+        let path = self.base_uri.as_deref()
+            .and_then(utils::url_path)
+            .map(|path| format!("{}/", path.to_lowercase()))?;
+        let url_kinds = [
+            (["/recipe/", "/recipes/"].as_slice(), ArticleKind::Recipe),
+            (["/forum/", "/forums/", "/thread/", "/threads/", "/questions/", "/t/"].as_slice(), ArticleKind::Forum),
+            (["/docs/", "/documentation/", "/reference/", "/manual/"].as_slice(), ArticleKind::Docs),
+            (["/blog/", "/blogs/"].as_slice(), ArticleKind::Blog),
+            (["/news/"].as_slice(), ArticleKind::News),
+        ];
+        url_kinds.iter()
+            .find(|(segments, _)| segments.iter().any(|segment| path.contains(segment)))
+            .map(|(_, kind)| *kind)
+    }
+
+    /// Gather structured authors from JSON-LD, `rel="author"` links and the byline
+    fn collect_authors(&self) -> Vec<Author> {
+        let mut authors = self.json_ld.authors.clone();
+        let link_base_uri = self.effective_base_uri.clone();
+
+        let link_selector = Selector::parse("a[rel~='author'], a[itemprop='author'], article header address a[href]:not([href^='mailto:'])").unwrap();
+        for link in self.document.select(&link_selector) {
+            let name = self.get_inner_text_from_ref(&link, true);
+            let name = name.strip_prefix("By ").unwrap_or(&name).trim().to_string();
+            if name.is_empty() || name.len() >= 100 {
+                continue;
+            }
+
+            let url = link.value().attr("href").map(|href| match &link_base_uri {
+                Some(base_uri) => to_absolute_uri(href, base_uri),
+                None => href.to_string(),
+            });
+            match authors.iter_mut().find(|author| author.name.eq_ignore_ascii_case(&name)) {
+                Some(author) => {
+                    if author.url.is_none() {
+                        author.url = url;
+                    }
                 }
+                None => authors.push(Author { name, url, image: None }),
+            }
+        }
+
+        if authors.is_empty() {
+            if let Some(byline) = &self.article_byline {
+                authors.push(Author { name: byline.clone(), ..Default::default() });
+            }
+        }
+
+        // Attach avatars found inside byline/author containers
+        let avatar_selector = Selector::parse(
+            ".byline img, .author img, .author-avatar img, [rel~='author'] img, [itemprop='author'] img"
+        ).unwrap();
+        for image in self.document.select(&avatar_selector) {
+            let Some(src) = image.value().attr("src") else {
+                continue;
+            };
+            let alt = image.value().attr("alt").unwrap_or("").to_lowercase();
+            let single_author = authors.len() == 1;
+            let target = authors.iter_mut().find(|author| {
+                author.image.is_none()
+                    && (single_author || (!alt.is_empty() && alt.contains(&author.name.to_lowercase())))
+            });
+            if let Some(author) = target {
+                author.image = Some(match &link_base_uri {
+                    Some(base_uri) => to_absolute_uri(src, base_uri),
+                    None => src.to_string(),
+                });
             }
         }
+
+        authors
+    }
+
+    /// Check inline styles and `<style>` sheets for vertical `writing-mode`
+    fn detect_vertical_writing(&self) -> bool {
+        let styled_selector = Selector::parse("[style]").unwrap();
+        if self.document.select(&styled_selector)
+            .any(|element| uses_vertical_writing(element.value().attr("style").unwrap_or("")))
+        {
+            return true;
+        }
+
+        let style_selector = Selector::parse("style").unwrap();
+        self.document.select(&style_selector)
+            .any(|element| uses_vertical_writing(&element.text().collect::<String>()))
     }
 
     fn get_article_title(&mut self) {
+        // The Open Graph title is the one the site chose to share the article under
+        if let Some(title) = self.metadata.get("og:title").map(|title| title.trim()).filter(|title| !title.is_empty()) {
+            self.article_title = Some(title.to_string());
+            self.report.provenance.record("title", MetadataSource::OpenGraph);
+            return;
+        }
+
         let title_selector = Selector::parse("title").unwrap();
         if let Some(title_element) = self.document.select(&title_selector).next() {
             self.article_title = Some(title_element.inner_html());
+            self.report.provenance.record("title", MetadataSource::TitleElement);
         }
 
         // Try to get a better title from h1 elements
@@ -362,12 +1561,19 @@ impl Readability {
             let h1_text = self.get_inner_text_from_ref(&h1, false);
             if h1_text.len() > 10 {
                 self.article_title = Some(h1_text);
+                self.report.provenance.record("title", MetadataSource::Heading);
                 break;
             }
         }
     }
 
-    fn grab_article(&mut self) -> Option<ElementRef> {
+    /// Find the article content, retrying with fewer heuristics while it
+    /// falls short of `char_threshold`, as Readability.js's `grabArticle` does
+    ///
+    /// Each retry starts from the document as prepared, and switches off
+    /// `strip_unlikelys`, then `weight_classes`, then `clean_conditionally`.
+    /// When none is left, the longest attempt wins.
+    fn grab_article(&mut self) -> Option<ElementRef<'_>> {
         if self.options.debug {
             println!("**** grabArticle ****");
         }
@@ -379,9 +1585,60 @@ impl Readability {
                 return None;
             }
         }
-        
-        // Remove unlikely candidates from DOM if flag is enabled
-        if self.options.flags.strip_unlikelys {
+
+        let char_threshold = self.options.char_threshold.resolve(self.scoring_language().as_deref());
+        let prepared = self.document.clone();
+        let prepared_bytes = memory::estimate_document_bytes(&prepared);
+        let mut attempts: Vec<(Html, ego_tree::NodeId, usize)> = Vec::new();
+        let mut attempts_bytes = 0;
+        let mut retries = 0;
+        let content = loop {
+            let content = self.grab_article_attempt();
+            let text_length = content
+                .and_then(|id| self.document.tree.get(id).and_then(ElementRef::wrap))
+                .map_or(0, |element| get_inner_text(&element, true).chars().count());
+            if text_length >= char_threshold {
+                break content;
+            }
+
+            let document = std::mem::replace(&mut self.document, prepared.clone());
+            if let Some(id) = content {
+                attempts_bytes += memory::estimate_document_bytes(&document);
+                attempts.push((document, id, text_length));
+            }
+            // The prepared copy, the fresh clone and the attempts kept for comparison
+            if self.record_memory(2 * prepared_bytes + attempts_bytes).is_err() {
+                return None;
+            }
+            if self.flags.strip_unlikelys {
+                self.flags.strip_unlikelys = false;
+            } else if self.flags.weight_classes {
+                self.flags.weight_classes = false;
+            } else if self.flags.clean_conditionally {
+                self.flags.clean_conditionally = false;
+            } else {
+                // The first of the longest attempts, with the document it was found in
+                let (document, id, _) = attempts.into_iter().reduce(|best, attempt| if attempt.2 > best.2 { attempt } else { best })?;
+                self.document = document;
+                break Some(id);
+            }
+            retries += 1;
+            if self.options.debug {
+                println!("Content too short ({} chars), retrying with {:?}", text_length, self.flags);
+            }
+        };
+
+        if retries > 0 {
+            self.report.push(ReportEntry::FlagsRelaxed { retries });
+        }
+        content.and_then(|id| self.document.tree.get(id).and_then(ElementRef::wrap))
+    }
+
+    /// One pass of candidate scoring with the current flags, returning the
+    /// id of the content element
+    fn grab_article_attempt(&mut self) -> Option<ego_tree::NodeId> {
+        // Remove unlikely candidates from DOM if flag is enabled
+        if self.flags.strip_unlikelys {
             self.remove_unlikely_candidates_from_dom();
         }
         
@@ -393,33 +1650,71 @@ impl Readability {
         
         if candidates.is_empty() {
             // Fallback to simple selector-based approach
-            return self.fallback_content_selection();
+            if !self.warnings.contains(&ParseWarning::FallbackContentSelection) {
+                self.warnings.push(ParseWarning::FallbackContentSelection);
+            }
+            return self.fallback_content_selection().map(|element| element.id());
         }
         
         // Find the best candidate
-        if let Some(best_candidate) = self.select_best_candidate(&candidates) {
-            // Get the tag name and some identifying information
-            let tag_name = best_candidate.value().name();
-            let text_content = self.get_inner_text_from_ref(&best_candidate, true);
-            
-            // Search for the element in the document by matching tag and content
-            let selector = Selector::parse(tag_name).unwrap();
-            for element in self.document.select(&selector) {
-                let element_text = self.get_inner_text_from_ref(&element, true);
-                if element_text == text_content {
-                    return Some(element);
-                }
-            }
+        let (top_candidate, best_score) = self.select_best_candidate(&candidates)?;
+        let content = self.content_siblings(top_candidate, best_score, &candidates);
+        drop(candidates);
+
+        match content.as_slice() {
+            [only] => Some(*only),
+            _ => Some(dom::wrap(&mut self.document, &content, "div")),
         }
-        
-        None
     }
-    
 
-    
+    /// `top_candidate` and the siblings that belong to the article with it,
+    /// in document order, as Readability.js's `grabArticle` picks them
+    ///
+    /// A sibling qualifies with a score above a fifth of the top candidate's
+    /// (at least 10), counting a bonus for sharing its class, or as a
+    /// paragraph of prose with few links.
+    fn content_siblings(&self, top_candidate: ElementRef, top_score: f64, candidates: &[(ElementRef, f64)]) -> Vec<ego_tree::NodeId> {
+        let threshold = (top_score * 0.2).max(10.0);
+        let top_class = top_candidate.value().attr("class").unwrap_or("");
+        let Some(parent) = top_candidate.parent() else {
+            return vec![top_candidate.id()];
+        };
+
+        parent.children()
+            .filter_map(ElementRef::wrap)
+            .filter(|sibling| {
+                if sibling.id() == top_candidate.id() {
+                    return true;
+                }
+                let bonus = if !top_class.is_empty() && sibling.value().attr("class") == Some(top_class) {
+                    top_score * 0.2
+                } else {
+                    0.0
+                };
+                if let Some((_, score)) = candidates.iter().find(|(candidate, _)| candidate.id() == sibling.id()) {
+                    if score + bonus >= threshold {
+                        return true;
+                    }
+                }
+                if sibling.value().name() != "p" {
+                    return false;
+                }
+                let link_density = get_link_density(sibling);
+                let text = get_inner_text(sibling, true);
+                let length = text.chars().count();
+                if length > 80 {
+                    link_density < 0.25
+                } else {
+                    length > 0 && link_density == 0.0 && (text.contains(". ") || text.ends_with('.'))
+                }
+            })
+            .map(|sibling| sibling.id())
+            .collect()
+    }
+
     fn get_class_weight(&self, element: &ElementRef) -> f64 {
         // Return 0 if weight classes flag is disabled
-        if !self.options.flags.weight_classes {
+        if !self.flags.weight_classes {
             return 0.0;
         }
         
@@ -444,69 +1739,70 @@ impl Readability {
                 weight += 25.0;
             }
         }
+
+        // Explicit author declarations of the content root outweigh name heuristics
+        if is_declared_content_root(element) {
+            weight += 50.0;
+        }
         
         weight
     }
     
-    fn find_and_score_candidates(&self) -> Vec<(ElementRef, f64)> {
-        let mut candidates = Vec::new();
-        let mut candidate_map: HashMap<String, (ElementRef, f64)> = HashMap::new();
-        
+    fn find_and_score_candidates(&self) -> Vec<(ElementRef<'_>, f64)> {
+        let scoring_lang = self.scoring_language();
+        let Some(scope) = self.scope_root() else {
+            return Vec::new();
+        };
+
         // Find all paragraph elements and other content containers
         let content_selector = Selector::parse("p, td, pre").unwrap();
+        let elements: Vec<_> = scope.select(&content_selector).collect();
+
+        let scores = score_paragraphs(&elements, scoring_lang.as_deref());
+
+        // Candidates in the order first seen, so ties resolve the same way on every run.
+        // Most paragraphs share their parent and grandparent with a neighbour
+        let mut candidates: Vec<(ElementRef, f64)> = Vec::with_capacity(elements.len());
+        let mut candidate_index: HashMap<ego_tree::NodeId, usize> = HashMap::with_capacity(elements.len());
+        let mut ancestors = Vec::with_capacity(self.options.max_candidate_depth);
         
-        for element in self.document.select(&content_selector) {
-            let text = get_inner_text(&element, true);
-            let text_length = text.trim().len();
-            
+        for (element, content_score) in elements.into_iter().zip(scores) {
             // Skip if too short
-            if text_length < 25 {
+            let Some(content_score) = content_score else {
                 continue;
-            }
+            };
             
-            // Initialize parent and grandparent candidates
-            let mut ancestors = Vec::new();
-            if let Some(parent) = element.parent() {
-                if let Some(parent_element) = ElementRef::wrap(parent) {
-                    // Skip unlikely candidates during filtering
-                    if self.options.flags.strip_unlikelys && self.is_unlikely_candidate(&parent_element) {
-                        continue;
-                    }
-                    ancestors.push((parent_element, 1));
-                    
-                    if let Some(grandparent) = parent.parent() {
-                        if let Some(grandparent_element) = ElementRef::wrap(grandparent) {
-                            if self.options.flags.strip_unlikelys && self.is_unlikely_candidate(&grandparent_element) {
-                                continue;
-                            }
-                            ancestors.push((grandparent_element, 2));
-                        }
-                    }
+            // Initialize ancestor candidates, up to the configured depth and
+            // never beyond the scope root
+            ancestors.clear();
+            let mut unlikely = false;
+            for ancestor in element.ancestors().filter_map(ElementRef::wrap).take(self.options.max_candidate_depth) {
+                // Skip unlikely candidates during filtering
+                if self.flags.strip_unlikelys && self.is_unlikely_candidate(&ancestor) {
+                    unlikely = true;
+                    break;
                 }
+                ancestors.push((ancestor, ancestors.len() + 1));
+                if ancestor.id() == scope.id() {
+                    break;
+                }
+            }
+            if unlikely {
+                continue;
             }
             
             // Initialize candidates if not already done
             for (ancestor, _level) in &ancestors {
-                let ancestor_id = self.get_element_id(ancestor);
-                if !candidate_map.contains_key(&ancestor_id) {
-                    let content_score = self.initialize_candidate_score(ancestor);
-                    candidate_map.insert(ancestor_id, (*ancestor, content_score));
-                }
+                candidate_index.entry(ancestor.id()).or_insert_with(|| {
+                    candidates.push((*ancestor, self.initialize_candidate_score(ancestor)));
+                    candidates.len() - 1
+                });
             }
             
-            // Calculate content score for this paragraph (matching JavaScript algorithm)
-            let mut content_score = 1.0;
-            
-            // Add points for any commas within this paragraph
-            content_score += count_commas(&text) as f64;
-            
-            // For every 100 characters in this paragraph, add another point. Up to 3 points.
-            content_score += (text_length as f64 / 100.0).min(3.0);
-            
             // Add scores to parent and grandparent (matching JavaScript dividers)
             for (ancestor, level) in &ancestors {
-                let ancestor_id = self.get_element_id(ancestor);
-                if let Some((_, current_score)) = candidate_map.get_mut(&ancestor_id) {
+                if let Some(&index) = candidate_index.get(&ancestor.id()) {
+                    let current_score = &mut candidates[index].1;
                     let score_divider = match level {
                          1 => 1.0, // parent: no division
                          2 => 2.0, // grandparent: divide by 2
@@ -517,66 +1813,69 @@ impl Readability {
             }
         }
         
-        // Convert map to vector and apply link density scaling
-        for (_, (element, mut score)) in candidate_map {
-            let link_density = get_link_density(&element);
-            score *= 1.0 - link_density;
-            candidates.push((element, score));
+        // Apply link density scaling
+        for (element, score) in &mut candidates {
+            *score *= 1.0 - get_link_density(element);
         }
         
         candidates
     }
     
+    /// Root of the region scoring is bounded to: the first `scope_selector`
+    /// match, or the whole document
+    fn scope_root(&self) -> Option<ElementRef<'_>> {
+        match &self.options.scope_selector {
+            Some(scope) => Selector::parse(scope).ok().and_then(|selector| self.document.select(&selector).next()),
+            None => Some(self.document.root_element()),
+        }
+    }
+
+    /// Language used for script-dependent scoring: the document language if
+    /// declared, otherwise a guess from the body text's script
+    fn scoring_language(&self) -> Option<String> {
+        if let Some(lang) = self.metadata.get("lang") {
+            return Some(lang.clone());
+        }
+
+        let body_selector = Selector::parse("body").unwrap();
+        let body = self.document.select(&body_selector).next()?;
+        let sample: String = body.text().take(200).collect();
+        analysis::detect_language_from_script(&sample).map(str::to_string)
+    }
+
     fn is_unlikely_candidate(&self, element: &ElementRef) -> bool {
         let tag_name = element.value().name();
-        
-        // Filter out navigation elements
-        if matches!(tag_name, "nav" | "aside" | "header" | "footer") {
-            return true;
-        }
-        
-        // Don't filter these tags
-        if matches!(tag_name, "body" | "a" | "table" | "tbody" | "tr" | "td" | "th" | "article" | "section") {
+
+        // Data tables are kept whole
+        if in_data_table(element) {
             return false;
         }
         
-        // Check class and id attributes
-        let class_and_id = format!(
-            "{} {}",
-            element.value().attr("class").unwrap_or(""),
-            element.value().attr("id").unwrap_or("")
-        );
-        
-        // Use the regex-based unlikely candidate detection
-        if is_unlikely_candidate(&class_and_id) && !has_positive_indicators(&class_and_id) {
+        // Filter out navigation elements, recommendation widgets and floating bars
+        if matches!(tag_name, "nav" | "aside" | "header" | "footer")
+            || is_recommendation_widget_element(element)
+            || is_floating_element(element)
+        {
             return true;
         }
-        
-        // Check for specific roles that are unlikely to contain article content
-        if let Some(role) = element.value().attr("role") {
-            if matches!(role, "menu" | "menubar" | "complementary" | "navigation" | "alert" | "alertdialog" | "dialog") {
-                return true;
-            }
+
+        // Filter out what the print view hides
+        if self.print_hints.as_ref().is_some_and(|hints| hints.is_hidden(element)) {
+            return true;
         }
         
-        false
-    }
-    
-    fn get_element_id(&self, element: &ElementRef) -> String {
-        // Create a unique identifier for the element
-        format!("{:p}", element.value())
+        is_unlikely_by_name(element)
     }
     
     fn initialize_candidate_score(&self, element: &ElementRef) -> f64 {
         let mut score = 1.0;
         
         // Initialize based on tag type (matching JavaScript _initializeNode)
-        let tag_name = element.value().name().to_uppercase();
-        match tag_name.as_str() {
-            "DIV" => score += 5.0,
-            "PRE" | "TD" | "BLOCKQUOTE" => score += 3.0,
-            "ADDRESS" | "OL" | "UL" | "DL" | "DD" | "DT" | "LI" | "FORM" => score -= 3.0,
-            "H1" | "H2" | "H3" | "H4" | "H5" | "H6" | "TH" => score -= 5.0,
+        match element.value().name() {
+            "div" => score += 5.0,
+            "pre" | "td" | "blockquote" => score += 3.0,
+            "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => score -= 3.0,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => score -= 5.0,
             _ => {},
         }
         
@@ -590,7 +1889,7 @@ impl Readability {
     
 
     
-    fn select_best_candidate<'a>(&self, candidates: &'a [(ElementRef<'a>, f64)]) -> Option<ElementRef<'a>> {
+    fn select_best_candidate<'a>(&self, candidates: &'a [(ElementRef<'a>, f64)]) -> Option<(ElementRef<'a>, f64)> {
         if candidates.is_empty() {
             return None;
         }
@@ -599,12 +1898,41 @@ impl Readability {
         let mut sorted_candidates = candidates.to_vec();
         sorted_candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         
-        let best_candidate = sorted_candidates[0].0;
-        let best_score = sorted_candidates[0].1;
+        let mut best_candidate = sorted_candidates[0].0;
+        let mut best_score = sorted_candidates[0].1;
         
         if self.options.debug {
             println!("Best candidate score: {}", best_score);
         }
+
+        // Several near-best candidates under one ancestor mean the content is
+        // split into fragments; that ancestor holds the whole article
+        let alternatives: Vec<ElementRef> = sorted_candidates.iter()
+            .take(self.options.nb_top_candidates)
+            .skip(1)
+            .filter(|(_, score)| best_score > 0.0 && score / best_score >= 0.75)
+            .map(|(candidate, _)| *candidate)
+            .collect();
+        if alternatives.len() >= MINIMUM_TOP_CANDIDATES {
+            let common_ancestor = best_candidate.ancestors()
+                .filter_map(ElementRef::wrap)
+                .take_while(|ancestor| !matches!(ancestor.value().name(), "body" | "html"))
+                .find(|ancestor| {
+                    let contained = alternatives.iter()
+                        .filter(|alternative| alternative.ancestors().any(|node| node.id() == ancestor.id()))
+                        .count();
+                    contained >= MINIMUM_TOP_CANDIDATES
+                });
+            if let Some(ancestor) = common_ancestor {
+                best_score = candidates.iter()
+                    .find(|(candidate, _)| candidate.id() == ancestor.id())
+                    .map_or_else(|| self.initialize_candidate_score(&ancestor), |(_, score)| *score);
+                best_candidate = ancestor;
+                if self.options.debug {
+                    println!("Using the common ancestor of the top candidates, with score: {}", best_score);
+                }
+            }
+        }
         
         // Check if we need to look at the parent for better content aggregation
         // This mimics the JavaScript logic for finding a better top candidate
@@ -628,14 +1956,38 @@ impl Readability {
                             if self.options.debug {
                                 println!("Using parent element with score: {}", parent_score);
                             }
-                            return Some(parent_element);
+                            return Some((parent_element, parent_score));
                         }
                     }
                 }
             }
         }
         
-        Some(best_candidate)
+        Some((best_candidate, best_score))
+    }
+
+    /// Map content blocks to elements under the extracted candidate at `content_path`
+    fn build_source_map(&self, content_path: &str, blocks: ArticleBlocks) -> Vec<SourceMapEntry> {
+        let root = Selector::parse(content_path).ok()
+            .and_then(|selector| self.document.select(&selector).next());
+        match root {
+            Some(root) => source_map::build(&self.document, root, &blocks),
+            None => Vec::new(),
+        }
+    }
+
+    /// Find the top candidate without extracting or cleaning it
+    fn locate_content(&self) -> Option<ContentLocation> {
+        let candidates = self.find_and_score_candidates();
+        let (element, score) = match self.select_best_candidate(&candidates) {
+            Some(best) => best,
+            None => (self.fallback_content_selection()?, 0.0),
+        };
+        Some(ContentLocation {
+            css_path: css_path(&element),
+            char_len: get_inner_text(&element, true).chars().count(),
+            score,
+        })
     }
     
 
@@ -653,8 +2005,8 @@ impl Readability {
         // Add a point for the paragraph itself as a base
         content_score += 1.0;
         
-        // Add points for any commas within this paragraph
-        content_score += count_commas(&text) as f64;
+        // Add points for any commas (or script equivalents) within this paragraph
+        content_score += count_commas_for_lang(&text, self.scoring_language().as_deref()) as f64;
         
         // For every 100 characters in this paragraph, add another point. Up to 3 points.
         content_score += (text.len() as f64 / 100.0).min(3.0);
@@ -662,8 +2014,51 @@ impl Readability {
         content_score
     }
     
-    fn fallback_content_selection(&self) -> Option<ElementRef> {
-        let selectors = ["article", "main", "#content", ".content", ".entry-content", "body"];
+    fn fallback_content_selection(&self) -> Option<ElementRef<'_>> {
+        if self.options.scope_selector.is_some() {
+            return self.scope_root();
+        }
+
+        // Explicitly marked story content wins; several `articleBody` parts
+        // are pages of one story, so take their common container
+        let main_selector = Selector::parse("article[role='main']").unwrap();
+        if let Some(element) = self.document.select(&main_selector).next() {
+            return Some(element);
+        }
+        let body_selector = Selector::parse("[itemprop~='articleBody']").unwrap();
+        let parts: Vec<ElementRef> = self.document.select(&body_selector).collect();
+        if let Some(element) = common_ancestor(&parts) {
+            return Some(element);
+        }
+        let role_selector = Selector::parse("[role='main']").unwrap();
+        if let Some(element) = self.document.select(&role_selector).next() {
+            return Some(element);
+        }
+
+        // Listing pages and some themes have many articles: pick the most substantial
+        let article_selector = Selector::parse("article").unwrap();
+        let heading_selector = Selector::parse("h1, h2").unwrap();
+        let best_article = self.document.select(&article_selector)
+            .map(|article| {
+                let text_length = get_inner_text(&article, true).len() as f64;
+                let mut score = text_length * (1.0 - get_link_density(&article));
+                if article.select(&heading_selector).next().is_some() {
+                    score *= 1.25;
+                }
+                (article, score)
+            })
+            .fold(None, |best: Option<(ElementRef, f64)>, (article, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((article, score)),
+            });
+        if let Some((article, _)) = best_article {
+            if self.options.debug {
+                println!("Found content using best of the article elements");
+            }
+            return Some(article);
+        }
+
+        let selectors = ["main", "#content", ".content", ".entry-content", "body"];
         
         for selector_str in &selectors {
             if let Ok(selector) = Selector::parse(selector_str) {
@@ -685,23 +2080,82 @@ impl Readability {
         
         for element in self.document.select(&script_selector) {
             let text = element.text().collect::<String>();
-            // Parse JSON-LD and extract relevant metadata
-            // This is a simplified implementation
-            if text.contains("@type") && text.contains("Article") {
-                // Extract article metadata from JSON-LD
-                if self.options.debug {
-                    println!("Found JSON-LD article metadata");
-                }
-            }
+            self.json_ld.add_script(&text);
+        }
+
+        if self.options.debug && !self.json_ld.types.is_empty() {
+            println!("Found JSON-LD types: {:?}", self.json_ld.types);
         }
     }
 
 
     
+    /// Readability.js's `_unwrapNoscriptImages`: drop images without any
+    /// source, then put the image a `<noscript>` holds in place of the
+    /// placeholder image just before it
     fn unwrap_noscript_images(&mut self) {
-        // Implementation for unwrapping noscript images
-        let _noscript_selector = Selector::parse("noscript").unwrap();
-        // Process noscript elements...
+        let image_selector = Selector::parse("img").unwrap();
+        let sourceless: Vec<ego_tree::NodeId> = self.document.select(&image_selector)
+            .filter(|image| {
+                !image.value().attrs().any(|(name, value)| {
+                    matches!(name, "src" | "srcset" | "data-src" | "data-srcset") || has_image_extension(value)
+                })
+            })
+            .map(|image| image.id())
+            .collect();
+        dom::remove(&mut self.document, &sourceless);
+
+        let noscript_selector = Selector::parse("noscript").unwrap();
+        let mut unwrapped = 0;
+        for noscript in dom::select_ids(&self.document, &noscript_selector) {
+            let Some(element) = self.document.tree.get(noscript).and_then(ElementRef::wrap) else {
+                continue;
+            };
+            // With scripting on, the parser keeps the contents as text
+            let markup: String = element.text().collect();
+            let contents = Html::parse_fragment(&markup);
+            let Some(image) = contents.root_element().children().find_map(ElementRef::wrap).filter(is_single_image) else {
+                continue;
+            };
+            let Some(previous) = element.prev_siblings().find_map(ElementRef::wrap).filter(is_single_image) else {
+                continue;
+            };
+            let Some(parent) = previous.parent().map(|parent| parent.id()) else {
+                continue;
+            };
+            let placeholder = previous.select(&image_selector).next().unwrap_or(previous);
+            let old_attrs: Vec<(String, String)> = placeholder.value().attrs()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            let previous = previous.id();
+
+            let Some(copy) = append_subtree(&mut self.document.tree, parent, *image) else {
+                continue;
+            };
+            if let Some(mut previous) = self.document.tree.get_mut(previous) {
+                previous.insert_id_before(copy);
+            }
+            dom::remove(&mut self.document, &[previous]);
+
+            // Keep the placeholder's sources, under `data-old-*` where the new image has its own
+            let new_image = self.document.tree.get(copy).and_then(ElementRef::wrap)
+                .and_then(|copy| if copy.value().name() == "img" { Some(copy) } else { copy.select(&image_selector).next() })
+                .map(|image| (image.id(), image.value().clone()));
+            if let Some((new_image, new_attrs)) = new_image {
+                for (name, value) in old_attrs {
+                    if !(name == "src" || name == "srcset" || has_image_extension(&value)) || new_attrs.attr(&name) == Some(value.as_str()) {
+                        continue;
+                    }
+                    let name = if new_attrs.attr(&name).is_some() { format!("data-old-{}", name) } else { name };
+                    dom::set_attr(&mut self.document, new_image, &name, &value);
+                }
+            }
+            unwrapped += 1;
+        }
+        if self.options.debug && unwrapped > 0 {
+            println!("Unwrapped {} noscript images", unwrapped);
+        }
     }
     
     fn prep_document(&mut self) {
@@ -709,28 +2163,40 @@ impl Readability {
             println!("**** prepDocument ****");
         }
         
-        // Remove script and style elements
-        self.remove_nodes_by_tag("script");
-        self.remove_nodes_by_tag("style");
-        self.remove_nodes_by_tag("noscript");
-        
-        // Remove unlikely candidates if flag is enabled
-        if self.options.flags.strip_unlikelys {
-            self.remove_unlikely_candidates_from_dom();
+        // Drop or lift inert template contents
+        self.handle_templates();
+
+        // Score substantial srcdoc documents with the page
+        self.lift_srcdoc_frames();
+
+        // Tell data tables from layout tables while they are whole
+        self.mark_data_tables();
+
+        // Strip e-mail preheaders, tracking pixels and layout tables
+        if self.profile == ExtractionProfile::Email {
+            self.prepare_email();
         }
+
+        // Replace single-cell layout tables with their cell
+        self.unwrap_layout_tables();
+
+        // Rejoin broken words and drop stray characters of OCR-derived text
+        if self.options.clean_ocr_artifacts {
+            self.clean_ocr_artifacts();
+        }
+
+        // Remove style elements; scripts are already gone
+        self.remove_nodes_by_tag("style");
         
         // Replace font tags with span tags
         self.replace_font_tags();
         
+        // Give pages of bare text and <br>s paragraphs to score
+        self.wrap_flat_text();
+
         // Replace <br> sequences with paragraphs
         self.replace_brs();
         
-        // Unwrap noscript images
-        self.unwrap_noscript_images();
-        
-        // Convert divs to paragraphs where appropriate
-        self.convert_divs_to_paragraphs();
-        
         // Remove empty paragraphs
         self.remove_empty_paragraphs();
         
@@ -739,87 +2205,758 @@ impl Readability {
         }
     }
     
+    /// Drop `<template>` elements, whose contents browsers never render, or
+    /// with `lift_templates` put their contents in their place
+    ///
+    /// Declarative shadow roots are rendered, so they are always flattened
+    /// into the light DOM.
+    fn handle_templates(&mut self) {
+        let template_selector = Selector::parse("template").unwrap();
+        let templates: Vec<_> = self.document.select(&template_selector).map(|template| template.id()).collect();
+        for template in templates {
+            let is_shadow_root = self.document.tree.get(template)
+                .and_then(|node| node.value().as_element())
+                .is_some_and(|element| element.attr("shadowrootmode").or(element.attr("shadowroot")).is_some());
+            if is_shadow_root {
+                self.flatten_shadow_root(template);
+                continue;
+            }
+            let children: Vec<_> = self.document.tree.get(template)
+                .map(|node| node.children().map(|child| child.id()).collect())
+                .unwrap_or_default();
+            let Some(mut node) = self.document.tree.get_mut(template) else {
+                continue;
+            };
+            if self.options.lift_templates {
+                for child in children {
+                    node.insert_id_before(child);
+                }
+            }
+            node.detach();
+        }
+    }
+
+    /// Replace a declarative shadow root with its contents, moving the host's
+    /// light children into their `<slot>`s the way browsers render them
+    fn flatten_shadow_root(&mut self, template: ego_tree::NodeId) {
+        let tree = &mut self.document.tree;
+        let Some(node) = tree.get(template) else {
+            return;
+        };
+        let Some(host) = node.parent() else {
+            return;
+        };
+        let shadow: Vec<_> = node.children().map(|child| child.id()).collect();
+        let light: Vec<_> = host.children().map(|child| child.id()).filter(|&id| id != template).collect();
+        // Slots of this shadow tree; those inside nested templates belong to them
+        let slots: Vec<_> = node.descendants()
+            .filter(|descendant| descendant.value().as_element().is_some_and(|element| element.name() == "slot"))
+            .filter(|slot| !slot.ancestors()
+                .take_while(|ancestor| ancestor.id() != template)
+                .any(|ancestor| ancestor.value().as_element().is_some_and(|element| element.name() == "template")))
+            .map(|slot| {
+                let name = slot.value().as_element().and_then(|element| element.attr("name")).unwrap_or("");
+                (slot.id(), name.to_string())
+            })
+            .collect();
+        let slot_name = |id| {
+            tree.get(id)
+                .and_then(|child| child.value().as_element())
+                .and_then(|element| element.attr("slot"))
+                .unwrap_or("")
+                .to_string()
+        };
+        let light: Vec<_> = light.into_iter().map(|id| (id, slot_name(id))).collect();
+
+        let mut assigned = HashSet::new();
+        for (slot, name) in slots {
+            let nodes: Vec<_> = light.iter()
+                .filter(|(id, slot_name)| *slot_name == name && !assigned.contains(id))
+                .map(|(id, _)| *id)
+                .collect();
+            // Unfilled slots show their fallback content
+            let replacement = if nodes.is_empty() {
+                tree.get(slot).map(|slot| slot.children().map(|child| child.id()).collect()).unwrap_or_default()
+            } else {
+                nodes
+            };
+            let Some(mut slot) = tree.get_mut(slot) else {
+                continue;
+            };
+            for id in replacement {
+                assigned.insert(id);
+                slot.insert_id_before(id);
+            }
+            slot.detach();
+        }
+        // Light children without a slot are not rendered
+        for (id, _) in &light {
+            if !assigned.contains(id) {
+                if let Some(mut child) = tree.get_mut(*id) {
+                    child.detach();
+                }
+            }
+        }
+        if let Some(mut node) = tree.get_mut(template) {
+            for child in shadow {
+                node.insert_id_before(child);
+            }
+            node.detach();
+        }
+    }
+
+    /// Replace `<iframe srcdoc>` frames holding a substantial document with
+    /// that document's body, so it is scored like the rest of the page
+    fn lift_srcdoc_frames(&mut self) {
+        let frame_selector = Selector::parse("iframe[srcdoc]").unwrap();
+        let body_selector = Selector::parse("body").unwrap();
+        let frames: Vec<_> = self.document.select(&frame_selector)
+            .filter_map(|frame| Some((frame.id(), frame.value().attr("srcdoc")?.to_string())))
+            .collect();
+        for (frame, srcdoc) in frames {
+            let frame_document = Html::parse_document(&srcdoc);
+            if rendered_text_length(&frame_document) < MIN_SRCDOC_CHARS {
+                continue;
+            }
+            let Some(body) = frame_document.select(&body_selector).next() else {
+                continue;
+            };
+            let Some(parent) = self.document.tree.get(frame).and_then(|node| node.parent()).map(|parent| parent.id()) else {
+                continue;
+            };
+            if self.options.debug {
+                println!("Using the srcdoc document of an iframe as content");
+            }
+            let wrapper = Html::parse_fragment(&format!("<div>{}</div>", body.inner_html()));
+            let Some(div) = wrapper.root_element().first_child() else {
+                continue;
+            };
+            append_subtree(&mut self.document.tree, parent, div);
+            let Some(copy) = self.document.tree.get(parent).and_then(|parent| parent.last_child()).map(|copy| copy.id()) else {
+                continue;
+            };
+            if let Some(mut node) = self.document.tree.get_mut(frame) {
+                node.insert_id_before(copy);
+                node.detach();
+            }
+        }
+    }
+
+    /// Flag the tables that hold data (a caption, header cells, a `summary`,
+    /// or a grid of many rows or columns) so cleaning never removes them
+    ///
+    /// Cleaning drops rows, captions and hidden cells, after which a data
+    /// table can look like layout, so tables are judged once, here.
+    fn mark_data_tables(&mut self) {
+        let table_selector = Selector::parse("table").unwrap();
+        let data_tables: Vec<_> = self.document.select(&table_selector)
+            .filter(is_data_table)
+            .map(|table| table.id())
+            .collect();
+        if self.options.debug {
+            println!("Marking {} data tables", data_tables.len());
+        }
+        for table in data_tables {
+            dom::set_attr(&mut self.document, table, DATA_TABLE_ATTR, "");
+        }
+    }
+
+    /// Replace single-cell layout tables with their cell, as a `p` if it
+    /// holds only phrasing content and a `div` otherwise
+    fn unwrap_layout_tables(&mut self) {
+        let table_selector = Selector::parse("table").unwrap();
+        let cells: Vec<_> = self.document.select(&table_selector)
+            .filter(|table| !is_marked_data_table(table))
+            .filter_map(|table| {
+                let rows = single_child(table, "tbody").unwrap_or(table);
+                let cell = single_child(single_child(rows, "tr")?, "td")?;
+                let phrasing = cell.children().all(|child| match child.value() {
+                    scraper::Node::Element(element) => is_phrasing_content(element.name()) || matches!(element.name(), "a" | "del" | "ins"),
+                    _ => true,
+                });
+                // The cell stands in for the table, so it takes over its class and id
+                let classes: Vec<&str> = table.value().classes().chain(cell.value().classes()).collect();
+                let id = cell.value().id().or(table.value().id());
+                let mut attributes: String = cell.value().attrs()
+                    .filter(|(name, _)| !matches!(*name, "class" | "id"))
+                    .map(|(name, value)| format!(r#" {}="{}""#, name, escape_html(value)))
+                    .collect();
+                if !classes.is_empty() {
+                    attributes.push_str(&format!(r#" class="{}""#, escape_html(&classes.join(" "))));
+                }
+                if let Some(id) = id {
+                    attributes.push_str(&format!(r#" id="{}""#, escape_html(id)));
+                }
+                let tag = if phrasing { "p" } else { "div" };
+                let replacement = Html::parse_fragment(&format!("<{}{}></{}>", tag, attributes, tag));
+                let element = replacement.select(&Selector::parse(tag).unwrap()).next()?.value().clone();
+                Some((table.id(), cell.id(), element))
+            })
+            .collect();
+        if cells.is_empty() {
+            return;
+        }
+        if self.options.debug {
+            println!("Unwrapping {} single-cell layout tables", cells.len());
+        }
+
+        for (table, cell, element) in cells {
+            let Some(mut cell_node) = self.document.tree.get_mut(cell) else {
+                continue;
+            };
+            *cell_node.value() = scraper::Node::Element(element);
+            if let Some(mut table_node) = self.document.tree.get_mut(table) {
+                table_node.insert_id_before(cell);
+                table_node.detach();
+            }
+        }
+    }
+
+    /// Remove the hidden preheader and 1×1 tracking images of an e-mail, and
+    /// replace its layout tables with a `div` per cell
+    fn prepare_email(&mut self) {
+        let all_selector = Selector::parse("body *").unwrap();
+        let removed: Vec<_> = self.document.select(&all_selector)
+            .filter(|element| match element.value().name() {
+                "img" => profiles::is_tracking_pixel(element),
+                _ => profiles::is_email_preheader(element),
+            })
+            .map(|element| element.id())
+            .collect();
+        if self.options.debug && !removed.is_empty() {
+            println!("Removing {} e-mail preheaders and tracking pixels", removed.len());
+        }
+        for id in removed {
+            if let Some(mut node) = self.document.tree.get_mut(id) {
+                node.detach();
+            }
+        }
+
+        let table_selector = Selector::parse("table").unwrap();
+        let tables: Vec<_> = self.document.select(&table_selector)
+            .filter(profiles::is_email_layout_table)
+            .map(|table| {
+                let sections = table.children().filter_map(ElementRef::wrap)
+                    .filter(|child| matches!(child.value().name(), "tbody" | "thead" | "tfoot"));
+                let cells: Vec<_> = std::iter::once(table).chain(sections)
+                    .flat_map(|rows| rows.children().filter_map(ElementRef::wrap))
+                    .filter(|row| row.value().name() == "tr")
+                    .flat_map(|row| row.children().filter_map(ElementRef::wrap))
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .map(|cell| cell.id())
+                    .collect();
+                (table.id(), cells)
+            })
+            .collect();
+        if tables.is_empty() {
+            return;
+        }
+        if self.options.debug {
+            println!("Unwrapping {} e-mail layout tables", tables.len());
+        }
+
+        let div = Html::parse_fragment("<div></div>");
+        let div = div.select(&Selector::parse("div").unwrap()).next().unwrap().value().clone();
+        // Outer tables come first; inner ones move along with their cells
+        for (table, cells) in tables {
+            for cell in cells {
+                let Some(mut cell_node) = self.document.tree.get_mut(cell) else {
+                    continue;
+                };
+                *cell_node.value() = scraper::Node::Element(div.clone());
+                if let Some(mut table_node) = self.document.tree.get_mut(table) {
+                    table_node.insert_id_before(cell);
+                }
+            }
+            if let Some(mut table_node) = self.document.tree.get_mut(table) {
+                table_node.detach();
+            }
+        }
+    }
+
+    /// Apply `text::clean_ocr_text` to the body's text nodes outside code, and
+    /// rejoin words hyphenated across adjacent text nodes (e.g. hOCR line spans)
+    fn clean_ocr_artifacts(&mut self) {
+        let body_selector = Selector::parse("body").unwrap();
+        let Some(body) = self.document.select(&body_selector).next() else {
+            return;
+        };
+        let mut texts: Vec<(ego_tree::NodeId, String, bool)> = body.descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let in_code = node.ancestors().filter_map(ElementRef::wrap)
+                    .any(|ancestor| matches!(ancestor.value().name(), "pre" | "code" | "script" | "style"));
+                (!in_code).then(|| (node.id(), text::clean_ocr_text(text), false))
+            })
+            .collect();
+        let non_blank: Vec<usize> = (0..texts.len()).filter(|&index| !texts[index].1.trim().is_empty()).collect();
+        for pair in non_blank.windows(2) {
+            let (previous, index) = (pair[0], pair[1]);
+            if !text::ends_with_broken_word(&texts[previous].1) || !text::starts_with_word_rest(&texts[index].1) {
+                continue;
+            }
+            // Move the rest of the word over, since text nodes are joined with spaces
+            let next = texts[index].1.trim_start();
+            let split = next.find(char::is_whitespace).unwrap_or(next.len());
+            let (rest, remainder) = (next[..split].to_string(), next[split..].to_string());
+            let stem = texts[previous].1.trim_end().trim_end_matches('-').to_string();
+            texts[previous].1 = stem + &rest;
+            texts[index].1 = remainder;
+            texts[previous].2 = true;
+            texts[index].2 = true;
+        }
+
+        for (id, cleaned, joined) in texts {
+            let Some(mut node) = self.document.tree.get_mut(id) else {
+                continue;
+            };
+            let changed = joined || node.value().as_text().is_some_and(|text| **text != *cleaned);
+            if changed {
+                *node.value() = scraper::Node::Text(scraper::node::Text { text: cleaned.as_str().into() });
+            }
+        }
+    }
+
+    /// Replace the hydration payload with the article found in it, appended to the body
+    fn lift_hydration_content(&mut self) {
+        let Some(html) = hydration::hydration_html(&self.document) else {
+            return;
+        };
+        if self.options.debug {
+            println!("Using article content from the hydration payload");
+        }
+        let body_selector = Selector::parse("body").unwrap();
+        let Some(body) = self.document.select(&body_selector).next().map(|body| body.id()) else {
+            return;
+        };
+
+        // The payload is consumed; don't let it leak into the content
+        let payload_selector = Selector::parse(hydration::PAYLOAD_SELECTOR).unwrap();
+        let payloads: Vec<_> = self.document.select(&payload_selector).map(|script| script.id()).collect();
+        for payload in payloads {
+            if let Some(mut node) = self.document.tree.get_mut(payload) {
+                node.detach();
+            }
+        }
+
+        let fragment = Html::parse_fragment(&format!("<div>{}</div>", html));
+        for child in fragment.root_element().children() {
+            append_subtree(&mut self.document.tree, body, child);
+        }
+    }
+
+    /// Remove the elements whose class, id or role marks them unlikely to
+    /// hold content, with their subtrees, below the scope root
+    ///
+    /// Data tables and code are kept. Structural elements such as `<header>`
+    /// inside the article stay too; they are only skipped when scoring.
     fn remove_unlikely_candidates_from_dom(&mut self) {
-        // This would remove unlikely elements from the DOM
-        // For now, we'll handle this in the candidate filtering stage
-        // In a full implementation, this would modify the document HTML
+        let Some(scope) = self.scope_root() else {
+            return;
+        };
+        let unlikely: Vec<_> = scope.descendants()
+            .skip(1)
+            .filter_map(ElementRef::wrap)
+            .filter(|element| is_unlikely_by_name(element)
+                && !in_data_table(element)
+                && !has_ancestor_tag(element, "table", None, None)
+                && !has_ancestor_tag(element, "code", None, None))
+            .map(|element| element.id())
+            .collect();
         if self.options.debug {
-            println!("Removing unlikely candidates from DOM");
+            println!("Removing {} unlikely candidates from DOM", unlikely.len());
         }
+        dom::remove(&mut self.document, &unlikely);
     }
     
     fn remove_empty_paragraphs(&mut self) {
-        // Remove paragraphs with no meaningful content
-        // This would be implemented by modifying the document HTML
-        // For now, we handle this during candidate selection
+        let empty = dom::empty_paragraphs(&self.document);
         if self.options.debug {
-            println!("Removing empty paragraphs");
+            println!("Removing {} empty paragraphs", empty.len());
         }
+        dom::remove(&mut self.document, &empty);
     }
     
     fn remove_nodes_by_tag(&mut self, tag_name: &str) {
-        // This is a conceptual implementation - in practice we'd need to modify the HTML string
-        // or use a different approach since scraper doesn't allow DOM modification
+        let selector = Selector::parse(tag_name).unwrap();
+        // JSON-LD and hydration payloads are data, read again by later parses
+        let data_selector = Selector::parse(&format!("script[type='application/ld+json' i], {}", hydration::PAYLOAD_SELECTOR)).unwrap();
+        let nodes: Vec<_> = self.document.select(&selector)
+            .filter(|element| !data_selector.matches(element))
+            .map(|element| element.id())
+            .collect();
         if self.options.debug {
-            println!("Removing {} tags", tag_name);
+            println!("Removing {} {} tags", nodes.len(), tag_name);
         }
+        dom::remove(&mut self.document, &nodes);
     }
     
     fn replace_font_tags(&mut self) {
-        // Replace font tags with span tags in the HTML
+        let fonts = dom::select_ids(&self.document, &Selector::parse("font").unwrap());
         if self.options.debug {
-            println!("Replacing font tags with span tags");
+            println!("Replacing {} font tags with span tags", fonts.len());
+        }
+        for font in fonts {
+            dom::rename(&mut self.document, font, "span");
         }
     }
     
     fn replace_brs(&mut self) {
-        // Convert sequences of <br> tags to paragraph breaks
+        let replaced = dom::replace_br_chains(&mut self.document);
         if self.options.debug {
-            println!("Converting <br> sequences to paragraphs");
+            println!("Converted {} <br> sequences to paragraphs", replaced);
         }
     }
     
-    fn convert_divs_to_paragraphs(&mut self) {
-        // Convert DIV elements to P elements where appropriate
-        if self.options.debug {
-            println!("Converting appropriate DIVs to paragraphs");
+    /// Wrap the text runs of a page without any `p`, `td` or `pre` into
+    /// paragraphs, split at blank lines and runs of `<br>`s
+    fn wrap_flat_text(&mut self) {
+        let block_selector = Selector::parse("p, td, pre").unwrap();
+        if self.document.select(&block_selector).next().is_some() {
+            return;
         }
-    }
-    
-    fn clean_article_content(&self, content: &str) -> String {
-        if self.options.debug {
-            println!("Cleaning article content");
+        let body_selector = Selector::parse("body").unwrap();
+        let Some(mut container) = self.document.select(&body_selector).next() else {
+            return;
+        };
+        // Look through wrappers such as `<body><div>...</div></body>`
+        loop {
+            let has_text = container.children().any(|child| child.value().as_text().is_some_and(|text| !text.trim().is_empty()));
+            let mut elements = container.children().filter_map(ElementRef::wrap);
+            match (elements.next(), elements.next()) {
+                (Some(only), None) if !has_text => container = only,
+                _ => break,
+            }
         }
-        
-        let mut cleaned_content = content.to_string();
-        
-        if self.options.debug {
-            println!("Original content before cleaning: {}", cleaned_content);
+        let container_id = container.id();
+        let children: Vec<_> = container.children().map(|child| child.id()).collect();
+
+        enum Item {
+            Node(ego_tree::NodeId),
+            Text(String),
+            Block(ego_tree::NodeId),
+            Break,
         }
-        
-        // Remove navigation elements and other unwanted content
-        let unwanted_patterns = [
-            r"(?s)<nav[^>]*>.*?</nav>",
-            r"(?s)<aside[^>]*>.*?</aside>",
-            r"(?s)<header[^>]*>.*?</header>",
-            r"(?s)<footer[^>]*>.*?</footer>",
-            r#"(?s)<div[^>]*class=["'][^"']*sidebar[^"']*["'][^>]*>.*?</div>"#,
-            r#"(?s)<div[^>]*class=["'][^"']*navigation[^"']*["'][^>]*>.*?</div>"#,
-        ];
-        
-        for pattern in &unwanted_patterns {
-            let re = regex::Regex::new(pattern).unwrap();
-            cleaned_content = re.replace_all(&cleaned_content, "").to_string();
+        let blank_line = &regexps::get_regexps().blank_line;
+        let mut items = Vec::new();
+        let mut after_br = false;
+        for &id in &children {
+            let Some(node) = self.document.tree.get(id) else {
+                continue;
+            };
+            match node.value() {
+                scraper::Node::Text(text) => {
+                    for (index, piece) in blank_line.split(text).enumerate() {
+                        if index > 0 {
+                            items.push(Item::Break);
+                        }
+                        items.push(Item::Text(piece.to_string()));
+                    }
+                    after_br &= text.trim().is_empty();
+                }
+                scraper::Node::Element(element) if element.name() == "br" => {
+                    // A second <br> in a row ends the paragraph
+                    if after_br {
+                        items.push(Item::Break);
+                    } else {
+                        items.push(Item::Node(id));
+                    }
+                    after_br = true;
+                }
+                scraper::Node::Element(element) if is_phrasing_content(element.name()) || matches!(element.name(), "a" | "del" | "ins") => {
+                    items.push(Item::Node(id));
+                    after_br = false;
+                }
+                _ => {
+                    items.push(Item::Break);
+                    items.push(Item::Block(id));
+                    items.push(Item::Break);
+                    after_br = false;
+                }
+            }
         }
-        
-        // Clean up excessive whitespace
-        let re_whitespace = regex::Regex::new(r"\s{2,}").unwrap();
-        cleaned_content = re_whitespace.replace_all(&cleaned_content, " ").to_string();
-        
+        let has_text = |items: &[Item], tree: &ego_tree::Tree<scraper::Node>| items.iter().any(|item| match item {
+            Item::Text(text) => !text.trim().is_empty(),
+            Item::Node(id) => tree.get(*id).and_then(ElementRef::wrap).is_some_and(|element| element.text().any(|text| !text.trim().is_empty())),
+            Item::Block(_) | Item::Break => false,
+        });
+        let run_count = items.split(|item| matches!(item, Item::Break)).filter(|run| has_text(run, &self.document.tree)).count();
+        if run_count == 0 {
+            return;
+        }
+        if self.options.debug {
+            println!("Wrapping {} text runs of a flat page into paragraphs", run_count);
+        }
+
+        let paragraph_fragment = Html::parse_fragment("<p></p>");
+        let paragraph = paragraph_fragment.select(&Selector::parse("p").unwrap()).next().unwrap().value().clone();
+        for &id in &children {
+            if let Some(mut node) = self.document.tree.get_mut(id) {
+                node.detach();
+            }
+        }
+        for run in items.split(|item| matches!(item, Item::Break)) {
+            let wrap = has_text(run, &self.document.tree);
+            let mut nodes = Vec::new();
+            for item in run {
+                match item {
+                    Item::Node(id) | Item::Block(id) => nodes.push(*id),
+                    Item::Text(text) => nodes.push(self.document.tree.orphan(scraper::Node::Text(scraper::node::Text { text: text.as_str().into() })).id()),
+                    Item::Break => {}
+                }
+            }
+            // Leading and trailing <br>s of a paragraph are redundant
+            let is_br = |id: &ego_tree::NodeId| self.document.tree.get(*id)
+                .and_then(|node| node.value().as_element())
+                .is_some_and(|element| element.name() == "br");
+            let is_blank = |id: &ego_tree::NodeId| self.document.tree.get(*id)
+                .and_then(|node| node.value().as_text())
+                .is_some_and(|text| text.trim().is_empty());
+            if !wrap {
+                nodes.retain(|id| !is_br(id));
+            } else {
+                while nodes.first().is_some_and(|id| is_br(id) || is_blank(id)) {
+                    nodes.remove(0);
+                }
+                while nodes.last().is_some_and(|id| is_br(id) || is_blank(id)) {
+                    nodes.pop();
+                }
+            }
+            let Some(mut container) = self.document.tree.get_mut(container_id) else {
+                return;
+            };
+            if wrap {
+                let mut p = container.append(scraper::Node::Element(paragraph.clone()));
+                for id in nodes {
+                    p.append_id(id);
+                }
+            } else {
+                for id in nodes {
+                    container.append_id(id);
+                }
+            }
+        }
+    }
+
+    fn clean_article_content(&self, content: &str) -> String {
+        if self.options.debug {
+            println!("Cleaning article content");
+            println!("Original content before cleaning: {}", content);
+        }
+        let docs = self.profile == ExtractionProfile::Docs;
+
+        // Drop fishy tables, lists and divs as Readability.js does
+        let conditionally_cleaned = if self.flags.clean_conditionally { self.clean_conditionally(content) } else { None };
+        let (mut fragment, root) = dom::parse_content(conditionally_cleaned.as_deref().unwrap_or(content));
+
+        // Remove navigation and other page chrome; data tables are kept whole
+        let chrome_selector = Selector::parse("nav, aside, header, footer, div[class*='sidebar'], div[class*='navigation']").unwrap();
+        let time_selector = Selector::parse("time").unwrap();
+        for id in dom::select_ids(&fragment, &chrome_selector) {
+            let Some(element) = fragment.tree.get(id).and_then(ElementRef::wrap) else {
+                continue;
+            };
+            // Already removed with an ancestor
+            if !element.ancestors().any(|ancestor| ancestor.id() == root) || in_data_table(&element) {
+                continue;
+            }
+            // Keep correction notices even when they live in asides or footers
+            let value = element.value();
+            let names = [value.attr("class"), value.id(), value.attr("role")].iter().flatten().copied().collect::<Vec<_>>().join(" ");
+            if has_correction_class(&names) || (docs && keep_in_docs(&element, &names)) {
+                continue;
+            }
+
+            // Keep dates from article headers and footers (event dates, "updated" stamps)
+            let times: Vec<ego_tree::NodeId> = if matches!(value.name(), "header" | "footer") {
+                element.select(&time_selector).map(|time| time.id()).collect()
+            } else {
+                Vec::new()
+            };
+            if !times.is_empty() {
+                let dates = dom::wrap(&mut fragment, &times, "p");
+                for &time in &times[1..] {
+                    let space = fragment.tree.orphan(scraper::Node::Text(scraper::node::Text { text: " ".into() })).id();
+                    if let Some(mut time) = fragment.tree.get_mut(time) {
+                        time.insert_id_before(space);
+                    }
+                }
+                if let Some(mut element) = fragment.tree.get_mut(id) {
+                    element.insert_id_before(dates);
+                }
+            }
+            dom::remove(&mut fragment, &[id]);
+        }
+
+        // Clean up excessive whitespace, leaving preformatted text and code as written
+        let texts: Vec<ego_tree::NodeId> = fragment.tree.root().descendants()
+            .filter(|node| node.value().is_text())
+            .filter(|node| !node.ancestors().filter_map(ElementRef::wrap).any(|ancestor| matches!(ancestor.value().name(), "pre" | "code")))
+            .map(|node| node.id())
+            .collect();
+        for id in texts {
+            if let Some(mut node) = fragment.tree.get_mut(id) {
+                if let scraper::Node::Text(text) = node.value() {
+                    if let Some(collapsed) = collapse_whitespace_runs(&text.text) {
+                        text.text = collapsed.into();
+                    }
+                }
+            }
+        }
+
+        // Data table marks are internal
+        let marked_selector = Selector::parse(&format!("[{}]", DATA_TABLE_ATTR)).unwrap();
+        for id in dom::select_ids(&fragment, &marked_selector) {
+            dom::remove_attr(&mut fragment, id, DATA_TABLE_ATTR);
+        }
+
+        let cleaned_content = fragment.tree.get(root).and_then(ElementRef::wrap).map(|root| root.inner_html()).unwrap_or_default();
         cleaned_content.trim().to_string()
     }
-    
 
+    /// Readability.js's `_cleanConditionally` over `<fieldset>`s, `<table>`s,
+    /// `<ul>`s and `<div>`s of content HTML
+    ///
+    /// Elements are judged last to first, as they stand after the removals
+    /// so far, so a container is judged without the fishy parts inside it.
+    /// Returns `None` if nothing was removed.
+    fn clean_conditionally(&self, content: &str) -> Option<String> {
+        let mut fragment = Html::parse_fragment(content);
+        let root = fragment.root_element().id();
+        // Tables of contents were already dealt with by `toc_policy`
+        let tocs: Vec<ego_tree::NodeId> = find_tocs(fragment.root_element()).iter().map(|toc| toc.id()).collect();
+        let mut removed = false;
+        for tag in ["fieldset", "table", "ul", "div"] {
+            let ids = dom::select_ids(&fragment, &Selector::parse(tag).unwrap());
+            for id in ids.into_iter().rev() {
+                let Some(element) = fragment.tree.get(id).and_then(ElementRef::wrap) else {
+                    continue;
+                };
+                // Already removed with an ancestor
+                if !element.ancestors().any(|ancestor| ancestor.id() == root) {
+                    continue;
+                }
+                if tocs.iter().any(|&toc| element.id() == toc || element.ancestors().any(|ancestor| ancestor.id() == toc)) {
+                    continue;
+                }
+                // Readability.js turns these divs into paragraphs before cleaning
+                if tag == "div"
+                    && (!has_child_block_element(&element)
+                        || (has_single_tag_inside_element(&element, "p") && get_link_density(&element) < 0.25))
+                {
+                    continue;
+                }
+                if self.is_fishy(&element, tag) {
+                    dom::remove(&mut fragment, &[id]);
+                    removed = true;
+                }
+            }
+        }
+        removed.then(|| fragment.root_element().inner_html())
+    }
+
+    /// Whether `element`, a `tag`, looks like chrome rather than content:
+    /// negative class weight, few commas, and too many links, images, inputs,
+    /// list items or embeds for its text
+    fn is_fishy(&self, element: &ElementRef, tag: &str) -> bool {
+        let text = get_inner_text(element, true);
+        let content_length = text.chars().count();
+        let descendants = || element.descendants().skip(1).filter_map(ElementRef::wrap);
+        let count = |tags: &[&str]| descendants().filter(|descendant| tags.contains(&descendant.value().name())).count();
+        // Share of the text inside descendants that are `tags`
+        let text_density = |tags: &[&str]| {
+            if content_length == 0 {
+                return 0.0;
+            }
+            let inner: usize = descendants()
+                .filter(|descendant| tags.contains(&descendant.value().name()))
+                .map(|descendant| get_inner_text(&descendant, true).chars().count())
+                .sum();
+            inner as f64 / content_length as f64
+        };
+
+        let is_list = matches!(tag, "ul" | "ol") || {
+            let list_length: usize = descendants()
+                .filter(|descendant| matches!(descendant.value().name(), "ul" | "ol"))
+                .map(|list| get_inner_text(&list, true).chars().count())
+                .sum();
+            content_length > 0 && list_length as f64 / content_length as f64 > 0.9
+        };
+
+        // Data tables and code are content whatever their shape
+        if in_data_table(element)
+            || has_ancestor_tag(element, "code", Some(3), None)
+            || descendants().any(|descendant| descendant.value().name() == "table" && is_marked_data_table(&descendant))
+        {
+            return false;
+        }
+
+        let weight = self.get_class_weight(element);
+        if weight < 0.0 {
+            return true;
+        }
+        if get_char_count(&text, Some(',')) >= 10 {
+            return false;
+        }
+
+        let paragraphs = count(&["p"]);
+        let images = count(&["img"]);
+        let list_items = count(&["li"]) as isize - 100;
+        let inputs = count(&["input"]);
+        let heading_density = text_density(&["h1", "h2", "h3", "h4", "h5", "h6"]);
+
+        let mut embeds = 0;
+        for embed in descendants().filter(|descendant| matches!(descendant.value().name(), "object" | "embed" | "iframe")) {
+            let is_video = |value: &str| match &self.options.allowed_video_regex {
+                Some(regex) => regex.is_match(value),
+                None => is_video_url(value),
+            };
+            if embed.value().attrs().any(|(_, value)| is_video(value))
+                || (embed.value().name() == "object" && is_video(&embed.inner_html()))
+            {
+                return false;
+            }
+            embeds += 1;
+        }
+
+        if contains_ad_words(&text) || contains_loading_words(&text) {
+            return true;
+        }
+
+        let link_density = get_link_density(element);
+        let text_density = text_density(&[
+            "span", "li", "td", "blockquote", "dl", "div", "img", "ol", "p", "pre", "table", "ul",
+        ]);
+        let in_figure = has_ancestor_tag(element, "figure", Some(3), None);
+        let modifier = self.options.link_density_modifier;
+
+        let remove = (!in_figure && images > 1 && (paragraphs as f64) < images as f64 * 0.5)
+            || (!is_list && list_items > paragraphs as isize)
+            || inputs > paragraphs / 3
+            || (!is_list
+                && !in_figure
+                && heading_density < 0.9
+                && content_length < 25
+                && (images == 0 || images > 2)
+                && link_density > 0.0)
+            || (!is_list && weight < 25.0 && link_density > 0.2 + modifier)
+            || (weight >= 25.0 && link_density > 0.5 + modifier)
+            || (embeds == 1 && content_length < 75)
+            || embeds > 1
+            || (images == 0 && text_density == 0.0);
+
+        // Simple lists of images stay
+        if is_list && remove {
+            let nested = element
+                .children()
+                .filter_map(ElementRef::wrap)
+                .any(|child| child.children().filter_map(ElementRef::wrap).count() > 1);
+            if !nested && images == count(&["li"]) {
+                return false;
+            }
+        }
+        remove
+    }
 
     fn get_inner_text_from_ref(&self, element: &ElementRef, normalize_spaces: bool) -> String {
         let text = element.text().collect::<Vec<_>>().join(" ");
@@ -832,515 +2969,2604 @@ impl Readability {
     }
 }
 
-/// Check if a document is likely to be readable/parseable
-pub fn is_probably_readerable(html: &str, options: Option<ReadabilityOptions>) -> bool {
-    let document = Html::parse_document(html);
-    let opts = options.unwrap_or_default();
-    
-    // Scale minimum score based on char_threshold
-    let min_content_length = if opts.char_threshold > 0 { 
-        opts.char_threshold 
-    } else { 
-        140  // Default fallback
-    };
-    
-    // Scale min_score based on char_threshold - lower thresholds need lower scores
-    let min_score = if min_content_length <= 20 {
-        8.0   // Very lenient for very short content
-    } else if min_content_length <= 50 {
-        20.0  // Strict for short content
-    } else if min_content_length <= 100 {
-        30.0  // Strict for medium content
-    } else {
-        40.0  // Strict for longer content
+/// Deep-copy `source` as the last child of `parent`, returning the copy's id
+fn append_subtree(
+    tree: &mut ego_tree::Tree<scraper::Node>,
+    parent: ego_tree::NodeId,
+    source: ego_tree::NodeRef<scraper::Node>,
+) -> Option<ego_tree::NodeId> {
+    let copy = tree.get_mut(parent)?.append(source.value().clone()).id();
+    for child in source.children() {
+        append_subtree(tree, copy, child);
+    }
+    Some(copy)
+}
+
+/// A copy of `document` without the nodes detached from its tree
+fn compact(document: &Html) -> Html {
+    let mut compacted = Html::new_document();
+    compacted.errors = document.errors.clone();
+    compacted.quirks_mode = document.quirks_mode;
+    let root = compacted.tree.root().id();
+    for child in document.tree.root().children() {
+        append_subtree(&mut compacted.tree, root, child);
+    }
+    compacted
+}
+
+/// Characters of visible text in a document's body, ignoring scripts
+fn rendered_text_length(document: &Html) -> usize {
+    let body_selector = Selector::parse("body").unwrap();
+    let Some(body) = document.select(&body_selector).next() else {
+        return 0;
     };
+    body.descendants()
+        .filter(|node| {
+            let in_script = node.ancestors().filter_map(ElementRef::wrap)
+                .any(|element| matches!(element.value().name(), "script" | "style" | "noscript"));
+            !in_script
+        })
+        .filter_map(|node| node.value().as_text())
+        .map(|text| text.trim().chars().count())
+        .sum()
+}
+
+/// The only child element of `element`, if it is a `tag`
+fn single_child<'a>(element: ElementRef<'a>, tag: &str) -> Option<ElementRef<'a>> {
+    has_single_tag_inside_element(&element, tag)
+        .then(|| element.children().find_map(ElementRef::wrap))
+        .flatten()
+}
+
+/// Alternates among the `nb_top_candidates` that must share an ancestor with
+/// the best candidate for that ancestor to become the top candidate
+const MINIMUM_TOP_CANDIDATES: usize = 3;
+
+/// Paragraph text above which `score_paragraphs` uses the rayon thread pool;
+/// below it, spawning work costs more than it saves
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_TEXT_BYTES: usize = 256 * 1024;
+
+/// Content score of each paragraph, or `None` for those too short to count;
+/// with the `parallel` feature, large documents are scored on the rayon
+/// thread pool
+fn score_paragraphs(paragraphs: &[ElementRef], lang: Option<&str>) -> Vec<Option<f64>> {
+    let style = CommaStyle::for_lang(lang);
+
+    // The tree can't be shared between threads, so only large documents pay
+    // for copying each paragraph's text out
+    #[cfg(feature = "parallel")]
+    if paragraphs.iter().flat_map(|paragraph| paragraph.text()).map(str::len).sum::<usize>() >= PARALLEL_MIN_TEXT_BYTES {
+        use rayon::prelude::*;
+        let texts: Vec<String> = paragraphs.iter().map(|paragraph| paragraph.text().collect::<Vec<_>>().join(" ")).collect();
+        return texts.par_iter().map(|text| paragraph_score([text.as_str()], style)).collect();
+    }
+    paragraphs.iter().map(|paragraph| paragraph_score(paragraph.text(), style)).collect()
+}
+
+/// Content score of a paragraph from its text nodes, or `None` when it is too
+/// short to count
+///
+/// Measures the text as if the chunks were joined with spaces and whitespace
+/// collapsed, without building that string.
+fn paragraph_score<'a>(chunks: impl IntoIterator<Item = &'a str>, style: CommaStyle) -> Option<f64> {
+    let (mut text_length, mut words, mut commas) = (0, 0usize, 0);
+    for chunk in chunks {
+        for word in chunk.split_whitespace() {
+            text_length += word.len();
+            words += 1;
+        }
+        commas += style.count_marks(chunk);
+    }
+    text_length += words.saturating_sub(1);
+    if text_length < 25 {
+        return None;
+    }
+    if style == CommaStyle::Spaces {
+        commas += words - 1;
+    }
+
+    // Calculate content score for this paragraph (matching JavaScript algorithm)
+    let mut content_score = 1.0;
+
+    // Add points for any commas (or script equivalents) within this paragraph
+    content_score += commas as f64;
+
+    // For every 100 characters in this paragraph, add another point. Up to 3 points.
+    content_score += (text_length as f64 / 100.0).min(3.0);
+    Some(content_score)
+}
+
+/// Whether the class, id or role of `element` marks it as unlikely to hold
+/// content, the test Readability.js removes elements by
+fn is_unlikely_by_name(element: &ElementRef) -> bool {
+    // Don't filter these tags
+    if matches!(element.value().name(), "body" | "a" | "table" | "tbody" | "tr" | "td" | "th" | "article" | "section") {
+        return false;
+    }
     
-    // Look for content-bearing elements
-    let content_selectors = ["p", "pre", "article", "div"];
-    let mut score = 0.0;
-    let mut total_text_length = 0;
+    // Check class and id attributes
+    if regexps::is_unlikely_class_and_id(element.value().attr("class").unwrap_or(""), element.value().attr("id").unwrap_or("")) {
+        return true;
+    }
     
-    for selector_str in &content_selectors {
-        if let Ok(selector) = Selector::parse(selector_str) {
-            for element in document.select(&selector) {
-                let text_content = element.text().collect::<String>();
-                let text_length = text_content.trim().len();
-                
-                if text_length < 10 {  // Skip very short elements (reduced from 25)
-                    continue;
-                }
-                
-                total_text_length += text_length;
-                
-                // Check for unlikely candidates
-                let class_and_id = format!("{} {}", 
-                    element.value().attr("class").unwrap_or(""),
-                    element.value().attr("id").unwrap_or("")
-                );
-                
-                if is_unlikely_candidate(&class_and_id) {
-                    score -= 5.0;  // Penalize unlikely candidates
-                    continue;
-                }
-                
-                // Score based on element type and content length
-                let element_score = match element.value().name() {
-                    "article" => (text_length as f64 * 0.5).min(30.0),
-                    "p" => (text_length as f64 * 0.3).min(20.0),
-                    "pre" => (text_length as f64 * 0.4).min(25.0),
-                    "div" => {
-                        // More lenient for divs when using low thresholds
-                        if min_content_length <= 50 && text_length > 20 {
-                            (text_length as f64 * 0.25).min(15.0)
-                        } else if text_length > 80 {
-                            (text_length as f64 * 0.2).min(15.0)
-                        } else {
-                            0.0
-                        }
-                    },
-                    _ => 0.0,
-                };
-                
-                score += element_score;
-                
-                // Early return if we have enough score
-                if score > min_score && total_text_length >= min_content_length {
-                    return true;
-                }
+    // Check for specific roles that are unlikely to contain article content
+    element.value().attr("role").is_some_and(|role| {
+        matches!(role, "menu" | "menubar" | "complementary" | "navigation" | "alert" | "alertdialog" | "dialog")
+    })
+}
+
+fn is_live_update_element(element: &ElementRef) -> bool {
+    element.value().attr("itemprop") == Some("liveBlogUpdate")
+        || is_live_update(element.value().attr("class").unwrap_or(""))
+}
+
+fn is_slide_element(element: &ElementRef) -> bool {
+    let value = element.value();
+    value.attr("data-slide").is_some()
+        || value.attr("data-slide-index").is_some()
+        || is_slide(&format!("{} {}", value.attr("class").unwrap_or(""), value.attr("id").unwrap_or("")))
+}
+
+/// If `element` is (inside) an entry such as a live-blog update or a slide,
+/// return the container holding the entries instead, so all of them are in scope
+fn widen_past(element: ElementRef<'_>, is_entry: fn(&ElementRef) -> bool) -> ElementRef<'_> {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .filter(is_entry)
+        .last()
+        .and_then(|update| update.parent())
+        .and_then(ElementRef::wrap)
+        .unwrap_or(element)
+}
+
+/// Whether `list` is a table of contents: several links, nearly all of its
+/// text in links to `#fragments`
+fn is_toc_list(list: &ElementRef) -> bool {
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let hash_links: Vec<ElementRef> = list
+        .select(&link_selector)
+        .filter(|link| link.value().attr("href").is_some_and(|href| href.starts_with('#') && href.len() > 1))
+        .collect();
+    if hash_links.len() < 3 {
+        return false;
+    }
+    let text_length = get_inner_text(list, true).len();
+    let hash_text_length: usize = hash_links.iter().map(|link| get_inner_text(link, true).len()).sum();
+    text_length > 0 && hash_text_length as f64 / text_length as f64 >= 0.8
+}
+
+/// Unwrap or drop the elements of content HTML whose tag isn't in
+/// `allowed_tags` (compared case-insensitively), or `None` if all are allowed
+fn apply_allowed_tags(content: &str, allowed_tags: &[String], action: DisallowedTagAction) -> Option<String> {
+    let mut fragment = Html::parse_fragment(content);
+    let disallowed: Vec<ego_tree::NodeId> = fragment
+        .root_element()
+        .descendants()
+        .skip(1)
+        .filter_map(ElementRef::wrap)
+        .filter(|element| !allowed_tags.iter().any(|tag| tag.eq_ignore_ascii_case(element.value().name())))
+        .map(|element| element.id())
+        .collect();
+    if disallowed.is_empty() {
+        return None;
+    }
+    match action {
+        DisallowedTagAction::Unwrap => {
+            for id in disallowed {
+                dom::unwrap(&mut fragment, id);
             }
         }
+        DisallowedTagAction::Drop => dom::remove(&mut fragment, &disallowed),
     }
-    
-    // Final check: require both minimum score and minimum content length
-    score > min_score && total_text_length >= min_content_length
+    Some(fragment.root_element().inner_html())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::path::Path;
-    use serde_json;
+/// Table-of-contents boxes under `root`: each TOC list, or its parent when
+/// that only adds a caption such as "Contents"
+fn find_tocs(root: ElementRef) -> Vec<ElementRef> {
+    let list_selector = Selector::parse("ul, ol").unwrap();
+    let mut tocs: Vec<ElementRef> = Vec::new();
+    for list in root.select(&list_selector) {
+        if tocs.iter().any(|toc| list.ancestors().any(|ancestor| ancestor.id() == toc.id())) || !is_toc_list(&list) {
+            continue;
+        }
+        let toc = list
+            .parent()
+            .and_then(ElementRef::wrap)
+            .filter(|parent| parent.id() != root.id() && !matches!(parent.value().name(), "ul" | "ol" | "li"))
+            .filter(|parent| get_inner_text(parent, true).len() < get_inner_text(&list, true).len() + 30)
+            .unwrap_or(list);
+        tocs.push(toc);
+    }
+    tocs
+}
 
-    // Helper function to create a readability parser
-    fn create_parser(html: &str) -> Readability {
-        Readability::new(html, Some(ReadabilityOptions {
-            debug: true,
-            char_threshold: 25,  // Lower threshold for testing
-            ..Default::default()
-        })).unwrap()
+/// Apply `policy` to table-of-contents boxes in content HTML, or `None` if
+/// nothing changed
+fn apply_toc_policy(content: &str, policy: TocPolicy) -> Option<String> {
+    if policy == TocPolicy::Keep {
+        return None;
     }
 
-    // Helper function to create a readability parser with custom options
-    fn create_parser_with_options(html: &str, options: ReadabilityOptions) -> Readability {
-        Readability::new(html, Some(options)).unwrap()
+    let mut fragment = Html::parse_fragment(content);
+    let root = fragment.root_element();
+    let tocs: Vec<ego_tree::NodeId> = find_tocs(root).iter().map(|toc| toc.id()).collect();
+    if tocs.is_empty() {
+        return None;
     }
 
-    // Helper function to load test case files
-    fn load_test_case(test_dir: &str) -> Result<(String, String, serde_json::Value), Box<dyn std::error::Error>> {
-        let base_path = Path::new("mozzila-readability/test/test-pages").join(test_dir);
-        
-        let source_path = base_path.join("source.html");
-        let expected_content_path = base_path.join("expected.html");
-        let expected_metadata_path = base_path.join("expected-metadata.json");
-        
-        let source = fs::read_to_string(&source_path)
-            .map_err(|e| format!("Failed to read source.html for {}: {}", test_dir, e))?;
-        let expected_content = fs::read_to_string(&expected_content_path)
-            .map_err(|e| format!("Failed to read expected.html for {}: {}", test_dir, e))?;
-        let expected_metadata: serde_json::Value = serde_json::from_str(
-            &fs::read_to_string(&expected_metadata_path)
-                .map_err(|e| format!("Failed to read expected-metadata.json for {}: {}", test_dir, e))?
-        ).map_err(|e| format!("Failed to parse expected-metadata.json for {}: {}", test_dir, e))?;
-        
-        Ok((source, expected_content, expected_metadata))
+    let replacement = match policy {
+        TocPolicy::Rebuild => {
+            let heading_selector = Selector::parse("h2[id], h3[id], h4[id]").unwrap();
+            let items: String = root
+                .select(&heading_selector)
+                .filter(|heading| !heading.ancestors().any(|ancestor| tocs.contains(&ancestor.id())))
+                .map(|heading| {
+                    let id = heading.value().attr("id").unwrap_or_default();
+                    format!("<li><a href=\"#{}\">{}</a></li>", escape_html(id), escape_html(&get_inner_text(&heading, true)))
+                })
+                .collect();
+            (!items.is_empty()).then(|| Html::parse_fragment(&format!("<ul>{}</ul>", items)))
+        }
+        _ => None,
+    };
+
+    // The rebuilt list takes the place of the first box
+    if let Some(list) = replacement.as_ref().and_then(|list| list.root_element().first_child()) {
+        let root = fragment.root_element().id();
+        if let Some(copy) = append_subtree(&mut fragment.tree, root, list) {
+            if let Some(mut first) = fragment.tree.get_mut(tocs[0]) {
+                first.insert_id_before(copy);
+            }
+        }
+    }
+    dom::remove(&mut fragment, &tocs);
+    Some(fragment.root_element().inner_html())
+}
+
+/// Text and `datetime` of every `<time>` element in content HTML, in document order
+fn collect_times(content: &str) -> Vec<(String, Option<String>)> {
+    let time_selector = Selector::parse("time").unwrap();
+    Html::parse_fragment(content)
+        .select(&time_selector)
+        .map(|time| (get_inner_text(&time, true), time.value().attr("datetime").map(str::to_string)))
+        .collect()
+}
+
+/// Longest text of an author bio box, in characters
+const MAX_AUTHOR_BIO_CHARS: usize = 600;
+
+/// Trailing top-level content blocks searched for a bio opening with an author's name
+const AUTHOR_BIO_TAIL_BLOCKS: usize = 3;
+
+/// What follows an author's name at the start of a bio ("Jane Doe is a reporter...")
+const BIO_OPENINGS: &[&str] = &[" is ", " was ", " has ", " writes ", " covers ", " reports ", ", a ", ", an ", ", the "];
+
+/// Find an author bio box in content HTML: a short block named like one, or
+/// one of the last blocks opening with an author's name and a description.
+/// Returns the content without it and the bio text
+fn split_author_bio(content: &str, author_names: &[&str]) -> Option<(String, String)> {
+    let mut fragment = Html::parse_fragment(content);
+    let root = fragment.root_element();
+    let top_level: Vec<ElementRef> = root.children().filter_map(ElementRef::wrap).collect();
+    let tail = &top_level[top_level.len().saturating_sub(AUTHOR_BIO_TAIL_BLOCKS)..];
+    let opens_with_author = |text: &str| {
+        author_names.iter().filter(|name| name.chars().count() >= 3).any(|name| {
+            text.strip_prefix(name).is_some_and(|rest| BIO_OPENINGS.iter().any(|opening| rest.starts_with(opening)))
+        })
+    };
+    let block_selector = Selector::parse("p, div, section, aside").unwrap();
+    // Document order, so a bio box is found before the paragraphs inside it
+    let bio = root.select(&block_selector).find(|element| {
+        let text = get_inner_text(element, true);
+        if !(20..=MAX_AUTHOR_BIO_CHARS).contains(&text.chars().count()) {
+            return false;
+        }
+        let named = element.value().classes().any(is_author_bio) || element.value().id().is_some_and(is_author_bio);
+        let in_tail = tail.iter().any(|block| element.ancestors().chain(std::iter::once(**element)).any(|node| node.id() == block.id()));
+        named || (in_tail && opens_with_author(&text))
+    })?;
+    let (bio, bio_text) = (bio.id(), get_inner_text(&bio, true));
+    dom::remove(&mut fragment, &[bio]);
+    Some((fragment.root_element().inner_html(), bio_text))
+}
+
+/// Images in content HTML whose only usable source is a lazy-load attribute
+fn count_lazy_images(content: &str) -> usize {
+    let image_selector = Selector::parse("img").unwrap();
+    Html::parse_fragment(content)
+        .select(&image_selector)
+        .filter(|image| {
+            let value = image.value();
+            let src = value.attr("src").map(str::trim).unwrap_or("");
+            let placeholder = src.is_empty() || src.starts_with("data:") || src.contains("placeholder") || src.contains("blank.gif");
+            let lazy = ["data-src", "data-lazy-src", "data-original", "data-srcset", "data-lazy"]
+                .iter()
+                .any(|name| value.attr(name).is_some_and(|lazy_src| !lazy_src.trim().is_empty()));
+            placeholder && lazy
+        })
+        .count()
+}
+
+/// Whether a numeric date such as `03/04/2021` reads differently as
+/// day/month and month/day
+fn is_ambiguous_date(value: &str) -> bool {
+    let Some(date) = value.split_whitespace().next() else {
+        return false;
+    };
+    let parts: Vec<&str> = date.split(['/', '.', '-']).collect();
+    let [first, second, year] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 2 && year.len() != 4 {
+        return false;
+    }
+    match (first.parse::<u32>(), second.parse::<u32>(), year.parse::<u32>()) {
+        (Ok(first), Ok(second), Ok(_)) => first != second && (1..=12).contains(&first) && (1..=12).contains(&second),
+        _ => false,
+    }
+}
+
+/// Inline elements kept in `Article::excerpt_html`
+const EXCERPT_INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "br", "cite", "code", "del", "em", "i", "ins", "mark", "q", "s", "small", "strong", "sub", "sup", "u",
+];
+
+/// Text an excerpt's paragraphs should add up to before it stops
+const EXCERPT_HTML_MIN_CHARS: usize = 100;
+
+/// The first paragraphs of content HTML, at least `EXCERPT_HTML_MIN_CHARS`
+/// long when the content allows, with inline formatting and nothing else
+fn excerpt_html(content: &str, strip_links: bool) -> Option<String> {
+    let paragraph_selector = Selector::parse("p").unwrap();
+    let fragment = Html::parse_fragment(content);
+    let mut excerpt = String::new();
+    let mut length = 0;
+    for paragraph in fragment.select(&paragraph_selector) {
+        let text_length = get_inner_text(&paragraph, true).chars().count();
+        if text_length == 0 {
+            continue;
+        }
+        excerpt.push_str("<p>");
+        for child in paragraph.children() {
+            push_inline_html(child, strip_links, &mut excerpt);
+        }
+        excerpt.push_str("</p>");
+        length += text_length;
+        if length >= EXCERPT_HTML_MIN_CHARS {
+            break;
+        }
+    }
+    (!excerpt.is_empty()).then_some(excerpt)
+}
+
+fn push_inline_html(node: ego_tree::NodeRef<scraper::Node>, strip_links: bool, html: &mut String) {
+    match node.value() {
+        scraper::Node::Text(text) => html.push_str(&escape_html(text)),
+        scraper::Node::Element(element) => {
+            let name = element.name();
+            let kept = EXCERPT_INLINE_TAGS.contains(&name) && !(strip_links && name == "a");
+            if kept {
+                match element.attr("href").filter(|_| name == "a") {
+                    Some(href) => html.push_str(&format!(r#"<a href="{}">"#, escape_html(href))),
+                    None => html.push_str(&format!("<{}>", name)),
+                }
+            }
+            if name == "br" {
+                return;
+            }
+            for child in node.children() {
+                push_inline_html(child, strip_links, html);
+            }
+            if kept {
+                html.push_str(&format!("</{}>", name));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The first `<base href>` of `document` resolved against `base_uri`, or
+/// else `base_uri`
+fn effective_base_uri(document: &Html, base_uri: Option<&str>) -> Option<String> {
+    let base_selector = Selector::parse("base[href]").unwrap();
+    let href = document.select(&base_selector).next()
+        .and_then(|base| base.value().attr("href"))
+        .map(str::trim)
+        .filter(|href| !href.is_empty());
+    match (href, base_uri) {
+        (Some(href), Some(base_uri)) => Some(to_absolute_uri(href, base_uri)),
+        (Some(href), None) if is_url(href) => Some(href.to_string()),
+        (Some(_), None) => None,
+        (None, base_uri) => base_uri.map(str::to_string),
+    }
+}
+
+/// Readability.js's `_fixRelativeUris`: resolve the links and media URLs of
+/// content HTML against `base_uri`, and replace `javascript:` links, dead
+/// once scripts are gone, with their text; `None` if nothing changed
+///
+/// `#fragment` links stay as they are unless a `<base href>` moved
+/// `base_uri` away from `document_uri`, the page's own URL.
+fn fix_relative_uris(content: &str, base_uri: Option<&str>, document_uri: Option<&str>) -> Option<String> {
+    let mut fragment = Html::parse_fragment(content);
+    let mut changed = false;
+    let keep_fragment_links = base_uri == document_uri;
+
+    let link_selector = Selector::parse("a[href], link[href]").unwrap();
+    for id in dom::select_ids(&fragment, &link_selector) {
+        let Some(link) = fragment.tree.get(id).and_then(ElementRef::wrap) else {
+            continue;
+        };
+        let href = link.value().attr("href").unwrap_or("").trim();
+        if href.get(..11).is_some_and(|scheme| scheme.eq_ignore_ascii_case("javascript:")) {
+            let only_text = link.children().count() == 1 && link.children().all(|child| child.value().is_text());
+            if only_text {
+                dom::unwrap(&mut fragment, id);
+            } else {
+                // Keep the children together, as the link did
+                dom::rename(&mut fragment, id, "span");
+                dom::clear_attrs(&mut fragment, id);
+            }
+            changed = true;
+        } else if let Some(base_uri) = base_uri.filter(|_| !href.is_empty()) {
+            let absolute = if href.starts_with('#') && !keep_fragment_links {
+                utils::resolve_uri(href, base_uri).unwrap_or_else(|| href.to_string())
+            } else {
+                to_absolute_uri(href, base_uri)
+            };
+            if absolute != href {
+                dom::set_attr(&mut fragment, id, "href", &absolute);
+                changed = true;
+            }
+        }
+    }
+
+    let Some(base_uri) = base_uri else {
+        return changed.then(|| fragment.root_element().inner_html());
+    };
+    let media_selector = Selector::parse("img, picture, figure, video, audio, source").unwrap();
+    for id in dom::select_ids(&fragment, &media_selector) {
+        let Some(media) = fragment.tree.get(id).and_then(ElementRef::wrap) else {
+            continue;
+        };
+        let mut fixed: Vec<(&str, String)> = Vec::new();
+        for name in ["src", "poster"] {
+            if let Some(value) = media.value().attr(name).map(str::trim).filter(|value| !value.is_empty()) {
+                let absolute = to_absolute_uri(value, base_uri);
+                if absolute != value {
+                    fixed.push((name, absolute));
+                }
+            }
+        }
+        // Data URIs have commas of their own
+        if let Some(srcset) = media.value().attr("srcset").filter(|srcset| !srcset.contains("data:")) {
+            let absolute = srcset
+                .split(',')
+                .map(str::trim)
+                .filter(|candidate| !candidate.is_empty())
+                .map(|candidate| match candidate.split_once(char::is_whitespace) {
+                    Some((url, descriptor)) => format!("{} {}", to_absolute_uri(url, base_uri), descriptor.trim()),
+                    None => to_absolute_uri(candidate, base_uri),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            if absolute != srcset {
+                fixed.push(("srcset", absolute));
+            }
+        }
+        for (name, value) in fixed {
+            dom::set_attr(&mut fragment, id, name, &value);
+            changed = true;
+        }
+    }
+    changed.then(|| fragment.root_element().inner_html())
+}
+
+/// Remove the outermost elements matching `should_remove` from content HTML,
+/// or `None` if nothing matched
+fn remove_elements(content: &str, should_remove: impl Fn(&ElementRef) -> bool) -> Option<String> {
+    let mut fragment = Html::parse_fragment(content);
+    let mut removed: Vec<ego_tree::NodeId> = Vec::new();
+    for element in fragment.root_element().descendants().skip(1).filter_map(ElementRef::wrap) {
+        let inside_removed = element.ancestors().any(|ancestor| removed.contains(&ancestor.id()));
+        if !inside_removed && should_remove(&element) {
+            removed.push(element.id());
+        }
+    }
+    if removed.is_empty() {
+        return None;
+    }
+    dom::remove(&mut fragment, &removed);
+    Some(fragment.root_element().inner_html())
+}
+
+/// Remove hidden elements from content HTML, or `None` if nothing is hidden
+///
+/// Slides are kept when `keep_slides` is set, since stitched slideshows hide
+/// all but the current slide. With `print_hints`, visibility follows the
+/// print view: print-only elements are kept and print-hidden ones dropped.
+fn remove_hidden_elements(
+    content: &str,
+    keep_sr_only: bool,
+    keep_slides: bool,
+    print_hints: Option<&PrintHints>,
+) -> Option<String> {
+    remove_elements(content, |element| {
+        let kept = (keep_slides && is_slide_element(element))
+            || (print_hints.is_some() && print::is_print_only(element));
+        let hidden = !is_node_visible_for(element, keep_sr_only)
+            || print_hints.is_some_and(|hints| hints.is_hidden(element));
+        !kept && hidden
+    })
+}
+
+/// Whether `element` is an ad slot injected into the article body: marked by
+/// ad attributes, classes or ids, or holding nothing but an "Advertisement" label
+fn is_inline_ad(element: &ElementRef) -> bool {
+    let value = element.value();
+    let marked = value.attrs().any(|(name, _)| name == "data-ad" || name.starts_with("data-ad-") || name == "data-google-query-id")
+        || value.classes().any(is_ad_container)
+        || value.id().is_some_and(is_ad_container);
+    let text = get_inner_text(element, true);
+    // Real content that happens to carry an ad-like name is kept
+    (marked && text.len() < 200)
+        || (matches!(value.name(), "div" | "aside" | "section" | "figure" | "p") && contains_ad_words(text.trim()))
+}
+
+/// The byline in an `<address>`: its author links, or else its text
+fn address_byline(address: &ElementRef) -> String {
+    let link_selector = Selector::parse("a[href]:not([href^='mailto:'])").unwrap();
+    let names: Vec<String> = address.select(&link_selector)
+        .map(|link| get_inner_text(&link, true))
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.is_empty() {
+        get_inner_text(address, true)
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Attribute `mark_data_tables` puts on data tables, removed from the output
+const DATA_TABLE_ATTR: &str = "data-readability-table";
+
+/// Whether `element` is a data table or inside one, which cleaning must keep intact
+fn in_data_table(element: &ElementRef) -> bool {
+    (element.value().name() == "table" && is_marked_data_table(element))
+        || has_ancestor_tag(element, "table", None, Some(is_marked_data_table))
+}
+
+/// Whether `table` was flagged by `mark_data_tables`
+fn is_marked_data_table(table: &ElementRef) -> bool {
+    table.value().attr(DATA_TABLE_ATTR).is_some()
+}
+
+/// Whether `element` is a recommendation widget: a known vendor's class or
+/// id, or a `data-widget*` attribute
+fn is_recommendation_widget_element(element: &ElementRef) -> bool {
+    let value = element.value();
+    value.attrs().any(|(name, _)| name.starts_with("data-widget"))
+        || value.classes().any(is_recommendation_widget)
+        || value.id().is_some_and(is_recommendation_widget)
+}
+
+/// Whether `element` floats over the page: fixed or sticky positioned, or
+/// named like a floating share bar
+fn is_floating_element(element: &ElementRef) -> bool {
+    let value = element.value();
+    is_fixed_position(element)
+        || value.classes().any(is_floating_bar)
+        || value.id().is_some_and(is_floating_bar)
+}
+
+/// Whether the page author marked `element` as the content root
+/// (`itemprop="articleBody"` or `role="main"`)
+fn is_declared_content_root(element: &ElementRef) -> bool {
+    element.value().attr("itemprop").is_some_and(|itemprop| itemprop.split_whitespace().any(|name| name == "articleBody"))
+        || element.value().attr("role").is_some_and(|role| role.trim().eq_ignore_ascii_case("main"))
+}
+
+/// The deepest element containing all of `elements` (the element itself if there is one)
+fn common_ancestor<'a>(elements: &[ElementRef<'a>]) -> Option<ElementRef<'a>> {
+    let (first, rest) = elements.split_first()?;
+    std::iter::once(*first)
+        .chain(first.ancestors().filter_map(ElementRef::wrap))
+        .find(|candidate| {
+            rest.iter().all(|element| {
+                element.id() == candidate.id() || element.ancestors().any(|ancestor| ancestor.id() == candidate.id())
+            })
+        })
+}
+
+/// Descend through containers whose only content is a single wrapper element
+///
+/// Without this, re-parsing extracted content would peel one wrapper per pass.
+fn unwrap_lone_wrappers(element: ElementRef<'_>) -> ElementRef<'_> {
+    let mut current = element;
+    loop {
+        let has_text = current
+            .children()
+            .filter_map(|child| child.value().as_text())
+            .any(|text| !text.trim().is_empty());
+        let mut children = current.children().filter_map(ElementRef::wrap);
+        match (children.next(), children.next()) {
+            (Some(only), None)
+                if !has_text && matches!(only.value().name(), "div" | "section" | "article" | "main") =>
+            {
+                current = only;
+            }
+            _ => return current,
+        }
+    }
+}
+
+/// Whether an attribute value names a JPEG, PNG or WebP image
+fn has_image_extension(value: &str) -> bool {
+    let value = value.to_ascii_lowercase();
+    [".jpg", ".jpeg", ".png", ".webp"].iter().any(|extension| value.contains(extension))
+}
+
+/// Whether a block the generic cleanup would drop is documentation content:
+/// an admonition, a page header carrying the heading, or anything with code
+fn keep_in_docs(element: &ElementRef, names: &str) -> bool {
+    let has = |tags: &str| element.select(&Selector::parse(tags).unwrap()).next().is_some();
+    is_admonition(names) || has("pre") || (element.value().name() == "header" && has("h1, h2, h3"))
+}
+
+/// `text` with each run of two or more whitespace characters replaced by a
+/// space, or `None` if it has no such run
+fn collapse_whitespace_runs(text: &str) -> Option<String> {
+    let mut chars = text.chars().peekable();
+    let mut collapsed = String::with_capacity(text.len());
+    let mut changed = false;
+    while let Some(ch) = chars.next() {
+        if ch.is_whitespace() && chars.peek().is_some_and(|next| next.is_whitespace()) {
+            while chars.peek().is_some_and(|next| next.is_whitespace()) {
+                chars.next();
+            }
+            collapsed.push(' ');
+            changed = true;
+        } else {
+            collapsed.push(ch);
+        }
+    }
+    changed.then_some(collapsed)
+}
+
+/// Remove runs of two or more blocks repeating an earlier run in the same
+/// order, as left by infinite-scroll snapshots that load the same story again
+///
+/// Returns the collapsed HTML and the number of blocks removed.
+fn collapse_repeated_blocks(content: &str) -> (String, usize) {
+    use std::hash::{Hash, Hasher};
+
+    let mut fragment = Html::parse_fragment(content);
+    let block_selector = Selector::parse("p, h1, h2, h3, h4, h5, h6, pre, blockquote, li").unwrap();
+    let blocks: Vec<ElementRef> = fragment.select(&block_selector)
+        .filter(|block| !block.ancestors().filter_map(ElementRef::wrap).any(|ancestor| block_selector.matches(&ancestor)))
+        .collect();
+
+    let hashes: Vec<Option<u64>> = blocks.iter()
+        .map(|block| {
+            let text = get_inner_text(block, true).to_lowercase();
+            if text.is_empty() {
+                return None;
+            }
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            text.hash(&mut hasher);
+            Some(hasher.finish())
+        })
+        .collect();
+
+    let mut seen = std::collections::HashSet::new();
+    let repeated: Vec<bool> = hashes.iter().map(|hash| hash.is_some_and(|hash| !seen.insert(hash))).collect();
+
+    // Only runs count that repeat an earlier run whole and in order; a lone
+    // repeated line is likely a separator or label
+    let mut to_remove = Vec::new();
+    let mut index = 0;
+    while index < blocks.len() {
+        let repeated_end = (index..blocks.len()).find(|&i| !repeated[i]).unwrap_or(blocks.len());
+        // The longest run from here that already appeared before it
+        let repeats_earlier = |run: &[Option<u64>]| {
+            index >= run.len() && (0..=index - run.len()).any(|start| hashes[start..start + run.len()] == *run)
+        };
+        match (index + 2..=repeated_end).rev().find(|&end| repeats_earlier(&hashes[index..end])) {
+            Some(end) => {
+                for block in &blocks[index..end] {
+                    // Take the whitespace before the block along, so no blank lines are left
+                    let blank = block.prev_sibling().filter(|node| node.value().as_text().is_some_and(|text| text.trim().is_empty()));
+                    to_remove.extend(blank.map(|node| node.id()));
+                    to_remove.push(block.id());
+                }
+                index = end;
+            }
+            None => index += 1,
+        }
+    }
+    let removed = to_remove.iter().filter(|&&id| fragment.tree.get(id).is_some_and(|node| node.value().is_element())).count();
+    if removed == 0 {
+        return (content.to_string(), 0);
+    }
+
+    dom::remove(&mut fragment, &to_remove);
+    (fragment.root_element().inner_html(), removed)
+}
+
+/// Check cleaned content for editorial edits or correction notices
+fn detect_corrections(content: &str) -> bool {
+    let fragment = Html::parse_fragment(content);
+    let edit_selector = Selector::parse("ins, del").unwrap();
+    if fragment.select(&edit_selector).next().is_some() {
+        return true;
+    }
+
+    let block_selector = Selector::parse("p, div, aside, section, footer, em, strong").unwrap();
+    fragment.select(&block_selector).any(|element| {
+        let class_and_id = format!(
+            "{} {}",
+            element.value().attr("class").unwrap_or(""),
+            element.value().attr("id").unwrap_or("")
+        );
+        has_correction_class(&class_and_id) || is_correction_notice(&get_inner_text(&element, true))
+    })
+}
+
+/// Locate the main content of a document without running the cleanup pipeline
+///
+/// Useful for highlighting the content in a browser or cropping a screenshot.
+pub fn score_document(html: &str, options: Option<ReadabilityOptions>) -> Option<ContentLocation> {
+    let mut parser = Readability::new(html, options).ok()?;
+    parser.reset();
+    parser.get_article_metadata();
+    parser.locate_content()
+}
+
+/// Recognize homepages and section fronts: many linked headings over
+/// similar-length teasers, with links making up much of the body text
+fn is_index_page(document: &Html) -> bool {
+    let heading_link_selector = Selector::parse("h1 > a[href], h2 > a[href], h3 > a[href], h4 > a[href]").unwrap();
+    let teaser_lengths: Vec<f64> = document.select(&heading_link_selector)
+        .filter_map(|link| link.parent().and_then(ElementRef::wrap))
+        .filter_map(|heading| heading.parent().and_then(ElementRef::wrap))
+        .map(|teaser| get_inner_text(&teaser, true).len() as f64)
+        .collect();
+    if teaser_lengths.len() < 6 {
+        return false;
+    }
+
+    let body_selector = Selector::parse("body").unwrap();
+    let link_density = document.select(&body_selector).next()
+        .map(|body| get_link_density(&body))
+        .unwrap_or(0.0);
+
+    let mean = teaser_lengths.iter().sum::<f64>() / teaser_lengths.len() as f64;
+    let variance = teaser_lengths.iter().map(|length| (length - mean).powi(2)).sum::<f64>() / teaser_lengths.len() as f64;
+    let similar_teasers = mean > 0.0 && variance.sqrt() / mean < 0.5;
+
+    link_density >= 0.5 || (link_density >= 0.3 && similar_teasers)
+}
+
+/// Recognize shop product and listing pages from JSON-LD `Product` data or
+/// from prices and purchase buttons outweighing the prose
+fn is_product_page(document: &Html, use_json_ld: bool) -> bool {
+    if use_json_ld {
+        let script_selector = Selector::parse("script[type='application/ld+json']").unwrap();
+        let mut json_ld = JsonLdMetadata::default();
+        for script in document.select(&script_selector) {
+            json_ld.add_script(&script.text().collect::<String>());
+        }
+        let is_product = json_ld.types.iter().any(|t| matches!(t.as_str(), "Product" | "ProductGroup"));
+        let is_article = json_ld.types.iter().any(|t| is_json_ld_article_type(t));
+        if is_product && !is_article {
+            return true;
+        }
+    }
+
+    let button_selector = Selector::parse("button, a, input[type='submit'], input[type='button']").unwrap();
+    let buttons = document.select(&button_selector)
+        .filter(|button| {
+            let label = button.value().attr("value").map(str::to_string)
+                .unwrap_or_else(|| get_inner_text(button, true));
+            label.len() < 40 && is_purchase_button(&label)
+        })
+        .count();
+    if buttons == 0 {
+        return false;
+    }
+
+    let body_selector = Selector::parse("body").unwrap();
+    let price_selector = Selector::parse("[itemprop='price']").unwrap();
+    let prices = document.select(&body_selector).next()
+        .map(|body| count_prices(&get_inner_text(&body, true)))
+        .unwrap_or(0)
+        .max(document.select(&price_selector).count());
+
+    let paragraph_selector = Selector::parse("p").unwrap();
+    let prose_length: usize = document.select(&paragraph_selector)
+        .map(|p| get_inner_text(&p, true).len())
+        .sum();
+
+    prices > 0 && (prices + buttons) * 100 > prose_length
+}
+
+/// Check if a document is likely to be readable/parseable
+///
+/// Parses `html`; with a [`Readability`] parser at hand, its
+/// [`is_probably_readerable`](Readability::is_probably_readerable) reuses the parsed document.
+pub fn is_probably_readerable(html: &str, options: Option<ReadabilityOptions>) -> bool {
+    let document = Html::parse_document(strip_leading_garbage(html));
+    document_is_probably_readerable(&document, &options.unwrap_or_default())
+}
+
+fn document_is_probably_readerable(document: &Html, opts: &ReadabilityOptions) -> bool {
+    if is_product_page(document, !opts.disable_json_ld) {
+        return false;
+    }
+    
+    // Scale minimum score based on char_threshold
+    let lang = document.root_element().value().attr("lang").map(str::to_string).or_else(|| {
+        let sample: String = document.root_element().text().take(200).collect();
+        analysis::detect_language_from_script(&sample).map(str::to_string)
+    });
+    let char_threshold = opts.char_threshold.resolve(lang.as_deref());
+    let min_content_length = if char_threshold > 0 { 
+        char_threshold 
+    } else { 
+        140  // Default fallback
+    };
+    
+    // Scale min_score based on char_threshold - lower thresholds need lower scores
+    let min_score = opts.readerable_min_score.unwrap_or(if min_content_length <= 20 {
+        8.0   // Very lenient for very short content
+    } else if min_content_length <= 50 {
+        20.0  // Strict for short content
+    } else if min_content_length <= 100 {
+        30.0  // Strict for medium content
+    } else {
+        40.0  // Strict for longer content
+    });
+    
+    // Look for content-bearing elements
+    let content_selectors = ["p", "pre", "article", "div"];
+    let mut score = 0.0;
+    let mut total_text_length = 0;
+    
+    for selector_str in &content_selectors {
+        if let Ok(selector) = Selector::parse(selector_str) {
+            for element in document.select(&selector) {
+                let text_content = element.text().collect::<String>();
+                let text_length = text_content.trim().len();
+                
+                if text_length < 10 {  // Skip very short elements (reduced from 25)
+                    continue;
+                }
+                
+                total_text_length += text_length;
+                
+                // Check for unlikely candidates
+                let class_and_id = format!("{} {}", 
+                    element.value().attr("class").unwrap_or(""),
+                    element.value().attr("id").unwrap_or("")
+                );
+                
+                if is_unlikely_candidate(&class_and_id) {
+                    score -= 5.0;  // Penalize unlikely candidates
+                    continue;
+                }
+                
+                // Score based on element type and content length
+                let element_score = match element.value().name() {
+                    "article" => (text_length as f64 * 0.5).min(30.0),
+                    "p" => (text_length as f64 * 0.3).min(20.0),
+                    "pre" => (text_length as f64 * 0.4).min(25.0),
+                    "div" => {
+                        // More lenient for divs when using low thresholds
+                        if min_content_length <= 50 && text_length > 20 {
+                            (text_length as f64 * 0.25).min(15.0)
+                        } else if text_length > 80 {
+                            (text_length as f64 * 0.2).min(15.0)
+                        } else {
+                            0.0
+                        }
+                    },
+                    _ => 0.0,
+                };
+                
+                score += element_score;
+                
+                // Early return if we have enough score
+                if score > min_score && total_text_length >= min_content_length {
+                    return true;
+                }
+            }
+        }
+    }
+    
+    // Final check: require both minimum score and minimum content length
+    score > min_score && total_text_length >= min_content_length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    // Helper function to create a readability parser
+    fn create_parser(html: &str) -> Readability {
+        Readability::new(html, Some(ReadabilityOptions {
+            debug: true,
+            char_threshold: CharThreshold::Chars(25),  // Lower threshold for testing
+            ..Default::default()
+        })).unwrap()
+    }
+
+    // Helper function to create a readability parser with custom options
+    fn create_parser_with_options(html: &str, options: ReadabilityOptions) -> Readability {
+        Readability::new(html, Some(options)).unwrap()
+    }
+
+    // Helper function to load test case files
+    fn load_test_case(test_dir: &str) -> Result<(String, String, serde_json::Value), Box<dyn std::error::Error>> {
+        let base_path = Path::new("mozzila-readability/test/test-pages").join(test_dir);
+        
+        let source_path = base_path.join("source.html");
+        let expected_content_path = base_path.join("expected.html");
+        let expected_metadata_path = base_path.join("expected-metadata.json");
+        
+        let source = fs::read_to_string(&source_path)
+            .map_err(|e| format!("Failed to read source.html for {}: {}", test_dir, e))?;
+        let expected_content = fs::read_to_string(&expected_content_path)
+            .map_err(|e| format!("Failed to read expected.html for {}: {}", test_dir, e))?;
+        let expected_metadata: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(&expected_metadata_path)
+                .map_err(|e| format!("Failed to read expected-metadata.json for {}: {}", test_dir, e))?
+        ).map_err(|e| format!("Failed to parse expected-metadata.json for {}: {}", test_dir, e))?;
+        
+        Ok((source, expected_content, expected_metadata))
+    }
+
+    // Test individual Mozilla test case
+    fn test_mozilla_case(test_dir: &str) {
+        let (source, _expected_content, expected_metadata) = match load_test_case(test_dir) {
+            Ok(data) => data,
+            Err(e) => {
+                println!("Skipping test case {}: {}", test_dir, e);
+                return;
+            }
+        };
+
+        // Create parser with base URI for URL resolution
+        let base_uri = "http://fakehost/test/page.html";
+        let mut parser = match Readability::new_with_base_uri(&source, base_uri, Some(ReadabilityOptions {
+            debug: false,
+            char_threshold: CharThreshold::Chars(25),
+            classes_to_preserve: vec!["caption".to_string()],
+            ..Default::default()
+        })) {
+            Ok(p) => p,
+            Err(e) => {
+                println!("Failed to create parser for {}: {:?}", test_dir, e);
+                return;
+            }
+        };
+
+        // Check if content is probably readerable first
+        let is_readerable = is_probably_readerable(&source, Some(ReadabilityOptions {
+            char_threshold: CharThreshold::Chars(25),
+            ..Default::default()
+        }));
+
+        let expected_readerable = expected_metadata["readerable"].as_bool().unwrap_or(false);
+        
+        // If expected to be readerable but our check says no, it might be a threshold issue
+        if expected_readerable && !is_readerable {
+            println!("Warning: {} expected to be readerable but failed readerable check", test_dir);
+        }
+
+        // Parse the article
+        let article = parser.parse();
+        
+        if expected_readerable {
+            if let Some(article) = article {
+                // Validate metadata
+                if let Some(expected_title) = expected_metadata["title"].as_str() {
+                    if let Some(actual_title) = &article.title {
+                        // Allow some flexibility in title matching
+                        if !actual_title.contains(expected_title) && !expected_title.contains(actual_title) {
+                            println!("Title mismatch in {}: expected '{}', got '{}'", 
+                                test_dir, expected_title, actual_title);
+                        }
+                    } else {
+                        println!("Missing title in {}: expected '{}'", test_dir, expected_title);
+                    }
+                }
+
+                if let Some(expected_byline) = expected_metadata["byline"].as_str() {
+                    if let Some(actual_byline) = &article.byline {
+                        if actual_byline != expected_byline {
+                            println!("Byline mismatch in {}: expected '{}', got '{}'", 
+                                test_dir, expected_byline, actual_byline);
+                        }
+                    } else {
+                        println!("Missing byline in {}: expected '{}'", test_dir, expected_byline);
+                    }
+                }
+
+                if let Some(expected_lang) = expected_metadata["lang"].as_str() {
+                    if let Some(actual_lang) = &article.lang {
+                        if actual_lang != expected_lang {
+                            println!("Language mismatch in {}: expected '{}', got '{}'", 
+                                test_dir, expected_lang, actual_lang);
+                        }
+                    } else {
+                        println!("Missing language in {}: expected '{}'", test_dir, expected_lang);
+                    }
+                }
+
+                if let Some(expected_site_name) = expected_metadata["siteName"].as_str() {
+                    if let Some(actual_site_name) = &article.site_name {
+                        if actual_site_name != expected_site_name {
+                            println!("Site name mismatch in {}: expected '{}', got '{}'", 
+                                test_dir, expected_site_name, actual_site_name);
+                        }
+                    } else {
+                        println!("Missing site name in {}: expected '{}'", test_dir, expected_site_name);
+                    }
+                }
+
+                if let Some(expected_published_time) = expected_metadata["publishedTime"].as_str() {
+                    if let Some(actual_published_time) = &article.published_time {
+                        if actual_published_time != expected_published_time {
+                            println!("Published time mismatch in {}: expected '{}', got '{}'", 
+                                test_dir, expected_published_time, actual_published_time);
+                        }
+                    } else {
+                        println!("Missing published time in {}: expected '{}'", test_dir, expected_published_time);
+                    }
+                }
+
+                // Validate that content exists and has reasonable length
+                if let Some(content) = &article.content {
+                    if content.trim().is_empty() {
+                        println!("Empty content in {}", test_dir);
+                    }
+                } else {
+                    println!("Missing content in {}", test_dir);
+                }
+
+                // Validate readerable field
+                assert_eq!(article.readerable, Some(true), "Article should be marked as readerable for {}", test_dir);
+            } else {
+                println!("Failed to parse article for {} (expected to be readerable)", test_dir);
+            }
+        } else {
+            // If not expected to be readerable, parsing might still succeed but with low quality
+            if article.is_some() {
+                println!("Unexpectedly parsed article for {} (expected not readerable)", test_dir);
+            }
+        }
+    }
+
+    #[test]
+    fn test_readability_options_default() {
+        let options = ReadabilityOptions::default();
+        assert!(!options.debug);
+        assert_eq!(options.max_elems_to_parse, 0);
+        assert_eq!(options.nb_top_candidates, 5);
+        assert_eq!(options.char_threshold, CharThreshold::Chars(25));
+        assert!(!options.keep_classes);
+        assert!(!options.disable_json_ld);
+    }
+
+    #[test]
+    fn test_article_creation() {
+        let article = Article {
+            title: Some("Test Title".to_string()),
+            content: Some("<div>Test content</div>".to_string()),
+            text_content: Some("Test content".to_string()),
+            length: Some(12),
+            excerpt: Some("Test excerpt".to_string()),
+            byline: Some("Test Author".to_string()),
+            readerable: Some(true),
+            dir: None,
+            site_name: Some("Test Site".to_string()),
+            lang: Some("en".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(article.title.unwrap(), "Test Title");
+        assert_eq!(article.length.unwrap(), 12);
+        assert!(article.excerpt.is_some());
+    }
+
+    #[test]
+    fn test_article_locate() {
+        let html = r#"<html><body><article>
+            <p>The first paragraph of the story, long enough, with a <em>comma</em> or two.</p>
+            <p>The second paragraph continues the story, again with commas, clauses, and detail.</p>
+        </article></body></html>"#;
+        let article = create_parser(html).parse().unwrap();
+        let text = article.text_content.as_deref().unwrap();
+
+        let sentence = &article.sentences()[1];
+        let spans = article.locate(sentence.range.clone());
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0].path.ends_with("p:nth-of-type(2)"));
+
+        let start = text.find("a comma or").unwrap();
+        let spans = article.locate(start..start + "a comma or".len());
+        assert_eq!(spans.len(), 3);
+        assert!(spans[1].path.ends_with("> em"));
+
+        assert!(Article::default().locate(0..4).is_empty());
+    }
+
+    #[test]
+    fn test_article_sentences() {
+        let article = Article {
+            text_content: Some("Mr. Brown wrote this. It has two sentences.".to_string()),
+            lang: Some("en".to_string()),
+            ..Default::default()
+        };
+
+        let sentences = article.sentences();
+        assert_eq!(sentences.len(), 2);
+        assert_eq!(sentences[0].text, "Mr. Brown wrote this.");
+        assert_eq!(sentences[1].text, "It has two sentences.");
+    }
+
+    #[test]
+    fn test_simple_article_parsing() {
+        let html = r#"
+            <!DOCTYPE html>
+            <html>
+            <head>
+                <title>Test Article</title>
+                <meta name="author" content="John Doe">
+                <meta name="description" content="This is a test article">
+            </head>
+            <body>
+                <h1>Test Article Title</h1>
+                <article>
+                    <p>This is the first paragraph of our test article. It contains enough content to be considered readable.</p>
+                    <p>This is the second paragraph with more content. It helps ensure the article meets the minimum length requirements for processing.</p>
+                    <p>A third paragraph to add more substance to our test article and make it comprehensive enough for testing.</p>
+                </article>
+            </body>
+            </html>
+        "#;
+
+        let options = ReadabilityOptions {
+            debug: true,
+            ..Default::default()
+        };
+        let mut parser = create_parser_with_options(html, options);
+        let result = parser.parse();
+
+        assert!(result.is_some());
+        let article = result.unwrap();
+        assert!(article.title.is_some() && !article.title.as_ref().unwrap().is_empty());
+        assert!(article.content.is_some());
+        assert!(article.length.is_some() && article.length.unwrap() > 100);
+        assert!(article.summary.is_none());
+    }
+
+    #[test]
+    fn test_summary_option() {
+        let html = r#"
+            <html><body><article>
+                <p>Readability extracts article content from web pages, removing clutter around it.</p>
+                <p>The extracted content is scored, cleaned, and returned with article metadata.</p>
+                <p>Readers then display the article content without any distracting page elements.</p>
+            </article></body></html>
+        "#;
+
+        let options = ReadabilityOptions {
+            summary_sentences: 1,
+            ..Default::default()
+        };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        let summary = article.summary.unwrap();
+        assert!(summary.starts_with("Readability extracts article content"));
+    }
+
+    #[test]
+    fn test_empty_document() {
+        let html = "<html><body></body></html>";
+        let options = ReadabilityOptions {
+            debug: true,
+            ..Default::default()
+        };
+        let mut parser = create_parser_with_options(html, options);
+        let result = parser.parse();
+        
+        // Empty document should not produce a result
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_minimal_content() {
+        let html = r#"
+            <html>
+            <body>
+                <p>Short</p>
+            </body>
+            </html>
+        "#;
+
+        let options = ReadabilityOptions {
+            debug: true,
+            ..Default::default()
+        };
+        let mut parser = create_parser_with_options(html, options);
+        let result = parser.parse();
+        
+        // Very short content should not be considered readable
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_article_with_metadata() {
+        let html = r#"
+            <!DOCTYPE html>
+            <html lang="en">
+            <head>
+                <title>Test Article - Test Site</title>
+                <meta name="author" content="Jane Smith">
+                <meta name="description" content="A comprehensive test article for readability testing">
+                <meta property="og:site_name" content="Test Publishing">
+                <meta property="og:title" content="Test Article">
+            </head>
+            <body>
+                <article>
+                    <h1>Test Article Title</h1>
+                    <div class="byline">By Jane Smith</div>
+                    <p>This is a comprehensive test article with enough content to be considered readable by the parser.</p>
+                    <p>The article contains multiple paragraphs with substantial text content that should pass all readability checks.</p>
+                    <p>Additional content to ensure the article meets minimum length requirements and provides meaningful extractable content.</p>
+                    <p>More content to test the parsing and extraction capabilities of the readability implementation.</p>
+                </article>
+            </body>
+            </html>
+        "#;
+
+        let mut parser = create_parser(html);
+        let result = parser.parse();
+
+        assert!(result.is_some());
+        let article = result.unwrap();
+        
+        assert!(article.title.is_some() && !article.title.as_ref().unwrap().is_empty());
+        assert!(article.byline.is_some());
+        assert!(article.site_name.is_some());
+        assert!(article.lang.is_some());
+        assert_eq!(article.lang.as_ref().unwrap(), "en");
+        assert!(article.length.is_some() && article.length.unwrap() > 200);
+    }
+
+    #[test]
+    fn test_is_probably_readerable_basic() {
+        // Test with content that should be readerable
+        let readable_html = r#"
+            <html>
+            <body>
+                <article>
+                    <h1>Long Article Title</h1>
+                    <p>This is a long article with substantial content that should be considered readable.</p>
+                    <p>Multiple paragraphs with enough text to meet the readability thresholds.</p>
+                    <p>Additional content to ensure this passes the readability checks.</p>
+                    <p>Even more content to make sure this document is substantial enough.</p>
+                </article>
+            </body>
+            </html>
+        "#;
+
+        assert!(is_probably_readerable(readable_html, None));
+
+        // Test with content that should not be readerable
+        let unreadable_html = r#"
+            <html>
+            <body>
+                <nav>Menu</nav>
+                <footer>Copyright</footer>
+            </body>
+            </html>
+        "#;
+
+        assert!(!is_probably_readerable(unreadable_html, None));
+    }
+
+    #[test]
+    fn test_is_probably_readerable_with_options() {
+        let html = r#"
+            <html>
+            <body>
+                <p>Medium length content that is somewhat substantial.</p>
+            </body>
+            </html>
+        "#;
+
+        // With default options, this should not be readerable
+        assert!(!is_probably_readerable(html, None));
+
+        // With lower thresholds, this should be readerable
+        let lenient_options = ReadabilityOptions {
+            char_threshold: CharThreshold::Chars(20),
+            ..Default::default()
+        };
+        assert!(is_probably_readerable(html, Some(lenient_options.clone())));
+
+        // The method reuses the parser's document and options
+        assert!(!Readability::new(html, None).unwrap().is_probably_readerable());
+        let mut parser = Readability::new(html, Some(lenient_options)).unwrap();
+        assert!(parser.is_probably_readerable());
+        assert!(parser.parse().is_some());
+    }
+
+    #[test]
+    fn test_parser_creation() {
+        let html = "<html><body><p>Test content</p></body></html>";
+        let parser = Readability::new(html, None);
+        assert!(parser.is_ok());
+    }
+
+    #[test]
+    fn test_parser_with_options() {
+        let html = "<html><body><p>Test content</p></body></html>";
+        let options = ReadabilityOptions {
+            debug: true,
+            char_threshold: CharThreshold::Chars(100),
+            ..Default::default()
+        };
+        let parser = Readability::new(html, Some(options));
+        assert!(parser.is_ok());
+    }
+
+    #[test]
+    fn test_unicode_handling() {
+        let unicode_html = r#"
+            <!DOCTYPE html>
+            <html lang="zh">
+            <head>
+                <title>测试文章</title>
+                <meta charset="UTF-8">
+            </head>
+            <body>
+                <article>
+                    <h1>Unicode Content Test</h1>
+                    <p>This article contains unicode characters: 测试 🚀 ñáéíóú àèìòù</p>
+                    <p>Emoji support test: 😀 🎉 🌟 💻 📚</p>
+                    <p>Various languages: English, Español, Français, 中文, 日本語, العربية</p>
+                    <p>Special characters: ™ © ® € £ ¥ § ¶ † ‡ • … ‰ ′ ″ ‹ › « » " " ' '</p>
+                </article>
+            </body>
+            </html>
+        "#;
+
+        let mut parser = create_parser(unicode_html);
+        let result = parser.parse();
+
+        assert!(result.is_some());
+        let article = result.unwrap();
+        
+        // Should handle unicode content without panicking
+        assert!(article.title.is_some());
+        assert!(article.text_content.is_some());
+    }
+
+    #[test]
+    fn test_ruby_and_vertical_text() {
+        let html = r#"
+            <html lang="ja">
+            <head><style>.honbun { writing-mode: vertical-rl; }</style></head>
+            <body>
+                <article class="honbun">
+                    <p>今日は<ruby>漢字<rp>(</rp><rt>かんじ</rt><rp>)</rp></ruby>の勉強をしました。とても楽しい一日でした。</p>
+                    <p>明日も<ruby>日本語<rt>にほんご</rt></ruby>を勉強します。毎日少しずつ続けることが大切です。</p>
+                </article>
+            </body>
+            </html>
+        "#;
+
+        let mut parser = create_parser(html);
+        let article = parser.parse().unwrap();
+        assert!(article.vertical_text);
+        // Style-derived hints survive into a second parse
+        assert!(parser.parse().unwrap().vertical_text);
+        let content = article.content.unwrap();
+        assert!(content.contains("<ruby>漢字<rp>(</rp><rt>かんじ</rt><rp>)</rp></ruby>"));
+
+        let horizontal = create_parser("<html><body><article><p>Plain horizontal text that is long enough to extract.</p></article></body></html>")
+            .parse()
+            .unwrap();
+        assert!(!horizontal.vertical_text);
+    }
+
+    #[test]
+    fn test_dir_extraction() {
+        let html = r#"
+            <html lang="ar" dir="rtl">
+            <body><article><p>هذا نص عربي طويل بما يكفي لاستخراجه كمقال من الصفحة.</p></article></body>
+            </html>
+        "#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(article.dir.as_deref(), Some("rtl"));
+    }
+
+    #[test]
+    fn test_corrections_preserved() {
+        let html = r#"
+            <html><body><article>
+                <p>The council voted on Tuesday to approve the new budget for the coming year.</p>
+                <p>The measure passed by <del>five</del> <ins>six</ins> votes after a long debate.</p>
+                <aside class="correction">Correction: An earlier version misstated the vote count.</aside>
+            </article></body></html>
+        "#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert!(article.has_corrections);
+        let content = article.content.unwrap();
+        assert!(content.contains("<del>five</del>"));
+        assert!(content.contains("Correction: An earlier version"));
+
+        let plain = create_parser("<html><body><article><p>Nothing was corrected in this plain article text.</p></article></body></html>")
+            .parse()
+            .unwrap();
+        assert!(!plain.has_corrections);
+    }
+
+    #[test]
+    fn test_structured_authors() {
+        let html = r#"
+            <html><head>
+                <script type="application/ld+json">
+                    {"@type": "NewsArticle", "headline": "Story",
+                     "author": {"@type": "Person", "name": "Jane Doe"}}
+                </script>
+            </head><body>
+                <div class="byline">
+                    <img src="/avatars/jane.png" alt="Jane Doe">
+                    By <a rel="author" href="/people/jane">Jane Doe</a>
+                </div>
+                <article>
+                    <p>This is the article body with enough content to be extracted properly.</p>
+                    <p>A second paragraph keeps the extracted article comfortably long enough.</p>
+                </article>
+            </body></html>
+        "#;
+
+        let mut parser = Readability::new_with_base_uri(html, "https://example.com/news/story", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.authors.len(), 1);
+        assert_eq!(article.authors[0].name, "Jane Doe");
+        assert_eq!(article.authors[0].url.as_deref(), Some("https://example.com/people/jane"));
+        assert_eq!(article.authors[0].image.as_deref(), Some("https://example.com/avatars/jane.png"));
+    }
+
+    #[test]
+    fn test_excerpt_html() {
+        let html = r#"<html><body><article>
+            <p>Short <b>lead</b>.</p>
+            <p>A <em>long</em> paragraph about <a href="https://example.com/rivers" class="x">rivers</a>, with <span>commas</span>, and enough text to fill the first lines of a preview card.<img src="a.jpg"></p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(
+            article.excerpt_html.as_deref(),
+            Some(r#"<p>Short <b>lead</b>.</p><p>A <em>long</em> paragraph about <a href="https://example.com/rivers">rivers</a>, with commas, and enough text to fill the first lines of a preview card.</p>"#)
+        );
+
+        let options = ReadabilityOptions { excerpt_strip_links: true, ..Default::default() };
+        let article = Readability::new(html, Some(options)).unwrap().parse().unwrap();
+        assert!(article.excerpt_html.unwrap().contains("paragraph about rivers, with"));
+    }
+
+    #[test]
+    fn test_allowed_tags() {
+        let html = r#"<html><body><article>
+            <p>Rivers carry <span class="x">silt</span>, sand and <a href="/clay">clay</a> to the sea, where deltas form.</p>
+            <figure><img src="delta.jpg"><figcaption>A delta</figcaption></figure>
+        </article></body></html>"#;
+        let allowed_tags = Some(["p", "A", "figure", "img"].iter().map(|tag| tag.to_string()).collect());
+        let options = ReadabilityOptions { allowed_tags, ..Default::default() };
+        let content = create_parser_with_options(html, options.clone()).parse().unwrap().content.unwrap();
+        assert!(content.contains(r#"<p>Rivers carry silt, sand and <a href="/clay">clay</a>"#), "{}", content);
+        assert!(content.contains(r#"<figure><img src="delta.jpg">A delta</figure>"#), "{}", content);
+        assert!(!content.contains("<div"));
+
+        let options = ReadabilityOptions { disallowed_tag_action: DisallowedTagAction::Drop, ..options };
+        let content = create_parser_with_options(html, options).parse().unwrap().content.unwrap();
+        assert!(content.contains(r#"<figure><img src="delta.jpg"></figure>"#), "{}", content);
+        assert!(content.contains("<p>Rivers carry , sand"), "{}", content);
+    }
+
+    #[test]
+    fn test_xhtml_output() {
+        let html = r#"<html><body><article>
+            <p>Rivers &amp; deltas carry silt, sand and clay to the sea.<br>Deltas form over centuries, one layer at a time.</p>
+            <p><img src="delta.jpg" alt="A &quot;bird's foot&quot; delta"></p>
+        </article></body></html>"#;
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("<br>"));
+
+        let options = ReadabilityOptions { xhtml_output: true, ..Default::default() };
+        let content = create_parser_with_options(html, options).parse().unwrap().content.unwrap();
+        assert!(content.contains("Rivers &amp; deltas"));
+        assert!(content.contains("<br/>Deltas form"));
+        assert!(content.contains(r#"alt="A &quot;bird's foot&quot; delta"/>"#), "{}", content);
+    }
+
+    #[test]
+    fn test_fix_relative_uris() {
+        let content = r##"<p>See <a href="../maps/nile">the map</a>, <a href="#notes">the notes</a> and <a href="javascript:share()">share</a>.</p>
+            <p><a href="javascript:void(0)"><b>Open</b> gallery</a></p>
+            <figure><img src="delta.jpg" srcset="delta-2x.jpg 2x, https://cdn.example.com/delta-3x.jpg 3x"><video poster="/poster.png" src="//cdn.example.com/v.mp4"></video></figure>"##;
+        let base_uri = Some("https://example.com/rivers/nile/");
+        let fixed = fix_relative_uris(content, base_uri, base_uri).unwrap();
+        assert!(fixed.contains(r#"<a href="https://example.com/rivers/maps/nile">the map</a>"#), "{}", fixed);
+        assert!(fixed.contains(r##"<a href="#notes">"##));
+        assert!(fixed.contains(" and share.") && fixed.contains("<span><b>Open</b> gallery</span>"));
+        assert!(fixed.contains(
+            r#"srcset="https://example.com/rivers/nile/delta-2x.jpg 2x, https://cdn.example.com/delta-3x.jpg 3x""#
+        ));
+        assert!(fixed.contains(r#"poster="https://example.com/poster.png" src="https://cdn.example.com/v.mp4""#));
+
+        // Without a base URI only script links change
+        assert!(fix_relative_uris(content, None, None).unwrap().contains(r#"<img src="delta.jpg""#));
+        assert_eq!(fix_relative_uris(r#"<img src="https://example.com/a.jpg">"#, base_uri, base_uri), None);
+
+        let html = r#"<html><head><base href="/static/"></head><body><article>
+            <p>Rivers carry silt, sand and clay to the sea, where deltas form. <img src="delta.jpg"></p>
+        </article></body></html>"#;
+        let article = Readability::new_with_base_uri(html, "https://example.com/post/1", None).unwrap().parse().unwrap();
+        assert!(article.content.unwrap().contains(r#"<img src="https://example.com/static/delta.jpg">"#));
+    }
+
+    #[test]
+    fn test_effective_base_uri() {
+        let page = |head: &str| {
+            format!(
+                r##"<html><head>{}</head><body><article>
+                <p>Rivers carry silt, sand and clay to the sea, where deltas form. <img src="delta.jpg"> <a href="#notes">Notes</a></p>
+                </article></body></html>"##,
+                head
+            )
+        };
+
+        let html = page(r#"<base href="https://cdn.example.com/docs/"><base href="https://other.example.com/">"#);
+        let mut parser = create_parser(&html);
+        assert_eq!(parser.effective_base_uri(), Some("https://cdn.example.com/docs/"));
+        let content = parser.parse().unwrap().content.unwrap();
+        assert!(content.contains(r#"<img src="https://cdn.example.com/docs/delta.jpg">"#), "{}", content);
+        // Fragment links point at the base document, as browsers follow them
+        assert!(content.contains(r#"<a href="https://cdn.example.com/docs/#notes">"#));
+
+        // A relative <base href> needs a base URI to resolve against
+        let html = page(r#"<base href="/static/">"#);
+        let mut parser = create_parser(&html);
+        assert_eq!(parser.effective_base_uri(), None);
+        assert!(parser.parse().unwrap().content.unwrap().contains(r#"<img src="delta.jpg">"#));
+
+        let parser = Readability::new_with_base_uri(&html, "https://example.com/post/1", None).unwrap();
+        assert_eq!(parser.effective_base_uri(), Some("https://example.com/static/"));
+
+        let mut parser = Readability::new_with_base_uri(&page(""), "https://example.com/post/1", None).unwrap();
+        assert_eq!(parser.effective_base_uri(), Some("https://example.com/post/1"));
+        assert!(parser.parse().unwrap().content.unwrap().contains(r##"<a href="#notes">"##));
+    }
+
+    #[test]
+    fn test_images_and_site_icons() {
+        let html = r#"<html><head>
+            <meta property="og:image" content="/cover-wide.jpg"><meta property="og:image:width" content="1200">
+            <meta property="og:image" content="/cover-square.jpg"><meta property="og:image:width" content="600">
+            <link rel="apple-touch-icon" sizes="180x180" href="/touch.png">
+            </head><body><article><p>A paragraph about rivers, with commas, and enough words to count.</p></article></body></html>"#;
+        let article = Readability::new_with_base_uri(html, "https://example.com/post/1", None).unwrap().parse().unwrap();
+        let images: Vec<_> = article.images.iter().map(|image| (image.url.as_str(), image.width)).collect();
+        assert_eq!(images, vec![("https://example.com/cover-wide.jpg", Some(1200)), ("https://example.com/cover-square.jpg", Some(600))]);
+        assert_eq!(
+            article.site_icons,
+            vec![ImageCandidate { url: "https://example.com/touch.png".to_string(), width: Some(180), height: Some(180) }]
+        );
+    }
+
+    #[test]
+    fn test_excerpt_max_chars() {
+        let lead = "Rivers carry silt, sand and clay to the sea, where deltas form over centuries. ".repeat(10);
+        let html = format!("<html><body><article><p>{}</p></article></body></html>", lead);
+
+        let excerpt = create_parser(&html).parse().unwrap().excerpt.unwrap();
+        assert!(excerpt.chars().count() <= 200 && excerpt.ends_with("centuries."), "{}", excerpt);
+
+        let options = ReadabilityOptions { excerpt_max_chars: 0, ..Default::default() };
+        let excerpt = create_parser_with_options(&html, options).parse().unwrap().excerpt.unwrap();
+        assert_eq!(excerpt, lead.trim());
+    }
+
+    #[test]
+    fn test_text_content_raw() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <ul><li>Nile</li><li>Amazon</li></ul>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(
+            article.text_content_raw.as_deref(),
+            Some("A long paragraph about rivers, with commas, and enough text to pass.\n\nNile\nAmazon\n\n\
+                  Another paragraph about mountains, with commas, and enough text to pass.")
+        );
+        assert!(!article.text_content.unwrap().contains('\n'));
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let html = r#"<html><body><article>
+            <h2>Rivers</h2>
+            <p>A long paragraph about <em>rivers</em>, with commas, and enough text to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        assert!(create_parser(html).parse().unwrap().paragraphs.is_empty());
+        let options = ReadabilityOptions { include_paragraphs: true, ..Default::default() };
+        let paragraphs = Readability::new(html, Some(options)).unwrap().parse().unwrap().paragraphs;
+        assert_eq!(paragraphs.len(), 3);
+        assert_eq!(paragraphs[0].level, Some(2));
+        assert_eq!(paragraphs[2].text, "Another paragraph about mountains, with commas, and enough text to pass.");
+        assert!(paragraphs[1].html.contains("<em>rivers</em>"));
+    }
+
+    #[test]
+    fn test_chunks() {
+        let html = r#"<html><body><article>
+            <h2>Rivers</h2>
+            <p>A long paragraph about rivers, with commas, and enough text to pass. Rivers flow to the sea.</p>
+            <h2>Mountains</h2>
+            <p>Another paragraph about mountains, with commas, and enough text to pass. Mountains are tall.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        let chunks = article.chunks(120, 30);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].text.starts_with("Rivers\n\nA long paragraph"));
+        assert!(chunks[1].text.starts_with("Mountains\n\nAnother paragraph"));
+        assert_eq!(chunks[1].heading.as_deref(), Some("Mountains"));
+
+        let options = ReadabilityOptions { include_paragraphs: true, ..Default::default() };
+        let article = Readability::new(html, Some(options)).unwrap().parse().unwrap();
+        assert_eq!(article.chunks(120, 30), chunks);
+        assert!(article.chunks(60, 0).iter().all(|chunk| chunk.text.ends_with('.')));
+    }
+
+    #[test]
+    fn test_tokens_estimate() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(article.tokens_estimate, None);
+        let options = ReadabilityOptions { estimate_tokens: true, ..Default::default() };
+        let article = Readability::new(html, Some(options)).unwrap().parse().unwrap();
+        let text = article.text_content.unwrap();
+        assert_eq!(article.tokens_estimate, Some(analysis::estimate_tokens(&text)));
+        assert!(article.tokens_estimate.unwrap() > text.split_whitespace().count() / 2);
+
+        fn words(text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+        let options = ReadabilityOptions { estimate_tokens: true, token_counter: Some(words), ..Default::default() };
+        let article = Readability::new(html, Some(options)).unwrap().parse().unwrap();
+        assert_eq!(article.tokens_estimate, Some(words(&article.text_content.unwrap())));
+    }
+
+    #[test]
+    fn test_author_bio() {
+        let html = |bio: &str| format!(r#"<html><head><meta name="author" content="Jane Doe"></head><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+            {}
+        </article></body></html>"#, bio);
+        let options = || Some(ReadabilityOptions { extract_author_bio: true, ..Default::default() });
+
+        let named = html(r#"<div class="author-box"><img src="jane.jpg"><p>Reporter at large, covering rivers.</p></div>"#);
+        let article = Readability::new(&named, options()).unwrap().parse().unwrap();
+        assert_eq!(article.author_bio.as_deref(), Some("Reporter at large, covering rivers."));
+        assert!(!article.content.unwrap().contains("Reporter at large"));
+
+        let opening = html("<p>Jane Doe is a reporter covering rivers, lakes and the people who live by them.</p>");
+        let article = Readability::new(&opening, options()).unwrap().parse().unwrap();
+        assert!(article.author_bio.unwrap().starts_with("Jane Doe is a reporter"));
+        assert!(!article.text_content.unwrap().contains("Jane Doe is"));
+
+        // Off by default, and only the named author's bio is taken
+        let article = Readability::new(&opening, None).unwrap().parse().unwrap();
+        assert!(article.author_bio.is_none() && article.content.unwrap().contains("Jane Doe is"));
+        let other = html("<p>John Roe is a fisherman we met on the river, with many stories to tell.</p>");
+        let article = Readability::new(&other, options()).unwrap().parse().unwrap();
+        assert!(article.author_bio.is_none());
+    }
+
+    #[test]
+    fn test_parse_warnings() {
+        let html = format!(r#"<html><head>
+            <meta property="article:published_time" content="03/04/2021">
+        </head><body>
+            <div class="byline">{}</div>
+            <article>
+                <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+                <img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" data-src="/photos/river.jpg">
+                <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+            </article>
+        </body></html>"#, "Written by a very long list of contributors ".repeat(4));
+
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.warnings, vec![
+            ParseWarning::BylineDiscarded { length: 164 },
+            ParseWarning::LazyImagesUnresolved { count: 1 },
+            ParseWarning::AmbiguousDate { value: "03/04/2021".to_string() },
+        ]);
+
+        let mut clean = create_parser("<html><body><article><p>A long paragraph about rivers, with commas, and enough text to pass.</p></article></body></html>");
+        assert!(clean.parse().unwrap().warnings.is_empty());
+
+        let options = ReadabilityOptions { char_threshold: CharThreshold::Chars(10), ..Default::default() };
+        let mut sparse = Readability::new("<html><body><main><div>Opening hours</div><div>Mon to Fri</div></main></body></html>", Some(options)).unwrap();
+        assert_eq!(sparse.parse().unwrap().warnings, vec![ParseWarning::FallbackContentSelection]);
+        assert!(!is_ambiguous_date("2021-03-04T10:00:00Z"));
+        assert!(!is_ambiguous_date("13/04/2021"));
+    }
+
+    #[test]
+    fn test_address_byline() {
+        let html = r#"
+            <html><head><title>Story</title></head><body>
+                <article>
+                    <header><h1>Story</h1>
+                        <address>By <a href="/people/jane">Jane Doe</a> <a href="mailto:jane@example.com">Email</a></address>
+                    </header>
+                    <p>This is the article body with enough content to be extracted properly.</p>
+                    <p>A second paragraph keeps the extracted article comfortably long enough.</p>
+                </article>
+            </body></html>
+        "#;
+
+        let mut parser = Readability::new_with_base_uri(html, "https://example.com/news/story", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+        assert_eq!(article.authors.len(), 1);
+        assert_eq!(article.authors[0].name, "Jane Doe");
+        assert_eq!(article.authors[0].url.as_deref(), Some("https://example.com/people/jane"));
+    }
+
+    #[test]
+    fn test_base_href() {
+        let html = |base: &str| format!(r#"
+            <html><head>{}</head><body>
+                <div class="byline">By <a rel="author" href="people/jane">Jane Doe</a></div>
+                <article>
+                    <p>This is the article body with enough content to be extracted properly.</p>
+                    <p>A second paragraph keeps the extracted article comfortably long enough.</p>
+                </article>
+            </body></html>
+        "#, base);
+        let author_url = |html: &str, base_uri: Option<&str>| {
+            let mut parser = match base_uri {
+                Some(base_uri) => Readability::new_with_base_uri(html, base_uri, None).unwrap(),
+                None => Readability::new(html, None).unwrap(),
+            };
+            parser.parse().unwrap().authors[0].url.clone()
+        };
+
+        let relative_base = html(r#"<base href="/staff/">"#);
+        assert_eq!(
+            author_url(&relative_base, Some("https://example.com/news/story")).as_deref(),
+            Some("https://example.com/staff/people/jane")
+        );
+        assert_eq!(author_url(&relative_base, None).as_deref(), Some("people/jane"));
+
+        let absolute_base = html(r#"<base target="_blank"><base href="https://cdn.example.org/site/">"#);
+        assert_eq!(author_url(&absolute_base, None).as_deref(), Some("https://cdn.example.org/site/people/jane"));
+        assert_eq!(
+            author_url(&html(""), Some("https://example.com/news/story")).as_deref(),
+            Some("https://example.com/news/people/jane")
+        );
+    }
+
+    #[cfg(feature = "json-ld")]
+    #[test]
+    fn test_article_kind_detection() {
+        let body = "<body><article><p>Article text that is long enough to be extracted by the parser.</p></article></body>";
+
+        let json_ld = format!(r#"<html><head><script type="application/ld+json">{{"@type":"BlogPosting"}}</script></head>{}</html>"#, body);
+        assert_eq!(create_parser(&json_ld).parse().unwrap().kind, Some(ArticleKind::Blog));
+
+        let microdata = format!(r#"<html><body><div itemscope itemtype="https://schema.org/Recipe">{}</div></body></html>"#, body);
+        assert_eq!(create_parser(&microdata).parse().unwrap().kind, Some(ArticleKind::Recipe));
+
+        let generator = format!(r#"<html><head><meta name="generator" content="Docusaurus v3"></head>{}</html>"#, body);
+        assert_eq!(create_parser(&generator).parse().unwrap().kind, Some(ArticleKind::Docs));
+
+        let html = format!("<html>{}</html>", body);
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/forum/topic-1", None).unwrap();
+        assert_eq!(parser.parse().unwrap().kind, Some(ArticleKind::Forum));
+        assert_eq!(create_parser(&html).parse().unwrap().kind, None);
+    }
+
+    #[test]
+    fn test_index_page_detection() {
+        let teasers: String = (1..=8).map(|i| format!(
+            r#"<div class="teaser"><h2><a href="/story-{i}">Headline number {i} about the day</a></h2><p>A short standfirst for story {i}.</p></div>"#
+        )).collect();
+        let html = format!(r#"<html><body><main>{}</main></body></html>"#, teasers);
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.kind, Some(ArticleKind::IndexPage));
+
+        let html = r#"<html><body><article>
+            <h2><a href="/a">Only one linked heading</a></h2>
+            <p>This is a normal article with plenty of prose and only the occasional <a href="/x">link</a> in it.</p>
+        </article></body></html>"#;
+        assert_ne!(create_parser(html).parse().unwrap().kind, Some(ArticleKind::IndexPage));
+    }
+
+    #[cfg(feature = "json-ld")]
+    #[test]
+    fn test_recipe_extraction() {
+        let html = r#"
+            <html><head>
+                <script type="application/ld+json">
+                    {"@type": "Recipe", "name": "Tomato Soup",
+                     "recipeIngredient": ["4 tomatoes", "1 onion"],
+                     "recipeInstructions": "Chop the vegetables.\nSimmer for 20 minutes.",
+                     "totalTime": "PT30M", "recipeYield": 2}
+                </script>
+            </head><body>
+                <div class="sidebar-box"><ul class="ingredients-list"><li>4 tomatoes</li><li>1 onion</li></ul></div>
+                <article>
+                    <p>This soup is a family favourite, warming, simple, and ready in half an hour.</p>
+                    <p>Chop the vegetables, then simmer them for twenty minutes until they are soft.</p>
+                </article>
+            </body></html>
+        "#;
+
+        let options = ReadabilityOptions {
+            extract_recipe: true,
+            ..Default::default()
+        };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        let recipe = article.recipe.unwrap();
+        assert_eq!(recipe.ingredients, vec!["4 tomatoes", "1 onion"]);
+        assert_eq!(recipe.instructions.len(), 2);
+        assert_eq!(recipe.recipe_yield.as_deref(), Some("2"));
+        assert_eq!(recipe.times.total.as_deref(), Some("PT30M"));
+        assert!(article.content.unwrap().contains("<li>4 tomatoes</li>"));
+
+        let without_option = create_parser(html).parse().unwrap();
+        assert!(without_option.recipe.is_none());
+    }
+
+    #[test]
+    fn test_live_blog_updates() {
+        let html = r#"
+            <html><body><article>
+                <p>Follow our live coverage of the election night as results come in across the country.</p>
+                <div class="liveblog-entry"><time datetime="2024-11-05T20:00Z">8pm</time><p>Polls have closed in the east.</p></div>
+                <div class="liveblog-entry"><time datetime="2024-11-05T21:00Z">9pm</time><p>First results are arriving now.</p></div>
+            </article></body></html>
+        "#;
+
+        let options = ReadabilityOptions {
+            extract_live_updates: true,
+            ..Default::default()
+        };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        assert_eq!(article.live_updates.len(), 2);
+        assert_eq!(article.live_updates[0].time.as_deref(), Some("2024-11-05T20:00Z"));
+        assert!(article.live_updates[1].text.contains("First results"));
+
+        let intro = article.text_content.unwrap();
+        assert!(intro.contains("Follow our live coverage"));
+        assert!(!intro.contains("Polls have closed"));
+        assert!(!article.content.unwrap().contains("liveblog-entry"));
+    }
+
+    #[test]
+    fn test_slideshow_stitching() {
+        let html = r#"
+            <html><body>
+                <div class="gallery">
+                    <div class="slide" data-slide="2" style="display:none">
+                        <img src="two.jpg"><p class="caption">Second slide: a long caption describing the harbour at dawn.</p>
+                    </div>
+                    <div class="slide" data-slide="1">
+                        <img src="one.jpg"><p class="caption">First slide: a long caption describing the old town square.</p>
+                    </div>
+                    <div class="slide" data-slide="3" style="display:none">
+                        <img src="three.jpg"><p class="caption">Third slide: a long caption describing the mountain trail, with views, lakes, and forests.</p>
+                    </div>
+                </div>
+            </body></html>
+        "#;
+
+        let article = create_parser(html).parse().unwrap();
+        let content = article.content.unwrap();
+        let first = content.find("one.jpg").unwrap();
+        let second = content.find("two.jpg").unwrap();
+        let third = content.find("three.jpg").unwrap();
+        assert!(first < second && second < third);
+        assert!(article.text_content.unwrap().contains("Third slide"));
+    }
+
+    #[test]
+    fn test_mediawiki_profile() {
+        let html = r##"
+            <html><head><meta name="generator" content="MediaWiki 1.42.0"></head>
+            <body class="mediawiki"><div id="mw-content-text">
+                <table class="infobox"><tr><th>Country</th><td>England</td></tr></table>
+                <h2>History<span class="mw-editsection">[<a href="/w/index.php?action=edit">edit</a>]</span></h2>
+                <p>The town was founded in 1900 by settlers from the coast, who built the first harbour.<sup class="reference"><a href="#cite_note-1">[1]</a></sup></p>
+                <p>It grew quickly during the industrial era, becoming a centre for shipbuilding and trade.</p>
+                <table class="navbox"><tr><td>Towns in the county</td></tr></table>
+            </div></body></html>
+        "##;
+
+        let options = ReadabilityOptions { keep_infobox: true, ..Default::default() };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        let content = article.content.unwrap();
+        assert!(!content.contains("action=edit"));
+        assert!(!content.contains("Towns in the county"));
+        assert!(!content.contains("England"));
+        assert!(content.contains(r##"<sup><a href="#cite_note-1">[1]</a></sup>"##));
+        assert_eq!(article.infobox[0].value, "England");
+
+        let options = ReadabilityOptions { profile: Some(ExtractionProfile::Generic), keep_infobox: true, ..Default::default() };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        assert!(article.infobox.is_empty());
+    }
+
+    #[test]
+    fn test_pdf_conversion() {
+        let text = "The river rises every spring and floods the fields along its banks, leaving fertile silt behind. \
+            Farmers plant as soon as the water recedes, and the harvest follows in early summer.";
+        let spans: String = text.split(' ').enumerate()
+            .map(|(index, word)| format!(
+                r#"<span style="position:absolute; top:{}px; left:{}px">{}</span>"#,
+                100 + index / 8 * 18, 50 + index % 8 * 60, word
+            ))
+            .collect();
+        let html = format!(r#"<html><head><title>Annual report</title></head><body><div style="position:relative">{}</div></body></html>"#, spans);
+
+        assert!(matches!(
+            create_parser(&html).parse_result(),
+            Err(ReadabilityError::UnsupportedInput(UnsupportedInputReason::PdfConversion))
+        ));
+        let options = ReadabilityOptions { reconstruct_pdf_text: true, char_threshold: CharThreshold::Chars(100), ..Default::default() };
+        let article = create_parser_with_options(&html, options).parse_result().unwrap();
+        assert_eq!(article.title.as_deref(), Some("Annual report"));
+        assert_eq!(article.text_content.as_deref(), Some(text));
+    }
+
+    #[test]
+    fn test_text_normalization() {
+        let html = r#"<html><head><title>The co&shy;operative’s report</title></head><body><article>
+            <p>A long paragraph about the co&shy;operative’s “ﬁelds”, with commas, and enough text&amp;nbsp;to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert!(article.text_content.as_deref().unwrap().contains("co\u{AD}operative’s “ﬁelds”"));
+        assert_eq!(
+            article.normalized_text(TextNormalizationPreset::SearchIndex),
+            TextNormalization::from(TextNormalizationPreset::SearchIndex).apply(article.text_content.as_deref().unwrap())
+        );
+
+        let options = ReadabilityOptions { text_normalization: TextNormalizationPreset::Reader.into(), ..Default::default() };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        assert_eq!(article.title.as_deref(), Some("The cooperative’s report"));
+        let text = article.text_content.unwrap();
+        assert!(text.contains("the cooperative’s “ﬁelds”") && text.contains("enough text to pass."));
+
+        let options = ReadabilityOptions { text_normalization: TextNormalizationPreset::SearchIndex.into(), ..Default::default() };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        assert!(article.text_content.unwrap().contains("the cooperative's \"fields\""));
+        assert!(article.text_content_raw.unwrap().contains("the cooperative's \"fields\""));
+    }
+
+    #[test]
+    fn test_cleaner_plugins() {
+        struct ClapCleaner;
+
+        impl CleanerPlugin for ClapCleaner {
+            fn name(&self) -> &str {
+                "clap"
+            }
+
+            fn clean(&self, dom: &mut plugins::ArticleDom, context: &plugins::Context) {
+                assert_eq!(context.title, Some("Rivers"));
+                if context.host().as_deref() == Some("medium.example") {
+                    dom.remove(&Selector::parse(".clap-bar").unwrap());
+                }
+            }
+        }
+
+        let html = r#"<html><head><title>Rivers</title></head><body><article>
+            <p>A long paragraph about <a href="/nile">rivers</a>, with commas, and enough text to pass.</p>
+            <div class="clap-bar"><p>Clap for this story if you enjoyed reading it, with commas, and more.</p></div>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+        let options = ReadabilityOptions {
+            cleaners: CleanerRegistry::new().with(ClapCleaner).with(plugins::StripLinks),
+            ..Default::default()
+        };
+        let article = Readability::new_with_base_uri(html, "https://medium.example/story", Some(options.clone()))
+            .unwrap()
+            .parse()
+            .unwrap();
+        let content = article.content.unwrap();
+        assert!(!content.contains("Clap for this story") && !content.contains("<a "));
+        assert!(!article.text_content.unwrap().contains("Clap"));
+
+        let article = Readability::new_with_base_uri(html, "https://other.example/story", Some(options)).unwrap().parse().unwrap();
+        assert!(article.content.unwrap().contains("Clap for this story"));
+    }
+
+    #[test]
+    fn test_ocr_cleanup() {
+        let html = r#"<html><body><article>
+            <p>The river rises every spring and floods the ﬁelds along its banks, leaving fertile silt be-
+            hind for the farmers.
+            |
+            Farmers plant as soon as the water recedes, and the harvest follows in early summer.</p>
+            <p><span class="ocr_line">The archive was digitized from micro-</span>
+                <span class="ocr_line">film reels, with commas, and enough text.</span></p>
+        </article></body></html>"#;
+
+        let text = create_parser(html).parse().unwrap().text_content.unwrap();
+        assert!(text.contains("be- hind") && text.contains(" | "));
+        let options = ReadabilityOptions { clean_ocr_artifacts: true, ..Default::default() };
+        let text = create_parser_with_options(html, options).parse().unwrap().text_content.unwrap();
+        assert!(text.contains("the fields along its banks, leaving fertile silt behind for the farmers. Farmers plant"));
+        assert!(text.contains("digitized from microfilm reels"));
+    }
+
+    #[test]
+    fn test_email_profile() {
+        let html = r#"
+            <html><head><meta name="x-apple-disable-message-reformatting"></head><body>
+            <span class="preheader" style="display:none !important; mso-hide:all">Your weekly digest of rivers</span>
+            <table role="presentation" width="100%"><tr><td align="center">
+                <table width="600" cellpadding="0" cellspacing="0" border="0">
+                    <tr><td><img src="logo.png" width="120" height="40" alt="Rivers Weekly"></td><td>Issue 12</td></tr>
+                    <tr><td colspan="2">
+                        <h1>The Nile in flood</h1>
+                        <p>A long paragraph about rivers, with commas, and enough text to pass the thresholds.</p>
+                        <p>Another paragraph about floods, with commas, and enough text to pass the thresholds.</p>
+                        <p>A third paragraph about farmers, with commas, and enough text to pass the thresholds.</p>
+                        <p><img src="https://t.example.com/open.gif" width="1" height="1" alt="">Read online.</p>
+                    </td></tr>
+                    <tr><td>One</td><td>Two</td></tr>
+                    <tr><td>Three</td><td>Four</td></tr>
+                    <tr><td>Five</td><td>Six</td></tr>
+                    <tr><td>Seven</td><td>Eight</td></tr>
+                    <tr><td>Nine</td><td>Ten</td></tr>
+                    <tr><td>Eleven</td><td>Twelve</td></tr>
+                </table>
+            </td></tr></table>
+            </body></html>
+        "#;
+
+        let article = create_parser(html).parse().unwrap();
+        let content = article.content.unwrap();
+        assert!(content.contains("A long paragraph about rivers"));
+        assert!(!content.contains("<table") && !content.contains("<td"));
+        assert!(!content.contains("open.gif"));
+        assert!(!article.text_content.unwrap().contains("weekly digest"));
+
+        let options = ReadabilityOptions { profile: Some(ExtractionProfile::Generic), ..Default::default() };
+        let content = create_parser_with_options(html, options).parse().unwrap().content.unwrap();
+        assert!(content.contains("<td"));
+    }
+
+    #[test]
+    fn test_docs_profile() {
+        let html = r##"
+            <html><head><meta name="generator" content="Docusaurus v3.1"></head><body>
+            <nav><a href="/docs/intro">Intro</a></nav>
+            <article><div class="markdown">
+                <header><h1 id="install">Installation<a class="hash-link" href="#install">#</a></h1></header>
+                <p>Install the command line tool with cargo, then run it against any page you like to read.</p>
+                <aside class="admonition warning"><p>Requires Rust 1.70 or newer to build from source.</p></aside>
+                <div class="code-navigation"><pre><code>cargo install readability-rust</code></pre></div>
+                <p>The binary reads HTML from standard input when no file argument is given to it.</p>
+            </div></article>
+            </body></html>
+        "##;
+
+        let article = create_parser(html).parse().unwrap();
+        let content = article.content.unwrap();
+        assert!(content.contains("Requires Rust 1.70"));
+        assert!(content.contains("cargo install readability-rust"));
+        let anchor = Selector::parse("h1 > a.hash-link[href='#install']").unwrap();
+        assert!(Html::parse_fragment(&content).select(&anchor).next().is_some());
+
+        let options = ReadabilityOptions { profile: Some(ExtractionProfile::Generic), ..Default::default() };
+        let content = create_parser_with_options(html, options).parse().unwrap().content.unwrap();
+        assert!(!content.contains("Requires Rust 1.70"));
+    }
+
+    #[test]
+    fn test_docs_profile_keeps_preformatted_whitespace() {
+        let code = "fn main() {\n    let page = fetch();\n\n    println!(\"{}\", page);\n}";
+        let html = format!(
+            r#"<html><head><meta name="generator" content="Docusaurus v3.1"></head><body><article>
+            <p>Fetch a page and print it to standard output, as the smallest possible example of the API.</p>
+            <pre><code>{code}</code></pre>
+            <p>Inline   <code>cargo  run</code>   keeps its spacing too, while the prose around it is collapsed.</p>
+            </article></body></html>"#
+        );
+
+        let content = create_parser(&html).parse().unwrap().content.unwrap();
+        assert!(content.contains(&format!("<pre><code>{}</code></pre>", code)), "{}", content);
+        assert!(content.contains("Inline <code>cargo  run</code> keeps"));
     }
 
-    // Helper function to get all test case directories
-    fn get_test_case_dirs() -> Vec<String> {
-        let test_pages_path = Path::new("mozzila-readability/test/test-pages");
-        
-        if !test_pages_path.exists() {
-            println!("Warning: Mozilla test pages directory not found at {:?}", test_pages_path);
-            return Vec::new();
-        }
-        
-        let mut dirs = Vec::new();
-        if let Ok(entries) = fs::read_dir(test_pages_path) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
-                        if let Some(name) = entry.file_name().to_str() {
-                            dirs.push(name.to_string());
-                        }
-                    }
-                }
-            }
-        }
-        
-        dirs.sort();
-        dirs
+    #[test]
+    fn test_product_page_detection() {
+        let product = r#"
+            <html><head><script type="application/ld+json">{"@type": "Product", "name": "Kettle"}</script></head>
+            <body><div class="product">
+                <h1>Steel Kettle</h1>
+                <p>A sturdy 1.7 litre kettle with a brushed steel finish and a quiet, fast boil.</p>
+                <span class="price">$39.99</span> <button>Add to cart</button>
+            </div></body></html>
+        "#;
+        assert!(!is_probably_readerable(product, None));
+        assert!(matches!(
+            create_parser(product).parse_result(),
+            Err(ReadabilityError::NotAnArticle(NotArticleReason::ProductPage))
+        ));
+        assert!(create_parser(product).parse().is_none());
+
+        // Without structured data, prices and shop buttons outweighing the prose
+        let listing = r#"
+            <html><body><ul>
+                <li><a href="/a">Kettle</a> <span>$39.99</span> <button>Add to cart</button></li>
+                <li><a href="/b">Toaster</a> <span>$24.50</span> <button>Add to cart</button></li>
+                <li><a href="/c">Blender</a> <span>$89.00</span> <button>Add to cart</button></li>
+            </ul><p>Free shipping on all orders over fifty dollars.</p></body></html>
+        "#;
+        assert!(matches!(create_parser(listing).parse_result(), Err(ReadabilityError::NotAnArticle(_))));
+
+        // A review that mentions a price is still an article
+        let review = r#"
+            <html><body><article>
+                <h1>Kettle review</h1>
+                <p>The Steel Kettle costs $39.99 and boils a full litre in under three minutes, which is quick.</p>
+                <p>It is quieter than most of the competition and the lid opens smoothly with one hand.</p>
+                <p>The handle stays cool, although the body gets hot enough that you should keep children away.</p>
+                <p><a href="/shop/kettle">Buy now</a></p>
+            </article></body></html>
+        "#;
+        assert!(create_parser(review).parse_result().is_ok());
     }
 
-    // Test individual Mozilla test case
-    fn test_mozilla_case(test_dir: &str) {
-        let (source, _expected_content, expected_metadata) = match load_test_case(test_dir) {
-            Ok(data) => data,
-            Err(e) => {
-                println!("Skipping test case {}: {}", test_dir, e);
-                return;
-            }
-        };
+    #[test]
+    fn test_repeated_blocks_collapsed() {
+        let story = "<p>The council voted on Tuesday to approve the new bridge across the river.</p>\
+            <p>Construction is expected to begin next spring and take two years.</p>";
+        let html = format!(r#"<html><body><article>{story}<p>Residents welcomed the decision at a packed meeting.</p>
+            {story}</article></body></html>"#);
 
-        // Create parser with base URI for URL resolution
-        let base_uri = "http://fakehost/test/page.html";
-        let mut parser = match Readability::new_with_base_uri(&source, base_uri, Some(ReadabilityOptions {
-            debug: false,
-            char_threshold: 25,
-            classes_to_preserve: vec!["caption".to_string()],
-            ..Default::default()
-        })) {
-            Ok(p) => p,
-            Err(e) => {
-                println!("Failed to create parser for {}: {:?}", test_dir, e);
-                return;
-            }
-        };
+        let options = ReadabilityOptions { collapse_duplicates: true, ..Default::default() };
+        let (result, report) = create_parser_with_options(&html, options).parse_with_report();
+        let article = result.unwrap();
+        assert_eq!(article.text_content.unwrap().matches("approve the new bridge").count(), 1);
+        assert_eq!(report.entries, vec![ReportEntry::DuplicateBlocksCollapsed { removed: 2 }]);
 
-        // Check if content is probably readerable first
-        let is_readerable = is_probably_readerable(&source, Some(ReadabilityOptions {
-            char_threshold: 25,
-            ..Default::default()
-        }));
+        let (_, report) = create_parser(&html).parse_with_report();
+        assert!(report.is_empty());
 
-        let expected_readerable = expected_metadata["readerable"].as_bool().unwrap_or(false);
-        
-        // If expected to be readerable but our check says no, it might be a threshold issue
-        if expected_readerable && !is_readerable {
-            println!("Warning: {} expected to be readerable but failed readerable check", test_dir);
-        }
+        // A chorus is kept unless collapsing is asked for
+        let verse = |words: &str| format!("<p>{} by the river, singing of the water all day long.</p>", words);
+        let chorus = "<p>Roll on, roll on, the river rolls on.</p><p>Down to the sea where the old boats have gone.</p>";
+        let song = format!("<html><body><article>{}{chorus}{}{chorus}</article></body></html>", verse("Walking"), verse("Sitting"));
+        let text = create_parser(&song).parse().unwrap().text_content.unwrap();
+        assert_eq!(text.matches("Roll on, roll on").count(), 2);
+    }
 
-        // Parse the article
-        let article = parser.parse();
-        
-        if expected_readerable {
-            if let Some(article) = article {
-                // Validate metadata
-                if let Some(expected_title) = expected_metadata["title"].as_str() {
-                    if let Some(actual_title) = &article.title {
-                        // Allow some flexibility in title matching
-                        if !actual_title.contains(expected_title) && !expected_title.contains(actual_title) {
-                            println!("Title mismatch in {}: expected '{}', got '{}'", 
-                                test_dir, expected_title, actual_title);
-                        }
-                    } else {
-                        println!("Missing title in {}: expected '{}'", test_dir, expected_title);
-                    }
-                }
+    #[test]
+    fn test_repeated_blocks_need_same_order() {
+        let (collapsed, removed) = collapse_repeated_blocks("<p>One.</p>\n<p>Two.</p>\n<p>Between.</p>\n<p>Two.</p>\n<p>One.</p>");
+        assert_eq!(removed, 0);
+        assert_eq!(collapsed.matches("One.").count(), 2);
 
-                if let Some(expected_byline) = expected_metadata["byline"].as_str() {
-                    if let Some(actual_byline) = &article.byline {
-                        if actual_byline != expected_byline {
-                            println!("Byline mismatch in {}: expected '{}', got '{}'", 
-                                test_dir, expected_byline, actual_byline);
-                        }
-                    } else {
-                        println!("Missing byline in {}: expected '{}'", test_dir, expected_byline);
-                    }
-                }
+        let (collapsed, removed) = collapse_repeated_blocks("<p>One.</p>\n<p>Two.</p>\n<p>Between.</p>\n<p>One.</p>\n<p>Two.</p>\n<p>After.</p>");
+        assert_eq!(removed, 2);
+        assert_eq!(collapsed, "<p>One.</p>\n<p>Two.</p>\n<p>Between.</p>\n<p>After.</p>");
+    }
 
-                if let Some(expected_lang) = expected_metadata["lang"].as_str() {
-                    if let Some(actual_lang) = &article.lang {
-                        if actual_lang != expected_lang {
-                            println!("Language mismatch in {}: expected '{}', got '{}'", 
-                                test_dir, expected_lang, actual_lang);
-                        }
-                    } else {
-                        println!("Missing language in {}: expected '{}'", test_dir, expected_lang);
-                    }
-                }
+    #[test]
+    fn test_single_repeated_block_kept() {
+        let (collapsed, removed) = collapse_repeated_blocks("<p>* * *</p><p>First part of the story.</p><p>* * *</p><p>Second part.</p>");
+        assert_eq!(removed, 0);
+        assert_eq!(collapsed.matches("* * *").count(), 2);
 
-                if let Some(expected_site_name) = expected_metadata["siteName"].as_str() {
-                    if let Some(actual_site_name) = &article.site_name {
-                        if actual_site_name != expected_site_name {
-                            println!("Site name mismatch in {}: expected '{}', got '{}'", 
-                                test_dir, expected_site_name, actual_site_name);
-                        }
-                    } else {
-                        println!("Missing site name in {}: expected '{}'", test_dir, expected_site_name);
-                    }
-                }
+        // Blocks whose source markup differs from their serialization are still removed and counted
+        let block = "<p class='lead'>The council voted on Tuesday.</p><p data-x=a>Work starts in spring.</p>";
+        let (collapsed, removed) = collapse_repeated_blocks(&format!("{block}<p>Residents welcomed it.</p>{block}"));
+        assert_eq!(removed, 2);
+        assert_eq!(collapsed.matches("council voted").count(), 1);
+        assert_eq!(collapsed.matches("starts in spring").count(), 1);
+    }
 
-                if let Some(expected_published_time) = expected_metadata["publishedTime"].as_str() {
-                    if let Some(actual_published_time) = &article.published_time {
-                        if actual_published_time != expected_published_time {
-                            println!("Published time mismatch in {}: expected '{}', got '{}'", 
-                                test_dir, expected_published_time, actual_published_time);
-                        }
-                    } else {
-                        println!("Missing published time in {}: expected '{}'", test_dir, expected_published_time);
-                    }
-                }
+    #[test]
+    fn test_scope_selector() {
+        let html = r#"<html><body>
+            <div id="site"><p>Site chrome that is long enough, with commas, commas, and more commas, to win scoring easily.</p>
+            <p>More site chrome, with commas, commas, commas, commas, and commas, to dominate the page.</p></div>
+            <div id="mail-body"><div><p>The message the embedding app cares about, short but with enough text.</p></div></div>
+        </body></html>"#;
 
-                // Validate that content exists and has reasonable length
-                if let Some(content) = &article.content {
-                    if content.trim().is_empty() {
-                        println!("Empty content in {}", test_dir);
-                    }
-                } else {
-                    println!("Missing content in {}", test_dir);
-                }
+        let options = ReadabilityOptions { scope_selector: Some("#mail-body".to_string()), ..Default::default() };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        let text = article.text_content.unwrap();
+        assert!(text.contains("embedding app"));
+        assert!(!text.contains("Site chrome"));
 
-                // Validate readerable field
-                assert_eq!(article.readerable, Some(true), "Article should be marked as readerable for {}", test_dir);
-            } else {
-                println!("Failed to parse article for {} (expected to be readerable)", test_dir);
-            }
-        } else {
-            // If not expected to be readerable, parsing might still succeed but with low quality
-            if article.is_some() {
-                println!("Unexpectedly parsed article for {} (expected not readerable)", test_dir);
-            }
-        }
+        let options = ReadabilityOptions { scope_selector: Some("#mail-body[".to_string()), ..Default::default() };
+        assert!(matches!(create_parser_with_options(html, options).parse_result(), Err(ReadabilityError::ParseError(_))));
     }
 
     #[test]
-    fn test_readability_options_default() {
-        let options = ReadabilityOptions::default();
-        assert!(!options.debug);
-        assert_eq!(options.max_elems_to_parse, 0);
-        assert_eq!(options.nb_top_candidates, 5);
-        assert_eq!(options.char_threshold, 25);
-        assert!(!options.keep_classes);
-        assert!(!options.disable_json_ld);
+    fn test_flags_relaxed_for_short_content() {
+        let paragraphs: String = ["Nile", "Yangtze", "Ganges", "Danube"]
+            .iter()
+            .map(|river| format!("<p>The {} carries silt, sand and clay to the sea, where deltas form over centuries.</p>", river))
+            .collect();
+        let html = format!(
+            r#"<html><body><div id="teaser"><p>A short teaser about the rivers of the world.</p></div>
+            <div class="extra">{}</div></body></html>"#,
+            paragraphs
+        );
+        let options = ReadabilityOptions { char_threshold: CharThreshold::Chars(200), ..Default::default() };
+        let mut parser = create_parser_with_options(&html, options);
+        let (result, report) = parser.parse_with_report();
+        assert!(result.unwrap().text_content.unwrap().contains("deltas form"));
+        assert_eq!(report.entries, vec![ReportEntry::FlagsRelaxed { retries: 1 }]);
+
+        // Every parse starts from the configured flags
+        let (_, report) = parser.parse_with_report();
+        assert_eq!(report.entries, vec![ReportEntry::FlagsRelaxed { retries: 1 }]);
+        assert!(parser.options.flags.strip_unlikelys);
     }
 
     #[test]
-    fn test_article_creation() {
-        let article = Article {
-            title: Some("Test Title".to_string()),
-            content: Some("<div>Test content</div>".to_string()),
-            text_content: Some("Test content".to_string()),
-            length: Some(12),
-            excerpt: Some("Test excerpt".to_string()),
-            byline: Some("Test Author".to_string()),
-            readerable: Some(true),
-            dir: None,
-            site_name: Some("Test Site".to_string()),
-            lang: Some("en".to_string()),
-            published_time: None,
-        };
+    fn test_new_uses_configured_flags() {
+        let flags = ReadabilityFlags { clean_conditionally: false, ..Default::default() };
+        let parser = create_parser_with_options("<html><body></body></html>", ReadabilityOptions { flags, ..Default::default() });
+        assert!(!parser.flags.clean_conditionally);
 
-        assert_eq!(article.title.unwrap(), "Test Title");
-        assert_eq!(article.length.unwrap(), 12);
-        assert!(article.excerpt.is_some());
+        // Before any parse, cleaning already follows them
+        let content = r#"<div><p>Rivers carry silt.</p><div><p><a href="/more">More rivers</a></p></div></div>"#;
+        assert!(parser.clean_article_content(content).contains("More rivers"));
     }
 
     #[test]
-    fn test_simple_article_parsing() {
-        let html = r#"
-            <!DOCTYPE html>
-            <html>
-            <head>
-                <title>Test Article</title>
-                <meta name="author" content="John Doe">
-                <meta name="description" content="This is a test article">
-            </head>
-            <body>
-                <h1>Test Article Title</h1>
-                <article>
-                    <p>This is the first paragraph of our test article. It contains enough content to be considered readable.</p>
-                    <p>This is the second paragraph with more content. It helps ensure the article meets the minimum length requirements for processing.</p>
-                    <p>A third paragraph to add more substance to our test article and make it comprehensive enough for testing.</p>
-                </article>
-            </body>
-            </html>
-        "#;
+    fn test_mark_data_tables() {
+        let rows: String = ["Nile", "Amazon", "Yangtze"]
+            .iter()
+            .map(|river| format!(r#"<tr><td><a href="/{}">{}</a></td><td><a href="/km">km</a></td></tr>"#, river.to_lowercase(), river))
+            .collect();
+        let html = format!(
+            r#"<html><body><article>
+            <p>Rivers carry silt, sand and clay to the sea, where deltas form over centuries.</p>
+            <table><caption class="sr-only">Longest rivers</caption>{}</table>
+            <p>Deltas grow, shift and sink, and the largest of them feed millions of people.</p>
+            <table><tr><td><a href="/share">Share</a></td><td><a href="/print">Print</a></td></tr>{}</table>
+            </article></body></html>"#,
+            rows,
+            rows.replace("Nile", "Volga")
+        );
+        let content = create_parser(&html).parse().unwrap().content.unwrap();
+        // Still a data table after its screen-reader caption is gone
+        assert!(content.contains("Yangtze") && !content.contains("Longest rivers"), "{}", content);
+        assert!(!content.contains("Volga"));
+        assert!(!content.contains(DATA_TABLE_ATTR));
+    }
 
-        let mut options = ReadabilityOptions::default();
-        options.debug = true;
-        let mut parser = create_parser_with_options(html, options);
-        let result = parser.parse();
+    #[test]
+    fn test_clean_conditionally() {
+        let content = r#"<div><p>Rivers carry silt, sand and clay to the sea.</p>
+            <div><p><a href="/nile">Nile</a> <a href="/ganges">Ganges</a></p><p><a href="/more">More rivers</a></p></div>
+            <ul><li><img src="nile.jpg"></li><li><img src="ganges.jpg"></li></ul>
+            <table data-readability-table=""><tr><td><a href="/nile">Nile</a></td><td>6650 km</td></tr></table>
+            <div>Deltas form over centuries.</div></div>"#;
+        let mut parser = create_parser("<html><body></body></html>");
+        let cleaned = parser.clean_conditionally(content).unwrap();
+        assert!(!cleaned.contains("More rivers"));
+        assert!(cleaned.contains("ganges.jpg"));
+        assert!(cleaned.contains("6650 km"));
+        assert!(cleaned.contains("Deltas form"));
 
-        assert!(result.is_some());
-        let article = result.unwrap();
-        assert!(article.title.is_some() && !article.title.as_ref().unwrap().is_empty());
-        assert!(article.content.is_some());
-        assert!(article.length.is_some() && article.length.unwrap() > 100);
+        // Nothing to remove leaves the content as it was
+        assert_eq!(parser.clean_conditionally("<p>Rivers carry silt.</p>"), None);
+
+        // The modifier is added to Readability.js's thresholds
+        let linked = r#"<div><div><p>Rivers of the world, from the Nile to the Mekong.</p><p><a href="/rivers">The full list of the rivers</a></p></div></div>"#;
+        assert!(parser.clean_conditionally(linked).is_some());
+        parser.options.link_density_modifier = 0.3;
+        assert_eq!(parser.clean_conditionally(linked), None);
+        parser.options.link_density_modifier = 0.0;
+
+        parser.flags.clean_conditionally = false;
+        assert!(parser.clean_article_content(content).contains("More rivers"));
     }
 
     #[test]
-    fn test_empty_document() {
-        let html = "<html><body></body></html>";
-        let mut options = ReadabilityOptions::default();
-        options.debug = true;
-        let mut parser = create_parser_with_options(html, options);
-        let result = parser.parse();
-        
-        // Empty document should not produce a result
-        assert!(result.is_none());
+    fn test_noscript_images_unwrapped() {
+        let paragraphs: String = ["Nile", "Yangtze", "Ganges", "Danube", "Mekong"]
+            .iter()
+            .map(|river| format!("<p>The {} carries silt, sand and clay to the sea, where deltas form over centuries.</p>", river))
+            .collect();
+        let html = format!(
+            r#"<html><body><article>{}<figure><img src="placeholder.gif" class="lazy"><noscript><img src="delta.jpg" alt="Delta"></noscript></figure>
+            <img class="lazy-empty"></article></body></html>"#,
+            paragraphs
+        );
+
+        let content = create_parser(&html).parse().unwrap().content.unwrap();
+        assert!(content.contains(r#"<img src="delta.jpg" alt="Delta" data-old-src="placeholder.gif">"#), "{}", content);
+        assert!(!content.contains("lazy-empty"));
     }
 
     #[test]
-    fn test_minimal_content() {
-        let html = r#"
-            <html>
-            <body>
-                <p>Short</p>
-            </body>
-            </html>
-        "#;
+    fn test_page_chrome_removed() {
+        let content = r#"<header><h1>Rivers</h1><time datetime="2024-05-01">May 1</time><time datetime="2024-05-02">May 2</time></header>
+            <p>Rivers carry   silt to the sea.</p>
+            <div class="sidebar"><div>Popular</div><p>Trailing sidebar text.</p></div>
+            <aside class="correction">An earlier version misnamed the Nile.</aside>
+            <footer><nav><a href="/">Home</a></nav></footer>"#;
+        let parser = create_parser("<html><body></body></html>");
+        let cleaned = parser.clean_article_content(content);
+        assert!(cleaned.starts_with(r#"<p><time datetime="2024-05-01">May 1</time> <time datetime="2024-05-02">May 2</time></p>"#), "{}", cleaned);
+        assert!(cleaned.contains("<p>Rivers carry silt to the sea.</p>"));
+        assert!(!cleaned.contains("Trailing sidebar text") && !cleaned.contains("Popular"));
+        assert!(cleaned.contains("misnamed the Nile"));
+        assert!(!cleaned.contains("Home") && !cleaned.contains("<h1>"));
+    }
 
-        let mut options = ReadabilityOptions::default();
-        options.debug = true;
-        let mut parser = create_parser_with_options(html, options);
-        let result = parser.parse();
-        
-        // Very short content should not be considered readable
-        assert!(result.is_none());
+    #[test]
+    fn test_text_content_after_cleaning() {
+        let paragraphs: String = ["Nile", "Yangtze", "Ganges", "Danube", "Mekong"]
+            .iter()
+            .map(|river| format!("<p>The {} carries silt, sand and clay to the sea, where deltas form over centuries.</p>", river))
+            .collect();
+        let html = format!(
+            r#"<html><body><article>{}<div class="share"><p>Share on <a href="/x">X</a> and <a href="/fb">Facebook</a></p></div></article></body></html>"#,
+            paragraphs
+        );
+
+        let article = create_parser(&html).parse().unwrap();
+        assert!(!article.content.unwrap().contains("Share on"));
+        let text = article.text_content.unwrap();
+        assert!(!text.contains("Share on"));
+        assert_eq!(article.length, Some(text.len()));
     }
 
     #[test]
-    fn test_article_with_metadata() {
-        let html = r#"
-            <!DOCTYPE html>
-            <html lang="en">
-            <head>
-                <title>Test Article - Test Site</title>
-                <meta name="author" content="Jane Smith">
-                <meta name="description" content="A comprehensive test article for readability testing">
-                <meta property="og:site_name" content="Test Publishing">
-                <meta property="og:title" content="Test Article">
-            </head>
-            <body>
-                <article>
-                    <h1>Test Article Title</h1>
-                    <div class="byline">By Jane Smith</div>
-                    <p>This is a comprehensive test article with enough content to be considered readable by the parser.</p>
-                    <p>The article contains multiple paragraphs with substantial text content that should pass all readability checks.</p>
-                    <p>Additional content to ensure the article meets minimum length requirements and provides meaningful extractable content.</p>
-                    <p>More content to test the parsing and extraction capabilities of the readability implementation.</p>
-                </article>
-            </body>
-            </html>
-        "#;
+    fn test_score_paragraphs() {
+        let fragment = Html::parse_fragment(
+            "<p>  Rivers carry <b>silt</b>, sand and clay\n to the sea.  </p><p>Too short</p>\
+             <p>长江、黄河，还有珠江都流向大海，沿途形成了许多冲积平原。</p>",
+        );
+        let paragraphs: Vec<ElementRef> = fragment.select(&Selector::parse("p").unwrap()).collect();
+        let scores = score_paragraphs(&paragraphs, None);
+        assert_eq!(scores[0], Some(1.0 + 1.0 + 0.45));
+        assert_eq!(scores[1], None);
 
-        let mut parser = create_parser(html);
-        let result = parser.parse();
+        // Large documents (scored in parallel with the `parallel` feature) keep order and scores
+        let many = Html::parse_fragment(&fragment.root_element().inner_html().repeat(10_000));
+        let many: Vec<ElementRef> = many.select(&Selector::parse("p").unwrap()).collect();
+        let expected: Vec<_> = many.iter().map(|paragraph| score_paragraphs(std::slice::from_ref(paragraph), None)[0]).collect();
+        assert_eq!(score_paragraphs(&many, None), expected);
+    }
 
-        assert!(result.is_some());
-        let article = result.unwrap();
-        
-        assert!(article.title.is_some() && !article.title.as_ref().unwrap().is_empty());
-        assert!(article.byline.is_some());
-        assert!(article.site_name.is_some());
-        assert!(article.lang.is_some());
-        assert_eq!(article.lang.as_ref().unwrap(), "en");
-        assert!(article.length.is_some() && article.length.unwrap() > 200);
+    #[test]
+    fn test_paragraph_score_from_chunks() {
+        // Text nodes are measured in place as if joined with spaces and collapsed
+        let joined = |chunks: &[&str]| utils::normalize_whitespace(&chunks.join(" "));
+        for (chunks, style) in [
+            (&["  Rivers carry ", "silt", ", sand and clay\n to the sea.  "][..], CommaStyle::Commas),
+            (&["Deltas", "form", "over centuries, slowly"][..], CommaStyle::Commas),
+            (&["长江、黄河，还有珠江都流向大海，", "沿途形成了许多冲积平原。"][..], CommaStyle::Cjk),
+            (&["ภาษาไทย ไม่มี ", "จุลภาค แต่ใช้ ", "ช่องว่างแทน"][..], CommaStyle::Spaces),
+            (&["", "  "][..], CommaStyle::Commas),
+        ] {
+            assert_eq!(paragraph_score(chunks.iter().copied(), style), paragraph_score([joined(chunks).as_str()], style), "{:?}", chunks);
+        }
+        assert_eq!(paragraph_score(["Rivers carry ", "silt", ", sand and clay to the sea."], CommaStyle::Commas), Some(1.0 + 1.0 + 0.45));
+    }
+
+    #[test]
+    fn test_sibling_content_appended() {
+        let paragraph = |topic: &str| format!("<p>A paragraph about {}, with commas, details, and enough words to be scored as prose.</p>", topic);
+        let html = format!(
+            r#"<html><body><div id="page">
+                <div class="story">{}{}{}</div>
+                <div class="promo"><a href="/shop">Shop now</a></div>
+                <div class="story">{}{}</div>
+                <p>A closing note on the rivers. It has no score of its own.</p>
+                <p><a href="/more">More stories</a></p>
+            </div></body></html>"#,
+            paragraph("rivers"), paragraph("deltas"), paragraph("floods"), paragraph("mountains"), paragraph("glaciers")
+        );
+        let text = create_parser(&html).parse().unwrap().text_content.unwrap();
+        for expected in ["rivers, with", "floods", "glaciers", "closing note"] {
+            assert!(text.contains(expected), "missing {:?} in {}", expected, text);
+        }
+        assert!(!text.contains("Shop now") && !text.contains("More stories"), "{}", text);
     }
 
     #[test]
-    fn test_is_probably_readerable_basic() {
-        // Test with content that should be readerable
-        let readable_html = r#"
-            <html>
-            <body>
-                <article>
-                    <h1>Long Article Title</h1>
-                    <p>This is a long article with substantial content that should be considered readable.</p>
-                    <p>Multiple paragraphs with enough text to meet the readability thresholds.</p>
-                    <p>Additional content to ensure this passes the readability checks.</p>
-                    <p>Even more content to make sure this document is substantial enough.</p>
-                </article>
-            </body>
-            </html>
-        "#;
+    fn test_common_ancestor_of_top_candidates() {
+        let fragment = |class: &str, topic: &str| format!(
+            r#"<div class="{}"><div><p>A paragraph about {}, with commas, details, and enough words to be scored.</p>
+            <p>Another paragraph about {}, with more commas, asides, and words to be scored.</p></div></div>"#,
+            class, topic, topic
+        );
+        let html = format!(
+            r#"<html><body><div id="article">{}{}{}{}</div></body></html>"#,
+            fragment("intro", "rivers"), fragment("part", "deltas"), fragment("aside-box", "floods"), fragment("outro", "estuaries")
+        );
 
-        assert!(is_probably_readerable(readable_html, None));
+        let text = create_parser(&html).parse().unwrap().text_content.unwrap();
+        for topic in ["rivers", "deltas", "floods", "estuaries"] {
+            assert!(text.contains(topic), "missing {:?} in {}", topic, text);
+        }
 
-        // Test with content that should not be readerable
-        let unreadable_html = r#"
-            <html>
-            <body>
-                <nav>Menu</nav>
-                <footer>Copyright</footer>
-            </body>
-            </html>
-        "#;
+        // The last fragment scores highest on its own
+        let options = ReadabilityOptions { nb_top_candidates: 1, ..Default::default() };
+        let text = create_parser_with_options(&html, options).parse().unwrap().text_content.unwrap();
+        assert!(text.contains("estuaries") && !text.contains("rivers"), "{}", text);
+    }
 
-        assert!(!is_probably_readerable(unreadable_html, None));
+    #[test]
+    fn test_max_candidate_depth() {
+        let html = r#"<html><body><section id="outer"><div id="inner">
+            <p>First paragraph of the article, long enough to be scored as content.</p>
+        </div></section></body></html>"#;
+
+        let parser = create_parser_with_options(html, ReadabilityOptions { max_candidate_depth: 1, ..Default::default() });
+        let ids: Vec<_> = parser.find_and_score_candidates().iter().map(|(e, _)| e.value().id().unwrap_or("").to_string()).collect();
+        assert_eq!(ids, vec!["inner"]);
+
+        let parser = create_parser_with_options(html, ReadabilityOptions { max_candidate_depth: 3, ..Default::default() });
+        assert_eq!(parser.find_and_score_candidates().len(), 3);
     }
 
     #[test]
-    fn test_is_probably_readerable_with_options() {
-        let html = r#"
-            <html>
-            <body>
-                <p>Medium length content that is somewhat substantial.</p>
-            </body>
-            </html>
-        "#;
+    fn test_score_document() {
+        let html = r#"<html><body>
+            <nav><a href="/">Home</a></nav>
+            <div id="story"><p>The first paragraph of the story, long enough, with a comma or two, to be scored.</p>
+            <p>The second paragraph continues the story, again with commas, clauses, and detail.</p></div>
+        </body></html>"#;
 
-        // With default options, this should not be readerable
-        assert!(!is_probably_readerable(html, None));
+        let location = score_document(html, None).unwrap();
+        assert_eq!(location.css_path, "div#story");
+        assert!(location.score > 0.0);
+        assert_eq!(location.char_len, get_inner_text(
+            &Html::parse_document(html).select(&Selector::parse("#story").unwrap()).next().unwrap(), true
+        ).chars().count());
 
-        // With lower thresholds, this should be readerable
-        let lenient_options = ReadabilityOptions {
-            char_threshold: 20,
+        let location = score_document("<html><body><article>Hi</article></body></html>", None).unwrap();
+        assert_eq!(location.css_path, "html > body > article");
+        assert_eq!(location.score, 0.0);
+
+        // The user's flags apply: without class weights the story's class and id earn nothing
+        let html = html.replace(r#"id="story""#, r#"id="story" class="article""#);
+        let weighted = score_document(&html, None).unwrap();
+        let options = ReadabilityOptions {
+            flags: ReadabilityFlags { weight_classes: false, ..Default::default() },
             ..Default::default()
         };
-        assert!(is_probably_readerable(html, Some(lenient_options)));
+        let unweighted = score_document(&html, Some(options)).unwrap();
+        assert!(unweighted.score < weighted.score, "{} vs {}", unweighted.score, weighted.score);
     }
 
     #[test]
-    fn test_parser_creation() {
-        let html = "<html><body><p>Test content</p></body></html>";
-        let parser = Readability::new(html, None);
-        assert!(parser.is_ok());
+    fn test_source_map() {
+        let html = r#"<html><body><nav><a href="/">Home</a></nav><article id="post">
+            <h2>Background</h2>
+            <p>The first paragraph of the story, long enough, with a comma or two, to be scored.</p>
+            <div><p>The second paragraph continues the story, again with commas, clauses, and detail.</p></div>
+        </article></body></html>"#;
+
+        let options = ReadabilityOptions { include_blocks: true, include_source_map: true, ..Default::default() };
+        let article = create_parser_with_options(html, options).parse().unwrap();
+        assert_eq!(article.source_map.len(), article.blocks.unwrap().len());
+        assert_eq!(article.source_map[0].path, "article#post > h2");
+        assert_eq!(article.source_map[2].path, "article#post > div > p");
+
+        assert!(create_parser(html).parse().unwrap().source_map.is_empty());
     }
 
+    #[cfg(feature = "json-ld")]
     #[test]
-    fn test_parser_with_options() {
-        let html = "<html><body><p>Test content</p></body></html>";
-        let options = ReadabilityOptions {
-            debug: true,
-            char_threshold: 100,
-            ..Default::default()
-        };
-        let parser = Readability::new(html, Some(options));
-        assert!(parser.is_ok());
+    fn test_apply_patch_and_reparse() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">{"@type": "NewsArticle", "headline": "Draft", "author": {"name": "Ann"}}</script>
+        </head><body><article>
+            <p>The first paragraph of the draft, long enough, with a comma or two, to be scored.</p>
+            <div id="editing"><p>Original second paragraph that the editor is about to change.</p></div>
+        </article></body></html>"#;
+
+        let mut parser = create_parser(html);
+        assert!(parser.parse().unwrap().text_content.unwrap().contains("Original second"));
+
+        parser.apply_patch("#editing", "<p>Rewritten second paragraph, typed in the <em>editor</em> just now.</p>").unwrap();
+        let article = parser.parse().unwrap();
+        let text = article.text_content.unwrap();
+        assert!(text.contains("Rewritten second paragraph"));
+        assert!(!text.contains("Original second"));
+        assert_eq!(article.authors.len(), 1);
+
+        assert!(matches!(parser.apply_patch("#missing", "<p>x</p>"), Err(ReadabilityError::ParseError(_))));
+
+        // Replaced nodes don't pile up over many edits
+        let nodes = parser.document.tree.nodes().count();
+        for edit in 0..100 {
+            parser.apply_patch("#editing", &format!("<p>Edit number {}, typed in the <em>editor</em> just now.</p>", edit)).unwrap();
+        }
+        assert!(parser.document.tree.nodes().count() < 2 * nodes);
+        assert!(parser.parse().unwrap().text_content.unwrap().contains("Edit number 99"));
     }
 
     #[test]
-    fn test_unicode_handling() {
-        let unicode_html = r#"
-            <!DOCTYPE html>
-            <html lang="zh">
-            <head>
-                <title>测试文章</title>
-                <meta charset="UTF-8">
-            </head>
-            <body>
-                <article>
-                    <h1>Unicode Content Test</h1>
-                    <p>This article contains unicode characters: 测试 🚀 ñáéíóú àèìòù</p>
-                    <p>Emoji support test: 😀 🎉 🌟 💻 📚</p>
-                    <p>Various languages: English, Español, Français, 中文, 日本語, العربية</p>
-                    <p>Special characters: ™ © ® € £ ¥ § ¶ † ‡ • … ‰ ′ ″ ‹ › « » " " ' '</p>
-                </article>
-            </body>
-            </html>
-        "#;
+    fn test_memory_limit() {
+        let html = format!("<html><body><article>{}</article></body></html>",
+            "<p>A paragraph of text, with a comma, repeated many times over.</p>".repeat(200));
 
-        let mut parser = create_parser(unicode_html);
-        let result = parser.parse();
+        let options = ReadabilityOptions { max_memory_bytes: 4096, ..Default::default() };
+        assert!(matches!(
+            create_parser_with_options(&html, options).parse_result(),
+            Err(ReadabilityError::MemoryLimitExceeded { limit: 4096, .. })
+        ));
 
-        assert!(result.is_some());
-        let article = result.unwrap();
-        
-        // Should handle unicode content without panicking
-        assert!(article.title.is_some());
-        assert!(article.text_content.is_some());
+        let options = ReadabilityOptions { max_memory_bytes: 64 * 1024 * 1024, ..Default::default() };
+        assert!(create_parser_with_options(&html, options).parse_result().is_ok());
+    }
+
+    #[test]
+    fn test_memory_limit_counts_retries() {
+        let paragraphs: String = ["Nile", "Amazon", "Yangtze"]
+            .iter()
+            .map(|river| format!("<p>The {} carries silt, sand and clay to the sea, where deltas form over centuries.</p>", river))
+            .collect();
+        let html = format!(
+            r#"<html><body><div id="teaser"><p>A short teaser about the rivers of the world.</p></div>
+            <div class="extra">{}</div></body></html>"#,
+            paragraphs
+        );
+        // Room for the document, but not for the copies a retry keeps
+        let limit = memory::estimate_document_bytes(&Html::parse_document(&html)) * 3 / 2;
+        let options = ReadabilityOptions { char_threshold: CharThreshold::Chars(200), max_memory_bytes: limit, ..Default::default() };
+        assert!(matches!(
+            create_parser_with_options(&html, options.clone()).parse_result(),
+            Err(ReadabilityError::MemoryLimitExceeded { used, .. }) if used > limit
+        ));
+
+        let options = ReadabilityOptions { char_threshold: CharThreshold::Chars(20), ..options };
+        assert!(create_parser_with_options(&html, options).parse_result().is_ok());
     }
 
     #[test]
@@ -1363,7 +5589,7 @@ mod tests {
         
         // Create parser with lower character threshold for malformed HTML
         let options = ReadabilityOptions {
-            char_threshold: 50, // Lower threshold for this test
+            char_threshold: CharThreshold::Chars(50), // Lower threshold for this test
             debug: true,
             ..Default::default()
         };
@@ -1685,12 +5911,15 @@ mod tests {
         assert!(article.byline.is_some());
         
         // Test that the article can be serialized (important for CLI JSON output)
-        let json_result = serde_json::to_string(&article);
-        assert!(json_result.is_ok());
-        
-        let json_str = json_result.unwrap();
-        assert!(json_str.contains("CLI Integration Test"));
-        assert!(json_str.contains("CLI Tester"));
+        #[cfg(feature = "serde")]
+        {
+            let json_result = serde_json::to_string(&article);
+            assert!(json_result.is_ok());
+
+            let json_str = json_result.unwrap();
+            assert!(json_str.contains("CLI Integration Test"));
+            assert!(json_str.contains("CLI Tester"));
+        }
     }
 
     #[test]
@@ -1710,45 +5939,34 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "corpus")]
     #[test]
     fn test_all_mozilla_test_cases() {
         // This test runs all available Mozilla test cases
-        let test_dirs = get_test_case_dirs();
-        
-        if test_dirs.is_empty() {
+        let test_pages_path = Path::new("mozzila-readability/test/test-pages");
+        if !test_pages_path.exists() {
             println!("No Mozilla test cases found - skipping comprehensive test");
             return;
         }
-        
-        println!("Running {} Mozilla test cases", test_dirs.len());
-        
-        let mut passed = 0;
-        let mut failed = 0;
-        
-        for test_dir in &test_dirs {
-            println!("Testing: {}", test_dir);
-            
-            // Catch panics to continue testing other cases
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                test_mozilla_case(test_dir);
-            }));
-            
-            match result {
-                Ok(_) => {
-                    passed += 1;
-                    println!("✓ {}", test_dir);
-                },
-                Err(e) => {
-                    failed += 1;
-                    println!("✗ {} - {:?}", test_dir, e);
-                }
-            }
+
+        let options = ReadabilityOptions {
+            classes_to_preserve: vec!["caption".to_string()],
+            ..Default::default()
+        };
+        let stats = corpus::run_corpus(test_pages_path, &options).unwrap();
+
+        println!("Mozilla test results over {} cases:", stats.cases.len());
+        println!("  success rate: {:.3}", stats.success_rate());
+        println!("  mean text similarity: {:.3}", stats.mean_text_similarity());
+        for field in corpus::METADATA_FIELDS {
+            println!("  {} accuracy: {:.3}", field, stats.field_accuracy(field));
         }
-        
-        println!("\nMozilla test results: {} passed, {} failed", passed, failed);
-        
-        // Don't fail the test if some cases fail - this is for compatibility checking
-        // assert!(failed == 0, "Some Mozilla test cases failed");
+        println!("  failed: {:?}", stats.failures());
+
+        // Floors against regressions; raise them as parity improves
+        assert!(stats.success_rate() >= 0.9, "success rate {:.3}", stats.success_rate());
+        assert!(stats.mean_text_similarity() >= 0.6, "text similarity {:.3}", stats.mean_text_similarity());
+        assert!(stats.field_accuracy("title") >= 0.6, "title accuracy {:.3}", stats.field_accuracy("title"));
     }
 
     #[test]
@@ -1763,7 +5981,7 @@ mod tests {
             if let Ok((source, _, expected_metadata)) = load_test_case(test_dir) {
                 let mut parser = Readability::new_with_base_uri(&source, "http://fakehost/test/page.html", Some(ReadabilityOptions {
                     debug: false,
-                    char_threshold: 25,
+                    char_threshold: CharThreshold::Chars(25),
                     ..Default::default()
                 })).unwrap();
                 
@@ -1814,7 +6032,7 @@ mod tests {
             if let Ok((source, _, expected_metadata)) = load_test_case(test_case) {
                 let expected_readerable = expected_metadata["readerable"].as_bool().unwrap_or(false);
                 let actual_readerable = is_probably_readerable(&source, Some(ReadabilityOptions {
-                    char_threshold: 25,
+                    char_threshold: CharThreshold::Chars(25),
                     ..Default::default()
                 }));
                 
@@ -1842,7 +6060,7 @@ mod tests {
             if let Ok((source, _expected_content, _)) = load_test_case(test_case) {
                 let mut parser = Readability::new_with_base_uri(&source, "http://fakehost/test/page.html", Some(ReadabilityOptions {
                     debug: false,
-                    char_threshold: 25,
+                    char_threshold: CharThreshold::Chars(25),
                     classes_to_preserve: vec!["caption".to_string()],
                     ..Default::default()
                 })).unwrap();
@@ -1888,7 +6106,7 @@ mod tests {
             if let Ok((source, _, _expected_metadata)) = load_test_case(test_case) {
                 let mut parser = Readability::new_with_base_uri(&source, "http://fakehost/test/page.html", Some(ReadabilityOptions {
                     debug: false,
-                    char_threshold: 100,  // Lower threshold for edge cases
+                    char_threshold: CharThreshold::Chars(100),  // Lower threshold for edge cases
                     ..Default::default()
                 })).unwrap();
                 
@@ -1908,4 +6126,521 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_deterministic_output() {
+        // Two equally scored containers, and attributes in source order
+        let section = r#"<div><p>An equally long paragraph, with commas, and enough text to be scored.</p>
+            <p><img src="photo.jpg" alt="A photo" width="300" height="200"></p></div>"#;
+        let html = format!("<html><body>{}{}</body></html>", section, section.replace("equally", "similarly"));
+
+        let first = Readability::new(&html, None).unwrap().parse().unwrap().content;
+        for _ in 0..20 {
+            assert_eq!(Readability::new(&html, None).unwrap().parse().unwrap().content, first);
+        }
+        assert!(first.unwrap().contains(r#"src="photo.jpg" alt="A photo" width="300" height="200""#));
+    }
+
+    #[test]
+    fn test_round_trip_idempotent() {
+        let html = r#"<html><head><title>Story</title></head><body><div id="main"><div class="post">
+            <h2>Section</h2>
+            <p>The first paragraph of the story, with commas, details, and enough words to count.</p>
+            <div><p>A nested paragraph continues the story, with more details, and more commas.</p></div>
+            <ul><li>First item</li><li>Second item</li></ul>
+            <p>A closing paragraph with a <a href="page.html">relative link</a>, and yet more commas.</p>
+        </div></div></body></html>"#;
+        let options = || Some(ReadabilityOptions { char_threshold: CharThreshold::Chars(100), ..Default::default() });
+
+        let first = Readability::new_with_base_uri(html, "http://example.com/a/", options())
+            .unwrap().parse().unwrap().content.unwrap();
+        let second = Readability::new_with_base_uri(&first, "http://example.com/a/", options())
+            .unwrap().parse().unwrap().content.unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_algorithm_version() {
+        let html = "<html><body><article><p>A long paragraph about rivers, with commas, and enough text to pass.</p></article></body></html>";
+
+        let legacy = Readability::new(html, None).unwrap().parse().unwrap();
+        assert_eq!(legacy.algorithm_version, AlgorithmVersion::V1Legacy);
+        assert!(legacy.content.as_deref().unwrap().starts_with("<p>"));
+
+        let options = ReadabilityOptions { algorithm_version: AlgorithmVersion::V2MozillaParity, ..Default::default() };
+        let parity = Readability::new(html, Some(options.clone())).unwrap().parse().unwrap();
+        assert_eq!(parity.algorithm_version, AlgorithmVersion::V2MozillaParity);
+        let content = parity.content.unwrap();
+        assert!(content.starts_with(r#"<div id="readability-page-1" class="page"><p>"#));
+
+        // Still idempotent: the page wrapper isn't nested again
+        let reparsed = Readability::new(&content, Some(options)).unwrap().parse().unwrap();
+        assert_eq!(reparsed.content.unwrap(), content);
+    }
+
+    #[test]
+    fn test_content_hashes() {
+        let parse = |html: &str| Readability::new(html, None).unwrap().parse().unwrap();
+        let article = parse("<html><body><article><p>A long paragraph about <b>rivers</b> with commas, and enough text to pass.</p></article></body></html>");
+        let restyled = parse("<html><body><article><p>A long paragraph about <i>rivers</i> with commas, and enough text to pass.</p></article></body></html>");
+
+        let content_hash = article.content_hash.unwrap();
+        assert_eq!(content_hash.len(), 64);
+        assert_eq!(content_hash, utils::sha256_hex(article.content.as_deref().unwrap()));
+        assert_ne!(Some(content_hash), restyled.content_hash);
+        assert_eq!(article.text_hash, restyled.text_hash);
+    }
+
+    #[test]
+    fn test_metadata_provenance() {
+        let html = r#"<html lang="en"><head><title>Site</title>
+            <meta property="og:site_name" content="The Daily">
+            <script type="application/ld+json">{"@type": "NewsArticle", "datePublished": "2024-05-01"}</script>
+            </head><body><article><h1>A Descriptive Heading</h1>
+            <p class="byline">By Jane Doe</p>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            </article></body></html>"#;
+
+        let (result, report) = create_parser(html).parse_with_report();
+        let article = result.unwrap();
+        let provenance = report.provenance;
+        assert_eq!(article.title.as_deref(), Some("A Descriptive Heading"));
+        assert_eq!(provenance.get("title"), Some(MetadataSource::Heading));
+        assert_eq!(provenance.get("byline"), Some(MetadataSource::BylineElement));
+        assert_eq!(provenance.get("site_name"), Some(MetadataSource::OpenGraph));
+        assert_eq!(provenance.get("lang"), Some(MetadataSource::HtmlAttribute));
+        assert_eq!(provenance.get("excerpt"), Some(MetadataSource::FirstParagraph));
+        #[cfg(feature = "json-ld")]
+        assert_eq!(provenance.get("published_time"), Some(MetadataSource::JsonLd));
+        assert_eq!(provenance.get("dir"), None);
+
+        let html = r#"<html><head><title>Site</title>
+            <meta property="og:title" content=" Rivers of the World ">
+            </head><body><article><h1>A Descriptive Heading</h1>
+            <p>Posted <time>today</time>, updated <time datetime="2024-05-02T09:00:00Z">Thursday</time>.</p>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            </article></body></html>"#;
+        let (result, report) = create_parser(html).parse_with_report();
+        let article = result.unwrap();
+        assert_eq!(article.title.as_deref(), Some("Rivers of the World"));
+        assert_eq!(report.provenance.get("title"), Some(MetadataSource::OpenGraph));
+        assert_eq!(article.published_time.as_deref(), Some("2024-05-02T09:00:00Z"));
+        assert_eq!(report.provenance.get("published_time"), Some(MetadataSource::TimeElement));
+    }
+
+    #[test]
+    fn test_multiple_article_elements() {
+        // No scorable paragraphs, so the fallback selection is used
+        let html = r#"<html><body>
+            <article><a href="/a">Teaser one</a> <a href="/b">Read more</a></article>
+            <article><h2>The Story</h2><div>The real story body, which goes on for a while with plenty of words in it.</div></article>
+            <article><div>Short note.</div></article>
+        </body></html>"#;
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("The real story body"));
+        assert!(!content.contains("Teaser one"));
+
+        // Page parts of one story are merged through their common container
+        let html = r#"<html><body><div id="story">
+            <div itemprop="articleBody"><div>First part of the story, told in a few words.</div></div>
+            <div itemprop="articleBody"><div>Second part of the story, continued here.</div></div>
+        </div><article><div>Unrelated teaser.</div></article></body></html>"#;
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("First part") && content.contains("Second part"));
+        assert!(!content.contains("Unrelated teaser"));
+    }
+
+    #[test]
+    fn test_declared_content_root() {
+        let document = Html::parse_fragment(r#"<div itemprop="headline articleBody"></div><main role="main"></main><div role="navigation"></div>"#);
+        let elements: Vec<ElementRef> = document.select(&Selector::parse("div, main").unwrap()).collect();
+        assert!(is_declared_content_root(&elements[0]));
+        assert!(is_declared_content_root(&elements[1]));
+        assert!(!is_declared_content_root(&elements[2]));
+
+        // The declared root beats a longer sibling block
+        let paragraph = "<p>A paragraph of the same length, with commas, and enough words to be scored.</p>";
+        let html = format!(
+            r#"<html><body><div class="block">{p}{p}{p}</div><div itemprop="articleBody">{p}{p}<p>Declared.</p></div></body></html>"#,
+            p = paragraph
+        );
+        let content = create_parser(&html).parse().unwrap().content.unwrap();
+        assert!(content.contains("Declared."));
+    }
+
+    #[test]
+    fn test_hidden_content_removed() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.<span class="sr-only">Opens in a new window</span></p>
+            <div class="d-none">Desktop-only promo text.</div>
+            <p style="display: none">Hidden paragraph, which is long enough, with commas too.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let text = create_parser(html).parse().unwrap().text_content.unwrap();
+        assert!(text.contains("rivers") && text.contains("mountains"));
+        assert!(!text.contains("Opens in a new window"));
+        assert!(!text.contains("promo"));
+        assert!(!text.contains("Hidden paragraph"));
+
+        let options = ReadabilityOptions { keep_sr_only: true, ..Default::default() };
+        let text = create_parser_with_options(html, options).parse().unwrap().text_content.unwrap();
+        assert!(text.contains("Opens in a new window"));
+        assert!(!text.contains("promo"));
+    }
+
+    #[test]
+    fn test_time_elements() {
+        let html = r#"<html><body><article>
+            <header><h1>Release notes</h1><a href="/">Home</a> <time datetime="2024-03-01">March 1, 2024</time></header>
+            <p>A long paragraph about the release, with commas, and enough text to pass.</p>
+            <p>Fixed in <time>version 2</time>, with commas, and enough text to pass too.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(article.times, vec![
+            ("March 1, 2024".to_string(), Some("2024-03-01".to_string())),
+            ("version 2".to_string(), None),
+        ]);
+        let content = article.content.unwrap();
+        assert!(content.contains(r#"<time datetime="2024-03-01">"#));
+        assert!(!content.contains("Home"));
+    }
+
+    #[test]
+    fn test_toc_policy() {
+        let html = r##"<html><body><article>
+            <div class="toc"><b>Contents</b><ul><li><a href="#history">History</a></li><li><a href="#geography">Geography</a></li><li><a href="#economy">Economy</a></li></ul></div>
+            <h2 id="history">History</h2><p>A long paragraph about history, with commas, and enough text to pass.</p>
+            <h2 id="geography">Geography</h2><p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <h2 id="economy">Economy</h2><p>A long paragraph about trade, with commas, and enough text to pass.</p>
+        </article></body></html>"##;
+        let parse = |toc_policy| {
+            let options = ReadabilityOptions { toc_policy, ..Default::default() };
+            create_parser_with_options(html, options).parse().unwrap().content.unwrap()
+        };
+
+        let removed = parse(TocPolicy::Remove);
+        assert!(!removed.contains("Contents"));
+        assert!(!removed.contains(r##"href="#history""##));
+        assert!(parse(TocPolicy::Keep).contains("Contents"));
+
+        let rebuilt = parse(TocPolicy::Rebuild);
+        assert!(!rebuilt.contains("Contents"));
+        assert!(rebuilt.contains(r##"<ul><li><a href="#history">History</a></li><li><a href="#geography">Geography</a></li>"##));
+    }
+
+    #[test]
+    fn test_inline_ads_removed() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <div data-ad="mid-1"><iframe src="https://ads.example.com/x"></iframe></div>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+            <div class="inline-ad"><span>Sponsored offer</span></div>
+            <div id="taboola-mid-article"><a href="/x">Promoted story</a></div>
+            <div><div>Advertisement</div></div>
+            <p>A closing paragraph about valleys, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let article = create_parser(html).parse().unwrap();
+        let content = article.content.unwrap();
+        assert!(!content.contains("ads.example.com"));
+        assert!(!content.contains("Sponsored offer"));
+        assert!(!content.contains("Promoted story"));
+        assert!(!article.text_content.unwrap().contains("Advertisement"));
+        assert!(content.contains("mountains") && content.contains("valleys"));
+    }
+
+    #[test]
+    fn test_recommendation_widgets_removed() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+            <div class="OUTBRAIN" data-widget-id="AR_1"><p>You may also like, with commas, these stories from around the web.</p></div>
+            <section class="zergnet-widget"><p>Around the web: ten things, with commas, that will surprise you today.</p></section>
+        </article></body></html>"#;
+
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("mountains"));
+        assert!(!content.contains("You may also like"));
+        assert!(!content.contains("Around the web"));
+    }
+
+    #[test]
+    fn test_floating_elements_removed() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <div style="position: fixed; bottom: 0"><p>Subscribe now, for just one dollar, and read everything we publish.</p></div>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+            <div class="sticky-share"><p>Share this story, with your friends, on every network you use.</p></div>
+        </article></body></html>"#;
+
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("rivers"));
+        assert!(content.contains("mountains"));
+        assert!(!content.contains("Subscribe now"));
+        assert!(!content.contains("Share this story"));
+    }
+
+    #[test]
+    fn test_print_hints() {
+        let html = r#"<html><head><style>@media print { .promo { display: none } }</style></head><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <div class="promo"><p>Try our newsletter, delivered daily, free for a whole month.</p></div>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+            <p class="noprint">Jump to comments, or read more, below this article.</p>
+            <p class="d-none d-print-block">Originally published at example.com, in print.</p>
+        </article></body></html>"#;
+
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("Try our newsletter"));
+        assert!(!content.contains("Originally published"));
+
+        let options = ReadabilityOptions { use_print_hints: true, ..Default::default() };
+        let content = Readability::new(html, Some(options)).unwrap().parse().unwrap().content.unwrap();
+        assert!(content.contains("mountains"));
+        assert!(!content.contains("Try our newsletter"));
+        assert!(!content.contains("Jump to comments"));
+        assert!(content.contains("Originally published"));
+    }
+
+    #[test]
+    fn test_char_threshold_auto() {
+        assert_eq!(CharThreshold::Auto.resolve(Some("en-US")), 500);
+        assert_eq!(CharThreshold::Auto.resolve(Some("zh-Hans")), 150);
+        assert_eq!(CharThreshold::Chars(40).resolve(Some("ja")), 40);
+
+        let chinese = "我们在河边散步，看见许多鸟在水面上飞翔，风景非常美丽。".repeat(8);
+        let html = format!("<html><body><article><p>{}</p></article></body></html>", chinese);
+        let options = || Some(ReadabilityOptions { char_threshold: CharThreshold::Auto, ..Default::default() });
+        assert!(Readability::new(&html, options()).unwrap().parse().is_some());
+
+        let english = "We walked along the river, watching birds fly over the water. ".repeat(4);
+        let html = format!("<html><body><article><p>{}</p></article></body></html>", english);
+        assert!(english.len() > 200);
+        assert!(Readability::new(&html, options()).unwrap().parse().is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_utf8() {
+        let mut html = b"<html><body><article><p>A long paragraph about rivers, with commas, and enough text to pass.</p><p>Caf".to_vec();
+        html.extend_from_slice(b"\xe9 and \xff\xfe more words, with commas, in a second paragraph.</p></article></body></html>");
+
+        let article = Readability::from_bytes(&html, None).unwrap().parse().unwrap();
+        assert_eq!(article.decoding_errors, 3);
+        assert!(article.text_content.unwrap().contains("Caf\u{FFFD} and \u{FFFD}\u{FFFD} more"));
+
+        let valid = Readability::from_bytes("<p>Café</p>".as_bytes(), None).unwrap();
+        assert_eq!(valid.decoding_errors, 0);
+    }
+
+    #[test]
+    fn test_dirty_document_prefix() {
+        let page = r#"<!DOCTYPE html><html lang="en"><head><title>River Story</title></head><body>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </body></html>"#;
+        let inputs = [
+            format!("\u{FEFF}{}", page),
+            format!("\r\n\t {}", page),
+            format!("<br />\n<b>Warning</b>:  Undefined variable $id in <b>/var/www/article.php</b> on line <b>12</b><br />\n{}", page),
+            format!("Notice: Undefined index: id in /var/www/article.php on line 5\n{}", page),
+        ];
+        for html in &inputs {
+            let article = Readability::new(html, None).unwrap().parse().unwrap();
+            assert_eq!(article.title.as_deref(), Some("River Story"));
+            assert_eq!(article.lang.as_deref(), Some("en"));
+            let content = article.content.unwrap();
+            assert!(content.starts_with("<p>A long paragraph"), "{}", content);
+            assert!(!content.contains("Undefined"));
+        }
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        for unit in page.encode_utf16() {
+            utf16.extend_from_slice(&unit.to_le_bytes());
+        }
+        let article = Readability::from_bytes(&utf16, None).unwrap().parse().unwrap();
+        assert_eq!(article.title.as_deref(), Some("River Story"));
+        assert_eq!(article.decoding_errors, 0);
+    }
+
+    #[test]
+    fn test_template_contents() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+            <template id="comment"><p>Reply template, with commas, shown only when someone replies.</p></template>
+            <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("mountains"));
+        assert!(!content.contains("Reply template"));
+
+        let options = ReadabilityOptions { lift_templates: true, ..Default::default() };
+        let content = Readability::new(html, Some(options)).unwrap().parse().unwrap().content.unwrap();
+        assert!(content.contains("Reply template"));
+        assert!(!content.contains("<template"));
+    }
+
+    #[test]
+    fn test_declarative_shadow_dom() {
+        let html = r#"<html><head><title>Rivers</title></head><body>
+            <story-page><template shadowrootmode="open">
+                <article>
+                    <slot name="headline"><h1>Untitled</h1></slot>
+                    <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+                    <slot></slot>
+                    <slot name="footer"><p>Default footer, with commas, shown when the page has none.</p></slot>
+                </article>
+            </template>
+                <h2 slot="headline">Rivers of the World</h2>
+                <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+                <p slot="sidebar">Unslotted sidebar, with commas, that browsers never render.</p>
+            </story-page>
+        </body></html>"#;
+
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("rivers"));
+        assert!(content.contains("mountains"));
+        assert!(content.contains("Default footer"));
+        assert!(!content.contains("Untitled"));
+        assert!(!content.contains("Unslotted sidebar"));
+        assert!(!content.contains("<template") && !content.contains("<slot"));
+        assert!(content.find("rivers").unwrap() < content.find("mountains").unwrap());
+    }
+
+    #[test]
+    fn test_srcdoc_iframe() {
+        let message: String = ["rivers", "mountains", "forests", "deserts"].iter()
+            .map(|topic| format!("<p>A long paragraph about {}, with commas, and enough text to pass &amp; more.</p>", topic))
+            .collect();
+        let html = format!(
+            r#"<html><head><title>Newsletter archive</title></head><body>
+                <div class="viewer"><iframe sandbox srcdoc="<html><body><div>{}</div></body></html>"></iframe></div>
+                <iframe srcdoc="<p>Tiny ad</p>"></iframe>
+            </body></html>"#,
+            message.replace('"', "&quot;").replace("&amp;", "&amp;amp;")
+        );
+
+        let article = create_parser(&html).parse().unwrap();
+        let content = article.content.unwrap();
+        assert!(content.contains("rivers") && content.contains("deserts"));
+        assert!(content.contains("&amp; more"));
+        assert!(!content.contains("srcdoc"));
+        assert!(!article.text_content.unwrap().contains("Tiny ad"));
+    }
+
+    #[test]
+    fn test_flat_documents() {
+        let options = || Some(ReadabilityOptions { char_threshold: CharThreshold::Chars(100), ..Default::default() });
+        let html = r#"<html><head><title>Notes</title></head><body>
+            <h1>Field notes</h1>
+            A long line about rivers, with commas, and enough words to count.<br>
+            A second line about mountains, with <b>commas</b>, and enough words to count.<br><br>
+            A third line about forests, with commas, and enough words to count.<br><br><br>
+        </body></html>"#;
+        let content = Readability::new(html, options()).unwrap().parse().unwrap().content.unwrap();
+        assert_eq!(content.matches("<p>").count(), 2, "{}", content);
+        assert!(content.contains("to count.<br>"));
+        assert!(content.contains("<b>commas</b>"));
+        assert!(content.contains("forests, with commas, and enough words to count.</p>"));
+
+        // Plain text in a wrapper, split at blank lines
+        let html = "<html><body><div>First paragraph about rivers, with commas, and enough words to count.\n\n\
+            Second paragraph about mountains, with commas, and enough words to count.</div></body></html>";
+        let content = Readability::new(html, options()).unwrap().parse().unwrap().content.unwrap();
+        assert_eq!(content.matches("<p>").count(), 2, "{}", content);
+    }
+
+    #[test]
+    fn test_data_tables_kept_whole() {
+        let html = r#"<html><body><article>
+            <p>A long paragraph about advertising, with commas, and enough text to pass.</p>
+            <table><caption>Rates</caption>
+                <tr><th>Placement</th><th>Price</th></tr>
+                <tr><td><div class="ad-slot">Banner</div></td><td>$10</td></tr>
+                <tr><td><div class="sidebar">Sidebar</div></td><td>$5</td></tr>
+            </table>
+            <table><tr><td><p>Layout cell, with commas, and enough text to pass the check.</p></td>
+                <td><div class="ad-slot">Buy now</div></td></tr></table>
+            <p>Another paragraph about prices, with commas, and enough text to pass.</p>
+        </article></body></html>"#;
+
+        let content = create_parser(html).parse().unwrap().content.unwrap();
+        assert!(content.contains("Banner") && content.contains("Sidebar"), "{}", content);
+        assert!(content.contains("Layout cell"));
+        assert!(!content.contains("Buy now"));
+    }
+
+    #[test]
+    fn test_identical_data_tables_kept() {
+        let table = r#"<table><caption>Rates</caption>
+                <tr><th>Placement</th><th>Price</th></tr>
+                <tr><td>Banner</td><td>$10</td></tr>
+                <tr><td>Sidebar</td><td>$5</td></tr>
+            </table>"#;
+        let html = format!(
+            r#"<html><body><article>
+            <p>A long paragraph about advertising, with commas, and enough text to pass.</p>
+            {table}
+            <p>Another paragraph about prices, with commas, and enough text to pass.</p>
+            {table}
+            <p>A last paragraph about discounts, with commas, and enough text to pass.</p>
+        </article></body></html>"#
+        );
+
+        let content = create_parser(&html).parse().unwrap().content.unwrap();
+        assert_eq!(content.matches("<caption>Rates</caption>").count(), 2, "{}", content);
+        assert!(!content.contains(DATA_TABLE_ATTR));
+    }
+
+    #[test]
+    fn test_layout_tables_unwrapped() {
+        let html = r#"<html><body>
+            <table width="100%"><tr><td class="page">
+                <table><tbody><tr><td>
+                    <p>A long paragraph about rivers, with commas, and enough text to pass.</p>
+                    <p>Another paragraph about mountains, with commas, and enough text to pass.</p>
+                    <table><tr><td>A caption-like line, with <i>commas</i>, under the story.</td></tr></table>
+                </td></tr></tbody></table>
+            </td></tr></table>
+            <table><caption>Flow</caption><tr><td>Nile</td></tr></table>
+        </body></html>"#;
+
+        let mut parser = create_parser(html);
+        let content = parser.parse().unwrap().content.unwrap();
+        assert!(content.contains("rivers") && content.contains("mountains"));
+        assert!(content.contains("<p>A caption-like line, with <i>commas</i>, under the story.</p>"), "{}", content);
+        assert!(!content.contains("<td"));
+
+        // Data tables stay tables; parsing leaves the source document as it was, so prepare it here
+        parser.prep_document();
+        let caption_selector = Selector::parse("table > caption").unwrap();
+        assert!(parser.document.select(&caption_selector).next().is_some());
+        let page_selector = Selector::parse("div.page").unwrap();
+        assert!(parser.document.select(&page_selector).next().is_some());
+    }
+
+    #[cfg(feature = "hydration")]
+    #[test]
+    fn test_hydration_payload() {
+        let body: String = ["rivers", "mountains", "forests", "deserts"].iter()
+            .map(|topic| format!("<p>A long paragraph about {}, with commas, and enough text to pass.</p>", topic))
+            .collect();
+        let payload = serde_json::json!({"props": {"pageProps": {"article": {"title": "Rivers", "html": body}}}});
+        let html = format!(
+            r#"<html><head><title>Rivers</title></head><body><div id="__next"></div><script id="__NEXT_DATA__" type="application/json">{}</script></body></html>"#,
+            payload
+        );
+        let options = |extract_hydration| Some(ReadabilityOptions {
+            char_threshold: CharThreshold::Chars(100),
+            extract_hydration,
+            ..Default::default()
+        });
+
+        let article = Readability::new(&html, options(true)).unwrap().parse().unwrap();
+        let content = article.content.unwrap();
+        assert_eq!(content.matches("<p>A long paragraph about").count(), 4);
+        assert!(!content.contains("__NEXT_DATA__"));
+    }
+}
@@ -0,0 +1,140 @@
+//! Memory accounting and a parsing engine for batch workloads
+//!
+//! Sizes are estimates from the parsed tree and the strings the parser
+//! produces, not allocator measurements, but they scale with real usage
+//! closely enough to enforce a budget.
+
+use std::collections::HashMap;
+
+use scraper::{Html, Node};
+
+#[cfg(feature = "accounting")]
+use crate::metrics::MetricsRegistry;
+use crate::{Article, Readability, ReadabilityError, ReadabilityOptions};
+
+/// Estimate the heap held by a parsed document
+pub(crate) fn estimate_document_bytes(document: &Html) -> usize {
+    document
+        .tree
+        .nodes()
+        .map(|node| {
+            std::mem::size_of::<Node>()
+                + match node.value() {
+                    Node::Text(text) => text.len(),
+                    Node::Comment(comment) => comment.len(),
+                    Node::Element(element) => element
+                        .attrs()
+                        .map(|(name, value)| name.len() + value.len() + 2 * std::mem::size_of::<usize>())
+                        .sum(),
+                    _ => 0,
+                }
+        })
+        .sum()
+}
+
+/// Estimate the heap held by an extracted article's strings
+pub(crate) fn estimate_article_bytes(article: &Article) -> usize {
+    [&article.content, &article.text_content, &article.title, &article.excerpt, &article.byline]
+        .iter()
+        .map(|field| field.as_ref().map_or(0, String::capacity))
+        .sum()
+}
+
+/// Resource usage of the last parse
+#[cfg(feature = "accounting")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseMetrics {
+    /// Estimated peak heap usage, in bytes
+    pub peak_bytes: usize,
+}
+
+/// Parses many documents with one set of options
+///
+/// Every parse builds its own document tree; only the metadata map's
+/// allocation is carried from one parse to the next.
+pub struct ReadabilityEngine {
+    options: ReadabilityOptions,
+    metadata: HashMap<String, String>,
+    #[cfg(feature = "accounting")]
+    metrics: ParseMetrics,
+    #[cfg(feature = "accounting")]
+    registry: MetricsRegistry,
+}
+
+impl ReadabilityEngine {
+    /// Create an engine that parses with `options`
+    pub fn new(options: ReadabilityOptions) -> Self {
+        Self {
+            options,
+            metadata: HashMap::new(),
+            #[cfg(feature = "accounting")]
+            metrics: ParseMetrics::default(),
+            #[cfg(feature = "accounting")]
+            registry: MetricsRegistry::default(),
+        }
+    }
+
+    /// Extract the article from `html`, resolving relative URLs against `base_uri`
+    pub fn parse(&mut self, html: &str, base_uri: Option<&str>) -> Result<Article, ReadabilityError> {
+        #[cfg(feature = "accounting")]
+        let started = std::time::Instant::now();
+        let mut parser = Readability::new(html, Some(self.options.clone()))?;
+        parser.base_uri = base_uri.map(str::to_string);
+        parser.metadata = std::mem::take(&mut self.metadata);
+
+        let result = parser.parse_result();
+
+        #[cfg(feature = "accounting")]
+        {
+            self.metrics = ParseMetrics { peak_bytes: parser.peak_bytes };
+            self.registry.record(html.len(), started.elapsed(), &result);
+        }
+        self.metadata = std::mem::take(&mut parser.metadata);
+        self.metadata.clear();
+        result
+    }
+
+    /// Resource usage of the most recent `parse`
+    #[cfg(feature = "accounting")]
+    pub fn metrics(&self) -> ParseMetrics {
+        self.metrics
+    }
+
+    /// Totals over every `parse` of this engine, renderable for Prometheus
+    #[cfg(feature = "accounting")]
+    pub fn registry(&self) -> &MetricsRegistry {
+        &self.registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_grows_with_document() {
+        let small = Html::parse_document("<p>Hi</p>");
+        let large = Html::parse_document(&"<p class=\"para\">Some paragraph text.</p>".repeat(100));
+        assert!(estimate_document_bytes(&large) > 10 * estimate_document_bytes(&small));
+    }
+
+    #[test]
+    fn test_engine_reuse() {
+        let mut engine = ReadabilityEngine::new(ReadabilityOptions::default());
+        for topic in ["rivers", "mountains"] {
+            let html = format!("<html><body><article><p>A long paragraph about {topic}, with commas, and enough text to pass.</p></article></body></html>");
+            let article = engine.parse(&html, None).unwrap();
+            assert!(article.text_content.unwrap().contains(topic));
+        }
+    }
+
+    #[cfg(feature = "accounting")]
+    #[test]
+    fn test_engine_metrics() {
+        let mut engine = ReadabilityEngine::new(ReadabilityOptions::default());
+        let html = "<html><body><article><p>A long paragraph, with commas, and enough text to pass.</p></article></body></html>";
+        engine.parse(html, None).unwrap();
+        assert!(engine.metrics().peak_bytes > html.len());
+        assert_eq!(engine.registry().documents_parsed(), 1);
+    }
+}
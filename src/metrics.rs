@@ -0,0 +1,134 @@
+//! Aggregate parse metrics in the Prometheus text exposition format
+//!
+//! `ReadabilityEngine` records every parse into a `MetricsRegistry`; a
+//! server can serve `MetricsRegistry::render` from its `/metrics` endpoint.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::{Article, ReadabilityError};
+
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+const SIZE_BUCKETS: &[f64] = &[1e3, 1e4, 1e5, 1e6, 1e7];
+
+/// Cumulative histogram with fixed upper bounds
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self { bounds, counts: vec![0; bounds.len()], sum: 0.0, count: 0 }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(&mut self.counts) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, self.count);
+    }
+}
+
+/// Counters and histograms over many parses
+#[derive(Debug, Clone)]
+pub struct MetricsRegistry {
+    documents_parsed: u64,
+    failures: BTreeMap<&'static str, u64>,
+    latency: Histogram,
+    input_bytes: Histogram,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self {
+            documents_parsed: 0,
+            failures: BTreeMap::new(),
+            latency: Histogram::new(LATENCY_BUCKETS),
+            input_bytes: Histogram::new(SIZE_BUCKETS),
+        }
+    }
+}
+
+impl MetricsRegistry {
+    /// Record one parse of an `input_bytes`-long document
+    pub fn record(&mut self, input_bytes: usize, elapsed: Duration, result: &Result<Article, ReadabilityError>) {
+        self.documents_parsed += 1;
+        if let Err(error) = result {
+            *self.failures.entry(failure_reason(error)).or_default() += 1;
+        }
+        self.latency.observe(elapsed.as_secs_f64());
+        self.input_bytes.observe(input_bytes as f64);
+    }
+
+    /// Number of documents parsed
+    pub fn documents_parsed(&self) -> u64 {
+        self.documents_parsed
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP readability_documents_parsed_total Documents parsed.");
+        let _ = writeln!(out, "# TYPE readability_documents_parsed_total counter");
+        let _ = writeln!(out, "readability_documents_parsed_total {}", self.documents_parsed);
+        let _ = writeln!(out, "# HELP readability_parse_failures_total Failed parses by reason.");
+        let _ = writeln!(out, "# TYPE readability_parse_failures_total counter");
+        for (reason, count) in &self.failures {
+            let _ = writeln!(out, "readability_parse_failures_total{{reason=\"{}\"}} {}", reason, count);
+        }
+        self.latency.render(&mut out, "readability_parse_duration_seconds", "Parse latency in seconds.");
+        self.input_bytes.render(&mut out, "readability_input_bytes", "Size of parsed documents in bytes.");
+        out
+    }
+}
+
+/// Label value for the failure class of `error`
+fn failure_reason(error: &ReadabilityError) -> &'static str {
+    match error {
+        ReadabilityError::InvalidHtml => "invalid_html",
+        ReadabilityError::NoContent => "no_content",
+        ReadabilityError::ParseError(_) => "parse_error",
+        ReadabilityError::NotAnArticle(_) => "not_an_article",
+        ReadabilityError::MemoryLimitExceeded { .. } => "memory_limit_exceeded",
+        ReadabilityError::UnsupportedInput(_) => "unsupported_input",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let mut registry = MetricsRegistry::default();
+        registry.record(2_000, Duration::from_millis(20), &Ok(Article::default()));
+        registry.record(500, Duration::from_millis(3), &Err(ReadabilityError::NoContent));
+
+        let text = registry.render();
+        assert!(text.contains("readability_documents_parsed_total 2\n"));
+        assert!(text.contains("readability_parse_failures_total{reason=\"no_content\"} 1\n"));
+        assert!(text.contains("readability_parse_duration_seconds_bucket{le=\"0.005\"} 1\n"));
+        assert!(text.contains("readability_parse_duration_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("readability_input_bytes_bucket{le=\"1000\"} 1\n"));
+        assert!(text.contains("readability_input_bytes_sum 2500\n"));
+    }
+}
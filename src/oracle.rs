@@ -0,0 +1,249 @@
+//! Differential testing against Mozilla's Readability.js
+//!
+//! `JsOracle` runs a pinned `@mozilla/readability` (with `jsdom`) under Node
+//! on the same input, and `compare` diffs its titles, bylines and text with
+//! ours, so parity regressions show up in tests. Requires the `oracle`
+//! feature, plus `npm install @mozilla/readability@<READABILITY_JS_VERSION> jsdom`
+//! in the oracle's module directory.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::corpus::text_similarity;
+use crate::utils::normalize_whitespace;
+use crate::{Article, Readability, ReadabilityOptions};
+
+/// The `@mozilla/readability` release results are compared against
+pub const READABILITY_JS_VERSION: &str = "0.6.0";
+
+/// Reads HTML from stdin and prints Readability.js's article as JSON
+const ORACLE_SCRIPT: &str = r#"
+const [expected, url] = process.argv.slice(-2);
+const version = require("@mozilla/readability/package.json").version;
+if (version !== expected) {
+    console.error(`@mozilla/readability ${version} is installed, ${expected} is required`);
+    process.exit(2);
+}
+const { JSDOM } = require("jsdom");
+const { Readability } = require("@mozilla/readability");
+let html = "";
+process.stdin.setEncoding("utf8");
+process.stdin.on("data", chunk => html += chunk);
+process.stdin.on("end", () => {
+    const article = new Readability(new JSDOM(html, { url }).window.document).parse();
+    process.stdout.write(JSON.stringify(article && {
+        title: article.title,
+        byline: article.byline,
+        excerpt: article.excerpt,
+        siteName: article.siteName,
+        lang: article.lang,
+        textContent: article.textContent,
+    }));
+});
+"#;
+
+/// Errors from running the oracle
+#[derive(Error, Debug)]
+pub enum OracleError {
+    #[error("Could not run node: {0}")]
+    NodeUnavailable(#[from] io::Error),
+    #[error("Readability.js failed: {0}")]
+    ScriptFailed(String),
+    #[error("Unexpected Readability.js output: {0}")]
+    InvalidOutput(#[from] serde_json::Error),
+}
+
+/// The fields of a Readability.js article that are compared
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleArticle {
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    pub excerpt: Option<String>,
+    pub site_name: Option<String>,
+    pub lang: Option<String>,
+    pub text_content: Option<String>,
+}
+
+/// A Node process running the pinned Readability.js
+#[derive(Debug, Clone)]
+pub struct JsOracle {
+    node: PathBuf,
+    module_dir: Option<PathBuf>,
+}
+
+impl Default for JsOracle {
+    fn default() -> Self {
+        Self { node: PathBuf::from("node"), module_dir: None }
+    }
+}
+
+impl JsOracle {
+    /// An oracle using `node` from `PATH` and modules from the current
+    /// directory, or from `READABILITY_ORACLE_DIR` if set
+    pub fn new() -> Self {
+        Self {
+            module_dir: std::env::var_os("READABILITY_ORACLE_DIR").map(PathBuf::from),
+            ..Default::default()
+        }
+    }
+
+    /// Use a specific Node binary
+    pub fn with_node(mut self, node: impl Into<PathBuf>) -> Self {
+        self.node = node.into();
+        self
+    }
+
+    /// Resolve `@mozilla/readability` and `jsdom` from `dir`'s `node_modules`
+    pub fn with_module_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.module_dir = Some(dir.into());
+        self
+    }
+
+    /// Whether Node and the pinned modules are installed, so tests can skip otherwise
+    pub fn is_available(&self) -> bool {
+        self.extract("<html><body><p>probe</p></body></html>", "http://localhost/").is_ok()
+    }
+
+    /// Extract `html` with Readability.js; `None` if it found no article
+    pub fn extract(&self, html: &str, base_uri: &str) -> Result<Option<OracleArticle>, OracleError> {
+        let mut command = Command::new(&self.node);
+        command.arg("-e").arg(ORACLE_SCRIPT).arg(READABILITY_JS_VERSION).arg(base_uri);
+        if let Some(dir) = &self.module_dir {
+            command.current_dir(dir);
+        }
+        let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(html.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(OracleError::ScriptFailed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+}
+
+/// A metadata field whose value differs between the implementations
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDiff {
+    pub field: &'static str,
+    pub rust: Option<String>,
+    pub js: Option<String>,
+}
+
+/// Structured differences between our article and Readability.js's
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParityDiff {
+    /// Whether each side extracted an article
+    pub rust_extracted: bool,
+    pub js_extracted: bool,
+    /// Metadata fields that differ
+    pub fields: Vec<FieldDiff>,
+    /// Word-overlap similarity (0.0–1.0) of the two texts
+    pub text_similarity: f64,
+}
+
+impl ParityDiff {
+    /// Whether both sides agree on extraction and metadata, and their texts
+    /// are at least `min_similarity` alike
+    pub fn is_parity(&self, min_similarity: f64) -> bool {
+        self.rust_extracted == self.js_extracted && self.fields.is_empty() && self.text_similarity >= min_similarity
+    }
+}
+
+/// Diff an article against the oracle's article for the same input
+pub fn compare(article: Option<&Article>, oracle: Option<&OracleArticle>) -> ParityDiff {
+    let normalize = |value: Option<&String>| {
+        value.map(|value| normalize_whitespace(value).trim().to_string()).filter(|value| !value.is_empty())
+    };
+    let pairs = [
+        ("title", article.and_then(|a| a.title.as_ref()), oracle.and_then(|o| o.title.as_ref())),
+        ("byline", article.and_then(|a| a.byline.as_ref()), oracle.and_then(|o| o.byline.as_ref())),
+        ("excerpt", article.and_then(|a| a.excerpt.as_ref()), oracle.and_then(|o| o.excerpt.as_ref())),
+        ("siteName", article.and_then(|a| a.site_name.as_ref()), oracle.and_then(|o| o.site_name.as_ref())),
+        ("lang", article.and_then(|a| a.lang.as_ref()), oracle.and_then(|o| o.lang.as_ref())),
+    ];
+    let fields = pairs
+        .into_iter()
+        .map(|(field, rust, js)| FieldDiff { field, rust: normalize(rust), js: normalize(js) })
+        .filter(|diff| diff.rust != diff.js)
+        .collect();
+
+    let rust_text = article.and_then(|a| a.text_content.as_deref()).unwrap_or("");
+    let js_text = oracle.and_then(|o| o.text_content.as_deref()).unwrap_or("");
+    ParityDiff {
+        rust_extracted: article.is_some(),
+        js_extracted: oracle.is_some(),
+        fields,
+        text_similarity: text_similarity(rust_text, js_text),
+    }
+}
+
+/// Extract `html` with both implementations and diff the results
+pub fn compare_with_js(
+    oracle: &JsOracle,
+    html: &str,
+    base_uri: &str,
+    options: Option<ReadabilityOptions>,
+) -> Result<ParityDiff, OracleError> {
+    let js = oracle.extract(html, base_uri)?;
+    let rust = Readability::new_with_base_uri(html, base_uri, options).ok().and_then(|mut parser| parser.parse());
+    Ok(compare(rust.as_ref(), js.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let article = Article {
+            title: Some("Rivers".to_string()),
+            byline: Some("Jane  Doe".to_string()),
+            text_content: Some("The river runs to the sea".to_string()),
+            ..Default::default()
+        };
+        let oracle = OracleArticle {
+            title: Some("Rivers of the World".to_string()),
+            byline: Some("Jane Doe".to_string()),
+            text_content: Some("The river runs to the sea".to_string()),
+            ..Default::default()
+        };
+
+        let diff = compare(Some(&article), Some(&oracle));
+        assert_eq!(diff.fields, vec![FieldDiff {
+            field: "title",
+            rust: Some("Rivers".to_string()),
+            js: Some("Rivers of the World".to_string()),
+        }]);
+        assert_eq!(diff.text_similarity, 1.0);
+        assert!(!diff.is_parity(0.9));
+
+        let diff = compare(None, Some(&oracle));
+        assert!(!diff.rust_extracted && diff.js_extracted);
+    }
+
+    #[test]
+    fn test_oracle_parity() {
+        // Runs only where the pinned Readability.js is installed
+        let oracle = JsOracle::new();
+        if !oracle.is_available() {
+            println!("Readability.js {} not installed - skipping oracle test", READABILITY_JS_VERSION);
+            return;
+        }
+
+        let html = r#"<html><head><title>Rivers</title></head><body><article>
+            <h1>Rivers</h1>
+            <p>A long paragraph about rivers, with commas, and enough text to pass the threshold of both parsers.</p>
+            <p>Another paragraph about mountains, with commas, and enough text to pass the threshold of both parsers.</p>
+        </article></body></html>"#;
+        let diff = compare_with_js(&oracle, html, "http://fakehost/test/page.html", None).unwrap();
+        assert!(diff.rust_extracted && diff.js_extracted);
+        assert!(diff.text_similarity >= 0.9, "{:?}", diff);
+    }
+}
@@ -0,0 +1,272 @@
+//! PDF-to-HTML converter output
+//!
+//! Tools such as pdf2htmlEX and `pdftohtml -c` place every word or line in
+//! an absolutely positioned element, so the generic algorithm sees hundreds
+//! of one-line "paragraphs". Such documents are detected here, and their
+//! text flow can be rebuilt by ordering the fragments by position.
+
+use std::collections::HashMap;
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::utils::{escape_html, get_inner_text};
+
+/// Generator meta values of PDF-to-HTML converters
+const PDF_GENERATORS: &[&str] = &["pdf2htmlex", "pdftohtml"];
+
+/// Fewest positioned fragments for a document to count as converted
+const MIN_FRAGMENTS: usize = 20;
+
+/// Share of the body text that positioned fragments must hold
+const MIN_FRAGMENT_TEXT_RATIO: f64 = 0.8;
+
+/// Vertical distance (px) within which fragments belong to the same line
+const LINE_TOLERANCE: f64 = 3.0;
+
+/// A positioned piece of text on a page
+struct Fragment {
+    text: String,
+    page: usize,
+    top: f64,
+    left: f64,
+}
+
+/// A line of text; `top` is unknown for converters that don't inline positions
+struct Line {
+    page: usize,
+    top: Option<f64>,
+    text: String,
+}
+
+/// Whether `document` looks like the output of a PDF-to-HTML converter
+pub(crate) fn is_pdf_conversion(document: &Html) -> bool {
+    let generator_selector = Selector::parse("meta[name='generator' i]").unwrap();
+    let generated = document
+        .select(&generator_selector)
+        .filter_map(|meta| meta.value().attr("content"))
+        .any(|generator| PDF_GENERATORS.iter().any(|name| generator.to_lowercase().contains(name)));
+    if generated || document.select(&Selector::parse("#page-container .pf").unwrap()).next().is_some() {
+        return true;
+    }
+
+    let visible_chars = |text: &str| text.chars().filter(|c| !c.is_whitespace()).count();
+    let fragments = positioned_fragments(document);
+    let fragment_chars: usize = fragments.iter().map(|fragment| visible_chars(&fragment.text)).sum();
+    let body_chars = document
+        .select(&Selector::parse("body").unwrap())
+        .next()
+        .map_or(0, |body| visible_chars(&get_inner_text(&body, true)));
+    fragments.len() >= MIN_FRAGMENTS && fragment_chars as f64 >= body_chars as f64 * MIN_FRAGMENT_TEXT_RATIO
+}
+
+/// Rebuild the text flow of converter output as a document of paragraphs,
+/// keeping the original `<head>` for metadata
+pub(crate) fn reconstruct(document: &Html) -> Html {
+    let mut lines = pdf2htmlex_lines(document);
+    if lines.is_empty() {
+        lines = position_lines(positioned_fragments(document));
+    }
+
+    let head = document
+        .select(&Selector::parse("head").unwrap())
+        .next()
+        .map(|head| head.inner_html())
+        .unwrap_or_default();
+    let paragraphs: String = paragraphs(&lines)
+        .iter()
+        .map(|paragraph| format!("<p>{}</p>", escape_html(paragraph)))
+        .collect();
+    Html::parse_document(&format!("<html><head>{}</head><body><article>{}</article></body></html>", head, paragraphs))
+}
+
+/// Elements with an inline absolute position and text of their own, in
+/// document order
+fn positioned_fragments(document: &Html) -> Vec<Fragment> {
+    let body_selector = Selector::parse("body [style]").unwrap();
+    let mut pages: HashMap<ego_tree::NodeId, usize> = HashMap::new();
+    let mut fragments = Vec::new();
+    for element in document.select(&body_selector) {
+        let style = style_map(&element);
+        if style.get("position").map(String::as_str) != Some("absolute") {
+            continue;
+        }
+        let (Some(top), Some(left)) = (pixels(&style, "top"), pixels(&style, "left")) else {
+            continue;
+        };
+        // Nested positioned elements are covered by their positioned ancestor
+        let positioned_ancestor = element.ancestors().filter_map(ElementRef::wrap).any(|ancestor| {
+            style_map(&ancestor).get("position").map(String::as_str) == Some("absolute")
+        });
+        let text = get_inner_text(&element, true);
+        if positioned_ancestor || text.is_empty() {
+            continue;
+        }
+        // Converters put the fragments of each page in one container
+        let parent = element.parent().map_or(element.id(), |parent| parent.id());
+        let next_page = pages.len();
+        let page = *pages.entry(parent).or_insert(next_page);
+        fragments.push(Fragment { text, page, top, left });
+    }
+    fragments
+}
+
+/// Text lines of pdf2htmlEX output (`.pf` pages of `.t` lines), which are
+/// already in reading order
+fn pdf2htmlex_lines(document: &Html) -> Vec<Line> {
+    let page_selector = Selector::parse(".pf").unwrap();
+    let line_selector = Selector::parse(".t").unwrap();
+    document
+        .select(&page_selector)
+        .enumerate()
+        .flat_map(|(page, element)| {
+            element
+                .select(&line_selector)
+                .map(|line| get_inner_text(&line, true))
+                .filter(|text| !text.is_empty())
+                .map(move |text| Line { page, top: None, text })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Group fragments into lines by page and vertical position, each read left to right
+fn position_lines(mut fragments: Vec<Fragment>) -> Vec<Line> {
+    fragments.sort_by(|a, b| a.page.cmp(&b.page).then(a.top.total_cmp(&b.top)).then(a.left.total_cmp(&b.left)));
+    let mut lines: Vec<(usize, f64, Vec<Fragment>)> = Vec::new();
+    for fragment in fragments {
+        match lines.last_mut() {
+            Some((page, top, line)) if *page == fragment.page && (fragment.top - *top).abs() <= LINE_TOLERANCE => {
+                line.push(fragment)
+            }
+            _ => lines.push((fragment.page, fragment.top, vec![fragment])),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(page, top, mut line)| {
+            line.sort_by(|a, b| a.left.total_cmp(&b.left));
+            let text = line.iter().map(|fragment| fragment.text.as_str()).collect::<Vec<_>>().join(" ");
+            Line { page, top: Some(top), text }
+        })
+        .collect()
+}
+
+/// Join lines into paragraphs, breaking at page boundaries, wide vertical
+/// gaps and short lines that end a sentence, and rejoining hyphenated words
+fn paragraphs(lines: &[Line]) -> Vec<String> {
+    let median = |mut values: Vec<f64>| -> Option<f64> {
+        values.sort_by(f64::total_cmp);
+        values.get(values.len().saturating_sub(1) / 2).copied()
+    };
+    let gaps: Vec<f64> = lines
+        .windows(2)
+        .filter(|pair| pair[0].page == pair[1].page)
+        .filter_map(|pair| Some(pair[1].top? - pair[0].top?))
+        .collect();
+    let line_gap = median(gaps);
+    let line_length = median(lines.iter().map(|line| line.text.chars().count() as f64).collect()).unwrap_or(0.0);
+
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut previous: Option<&Line> = None;
+    for line in lines {
+        let breaks = previous.is_none_or(|previous| {
+            let wide_gap = match (previous.top, line.top, line_gap) {
+                (Some(previous_top), Some(top), Some(gap)) => top - previous_top > gap * 1.5,
+                _ => false,
+            };
+            let ends_sentence = previous.text.ends_with(['.', '!', '?', ':', '"', '”'])
+                && (previous.text.chars().count() as f64) < line_length * 0.7;
+            previous.page != line.page || wide_gap || ends_sentence
+        });
+        match paragraphs.last_mut() {
+            Some(paragraph) if !breaks => {
+                let hyphenated = paragraph.strip_suffix('-').filter(|stem| stem.ends_with(char::is_alphabetic));
+                if let Some(stem_len) = hyphenated.map(str::len) {
+                    paragraph.truncate(stem_len);
+                } else {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(&line.text);
+            }
+            _ => paragraphs.push(line.text.clone()),
+        }
+        previous = Some(line);
+    }
+    paragraphs
+}
+
+/// Lowercased inline style properties of `element`
+fn style_map(element: &ElementRef) -> HashMap<String, String> {
+    element
+        .value()
+        .attr("style")
+        .unwrap_or("")
+        .split(';')
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            Some((name.trim().to_lowercase(), value.trim().to_lowercase()))
+        })
+        .collect()
+}
+
+fn pixels(style: &HashMap<String, String>, name: &str) -> Option<f64> {
+    style.get(name)?.trim_end_matches("px").trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pdftohtml_page(words: &[(&str, f64, f64)]) -> String {
+        let spans: String = words
+            .iter()
+            .map(|(word, top, left)| {
+                format!(r#"<span style="position:absolute;top:{}px;left:{}px">{}</span>"#, top, left, word)
+            })
+            .collect();
+        format!(r#"<html><head><title>Report</title></head><body><div style="position:relative">{}</div></body></html>"#, spans)
+    }
+
+    #[test]
+    fn test_detect_and_reconstruct() {
+        // Two lines and a paragraph, listed out of order
+        let mut words = Vec::new();
+        for (index, word) in "The quick brown fox jumps over the la-".split(' ').enumerate() {
+            words.push((word, 100.0, 50.0 + index as f64 * 40.0));
+        }
+        for (index, word) in "zy dog and runs away into the woods.".split(' ').enumerate() {
+            words.push((word, 118.0, 50.0 + index as f64 * 40.0));
+        }
+        for (index, word) in "Then it rains all day long here.".split(' ').enumerate() {
+            words.push((word, 160.0, 50.0 + index as f64 * 40.0));
+        }
+        words.reverse();
+        let document = Html::parse_document(&pdftohtml_page(&words));
+        assert!(is_pdf_conversion(&document));
+
+        let rebuilt = reconstruct(&document);
+        let texts: Vec<String> = rebuilt.select(&Selector::parse("p").unwrap()).map(|p| get_inner_text(&p, true)).collect();
+        assert_eq!(
+            texts,
+            vec!["The quick brown fox jumps over the lazy dog and runs away into the woods.", "Then it rains all day long here."]
+        );
+        assert!(rebuilt.select(&Selector::parse("head title").unwrap()).next().is_some());
+
+        let ordinary = Html::parse_document(r#"<html><body><p>An ordinary page.</p><div style="position:absolute;top:0;left:0">Badge</div></body></html>"#);
+        assert!(!is_pdf_conversion(&ordinary));
+    }
+
+    #[test]
+    fn test_pdf2htmlex_lines() {
+        let document = Html::parse_document(r#"<html><head><meta name="generator" content="pdf2htmlEX"></head><body>
+            <div id="page-container"><div class="pf"><div class="pc">
+                <div class="t">A first line of text that goes on for a while and</div>
+                <div class="t">ends here.</div>
+                <div class="t">A new paragraph starts on this line.</div>
+            </div></div></div>
+        </body></html>"#);
+        assert!(is_pdf_conversion(&document));
+        let rebuilt = reconstruct(&document);
+        assert_eq!(rebuilt.select(&Selector::parse("p").unwrap()).count(), 2);
+    }
+}
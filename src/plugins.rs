@@ -0,0 +1,292 @@
+//! Third-party cleaners run on the article content
+//!
+//! A [`CleanerPlugin`] removes or rewrites parts of the extracted content,
+//! typically for one site (a "Medium cleaner"), and can be published as its
+//! own crate. Plugins are added to [`ReadabilityOptions::cleaners`] directly,
+//! or registered by name with [`register_plugin`] so that tools like the CLI
+//! can enable them at runtime (`--plugin NAME`). They run after site profiles
+//! and before the generic cleanup, so classes and ids are still present.
+//!
+//! [`ReadabilityOptions::cleaners`]: crate::ReadabilityOptions::cleaners
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use scraper::{Html, Selector};
+
+use crate::ExtractionProfile;
+
+/// The article content being cleaned, as a mutable fragment
+pub struct ArticleDom {
+    fragment: Html,
+}
+
+impl ArticleDom {
+    pub fn new(html: &str) -> Self {
+        Self { fragment: Html::parse_fragment(html) }
+    }
+
+    /// The parsed content; select elements from its `root_element()`
+    pub fn html(&self) -> &Html {
+        &self.fragment
+    }
+
+    /// The parsed content, for direct tree edits
+    pub fn html_mut(&mut self) -> &mut Html {
+        &mut self.fragment
+    }
+
+    /// Replace the whole content, for string-based rewrites
+    pub fn set_html(&mut self, html: &str) {
+        self.fragment = Html::parse_fragment(html);
+    }
+
+    /// Remove the elements matching `selector` with their contents,
+    /// returning how many were removed
+    pub fn remove(&mut self, selector: &Selector) -> usize {
+        let ids = self.matching(selector);
+        for &id in &ids {
+            if let Some(mut node) = self.fragment.tree.get_mut(id) {
+                node.detach();
+            }
+        }
+        ids.len()
+    }
+
+    /// Replace the elements matching `selector` with their children,
+    /// returning how many were unwrapped
+    pub fn unwrap(&mut self, selector: &Selector) -> usize {
+        let ids = self.matching(selector);
+        for &id in &ids {
+            let children: Vec<_> = match self.fragment.tree.get(id) {
+                Some(node) => node.children().map(|child| child.id()).collect(),
+                None => continue,
+            };
+            if let Some(mut node) = self.fragment.tree.get_mut(id) {
+                for child in children {
+                    node.insert_id_before(child);
+                }
+                node.detach();
+            }
+        }
+        ids.len()
+    }
+
+    /// Serialize the content
+    pub fn to_html(&self) -> String {
+        self.fragment.root_element().inner_html()
+    }
+
+    /// Ids of the matching elements, outermost first
+    fn matching(&self, selector: &Selector) -> Vec<ego_tree::NodeId> {
+        let root = self.fragment.root_element();
+        root.select(selector).filter(|element| element.id() != root.id()).map(|element| element.id()).collect()
+    }
+}
+
+/// What a cleaner knows about the page beyond its content
+#[derive(Clone, Copy)]
+pub struct Context<'a> {
+    /// Base URI of the page, if known
+    pub url: Option<&'a str>,
+    pub profile: ExtractionProfile,
+    pub title: Option<&'a str>,
+    pub site_name: Option<&'a str>,
+    /// The whole prepared document
+    pub document: &'a Html,
+}
+
+impl<'a> Context<'a> {
+    /// A context with nothing known but the document, e.g. for testing a plugin
+    pub fn new(document: &'a Html) -> Self {
+        Self { url: None, profile: ExtractionProfile::Generic, title: None, site_name: None, document }
+    }
+
+    /// Host of `url`, lowercased, for plugins that only apply to some sites
+    pub fn host(&self) -> Option<String> {
+        let rest = self.url?.split_once("://")?.1;
+        let authority = rest.split(['/', '?', '#']).next()?;
+        let host = authority.rsplit('@').next()?.split(':').next()?;
+        (!host.is_empty()).then(|| host.to_lowercase())
+    }
+}
+
+/// A cleaner for the article content
+pub trait CleanerPlugin: Send + Sync {
+    /// Unique name, used to enable the plugin by name
+    fn name(&self) -> &str;
+
+    /// Plugins with higher priority run first; equal ones in registration order
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn clean(&self, dom: &mut ArticleDom, context: &Context);
+}
+
+/// The cleaners to run, in priority order
+#[derive(Clone, Default)]
+pub struct CleanerRegistry {
+    plugins: Vec<Arc<dyn CleanerPlugin>>,
+}
+
+impl CleanerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a plugin, replacing one of the same name
+    pub fn register(&mut self, plugin: impl CleanerPlugin + 'static) {
+        self.register_shared(Arc::new(plugin));
+    }
+
+    /// Add a plugin shared with other registries, e.g. one from [`find_plugin`]
+    pub fn register_shared(&mut self, plugin: Arc<dyn CleanerPlugin>) {
+        self.plugins.retain(|existing| existing.name() != plugin.name());
+        self.plugins.push(plugin);
+        // Stable, so registration order breaks ties
+        self.plugins.sort_by_key(|plugin| std::cmp::Reverse(plugin.priority()));
+    }
+
+    /// Builder form of [`register`](Self::register)
+    pub fn with(mut self, plugin: impl CleanerPlugin + 'static) -> Self {
+        self.register(plugin);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Plugin names in run order
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    /// Run every plugin over `html`
+    pub fn clean(&self, html: &str, context: &Context) -> String {
+        let mut dom = ArticleDom::new(html);
+        for plugin in &self.plugins {
+            plugin.clean(&mut dom, context);
+        }
+        dom.to_html()
+    }
+}
+
+impl fmt::Debug for CleanerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// Removes images, pictures and figures, for text-only output (`strip-images`)
+pub struct StripImages;
+
+impl CleanerPlugin for StripImages {
+    fn name(&self) -> &str {
+        "strip-images"
+    }
+
+    fn clean(&self, dom: &mut ArticleDom, _context: &Context) {
+        dom.remove(&Selector::parse("figure, picture, img, svg").unwrap());
+    }
+}
+
+/// Replaces links with their text (`strip-links`)
+pub struct StripLinks;
+
+impl CleanerPlugin for StripLinks {
+    fn name(&self) -> &str {
+        "strip-links"
+    }
+
+    fn clean(&self, dom: &mut ArticleDom, _context: &Context) {
+        dom.unwrap(&Selector::parse("a").unwrap());
+    }
+}
+
+type PluginCatalog = RwLock<HashMap<String, Arc<dyn CleanerPlugin>>>;
+
+/// Global catalog of plugins enabled by name, seeded with the built-in ones
+static PLUGINS: OnceLock<PluginCatalog> = OnceLock::new();
+
+fn plugin_catalog() -> &'static PluginCatalog {
+    PLUGINS.get_or_init(|| {
+        let builtin: [Arc<dyn CleanerPlugin>; 2] = [Arc::new(StripImages), Arc::new(StripLinks)];
+        RwLock::new(builtin.into_iter().map(|plugin| (plugin.name().to_string(), plugin)).collect())
+    })
+}
+
+/// Make `plugin` available by name, replacing one of the same name
+pub fn register_plugin(plugin: impl CleanerPlugin + 'static) {
+    let mut catalog = plugin_catalog().write().unwrap_or_else(|e| e.into_inner());
+    catalog.insert(plugin.name().to_string(), Arc::new(plugin));
+}
+
+/// The registered plugin called `name`
+pub fn find_plugin(name: &str) -> Option<Arc<dyn CleanerPlugin>> {
+    plugin_catalog().read().unwrap_or_else(|e| e.into_inner()).get(name).cloned()
+}
+
+/// Names of the registered plugins, sorted
+pub fn plugin_names() -> Vec<String> {
+    let mut names: Vec<String> = plugin_catalog().read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drops `.promo` blocks on example.com only
+    struct PromoCleaner;
+
+    impl CleanerPlugin for PromoCleaner {
+        fn name(&self) -> &str {
+            "promo"
+        }
+
+        fn priority(&self) -> i32 {
+            10
+        }
+
+        fn clean(&self, dom: &mut ArticleDom, context: &Context) {
+            if context.host().as_deref() == Some("example.com") {
+                dom.remove(&Selector::parse(".promo").unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_article_dom() {
+        let mut dom = ArticleDom::new(r#"<p>Read <a href="/x">the <b>docs</b></a>.</p><figure><img src="a.png"></figure>"#);
+        assert_eq!(dom.unwrap(&Selector::parse("a").unwrap()), 1);
+        assert_eq!(dom.remove(&Selector::parse("figure").unwrap()), 1);
+        assert_eq!(dom.to_html(), "<p>Read the <b>docs</b>.</p>");
+    }
+
+    #[test]
+    fn test_registry() {
+        let document = Html::parse_document("<html><body></body></html>");
+        let mut registry = CleanerRegistry::new().with(StripLinks).with(PromoCleaner);
+        registry.register(StripLinks);
+        assert_eq!(registry.names(), vec!["promo", "strip-links"]);
+        assert_eq!(format!("{:?}", registry), r#"["promo", "strip-links"]"#);
+
+        let html = r#"<p><a href="/a">Text</a></p><div class="promo">Buy</div>"#;
+        let context = Context { url: Some("https://Example.com:443/post"), ..Context::new(&document) };
+        assert_eq!(registry.clean(html, &context), "<p>Text</p>");
+        assert_eq!(registry.clean(html, &Context::new(&document)), r#"<p>Text</p><div class="promo">Buy</div>"#);
+    }
+
+    #[test]
+    fn test_plugin_catalog() {
+        assert!(find_plugin("strip-images").is_some());
+        register_plugin(PromoCleaner);
+        assert_eq!(find_plugin("promo").map(|plugin| plugin.priority()), Some(10));
+        assert!(plugin_names().contains(&"strip-links".to_string()));
+        assert!(find_plugin("medium").is_none());
+    }
+}
@@ -0,0 +1,145 @@
+//! Print-stylesheet hints
+//!
+//! Sites hide navigation, ads and share buttons from printouts and reveal
+//! print-only details, so their print view is an author-annotated reader
+//! view. `PrintHints` collects those signals for the cleaning passes.
+
+use scraper::{ElementRef, Html, Selector};
+
+/// Classes that hide an element from printouts
+const NO_PRINT_CLASSES: &[&str] = &["noprint", "no-print", "d-print-none", "hidden-print", "print-hidden", "screen-only"];
+
+/// Classes that show an element only in printouts
+const PRINT_ONLY_CLASSES: &[&str] = &["printonly", "print-only", "visible-print", "visible-print-block", "d-print-block"];
+
+/// Elements the document's print view hides or reveals
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PrintHints {
+    /// Selectors of print rules with `display: none`
+    hidden: Vec<Selector>,
+}
+
+impl PrintHints {
+    /// Collect the print hints of `document`, or `None` if it has none
+    pub(crate) fn from_document(document: &Html) -> Option<Self> {
+        let style_selector = Selector::parse("style").unwrap();
+        let mut hidden = Vec::new();
+        let mut has_print_css = false;
+        for style in document.select(&style_selector) {
+            let css: String = style.text().collect();
+            let blocks = if style.value().attr("media").is_some_and(is_print_media) {
+                vec![css]
+            } else {
+                print_media_blocks(&css)
+            };
+            for block in blocks {
+                has_print_css = true;
+                hidden.extend(hidden_selectors(&block));
+            }
+        }
+
+        let link_selector = Selector::parse("link[rel~='stylesheet' i][media]").unwrap();
+        let links_print_css = document.select(&link_selector)
+            .any(|link| link.value().attr("media").is_some_and(is_print_media));
+        let class_selector = Selector::parse("[class]").unwrap();
+        let has_print_classes = document.select(&class_selector)
+            .any(|element| is_no_print(&element) || is_print_only(&element));
+
+        (has_print_css || links_print_css || has_print_classes).then_some(Self { hidden })
+    }
+
+    /// Whether the print view hides `element`
+    pub(crate) fn is_hidden(&self, element: &ElementRef) -> bool {
+        is_no_print(element) || self.hidden.iter().any(|selector| selector.matches(element))
+    }
+}
+
+/// Whether `element` is marked to appear only in printouts
+pub(crate) fn is_print_only(element: &ElementRef) -> bool {
+    element.value().classes().any(|class| PRINT_ONLY_CLASSES.contains(&class))
+}
+
+fn is_no_print(element: &ElementRef) -> bool {
+    element.value().classes().any(|class| NO_PRINT_CLASSES.contains(&class))
+}
+
+/// Whether a media query applies to print
+fn is_print_media(media: &str) -> bool {
+    let media = media.to_lowercase();
+    media.contains("print") && !media.contains("not print")
+}
+
+/// Bodies of the `@media print { ... }` blocks in a stylesheet
+fn print_media_blocks(css: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut rest = css;
+    while let Some(start) = rest.find("@media") {
+        let after = &rest[start + "@media".len()..];
+        let Some(open) = after.find('{') else { break };
+        let mut depth = 0;
+        let mut close = after.len();
+        for (index, c) in after[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = open + index;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if is_print_media(&after[..open]) {
+            blocks.push(after[open + 1..close].to_string());
+        }
+        rest = &after[(close + 1).min(after.len())..];
+    }
+    blocks
+}
+
+/// Selectors of the rules in `css` that set `display: none`
+fn hidden_selectors(css: &str) -> Vec<Selector> {
+    css.split('}')
+        .filter_map(|rule| rule.split_once('{'))
+        .filter(|(_, declarations)| {
+            let declarations: String = declarations.chars().filter(|c| !c.is_whitespace()).collect();
+            declarations.to_lowercase().contains("display:none")
+        })
+        .flat_map(|(selectors, _)| selectors.split(','))
+        .filter_map(|selector| Selector::parse(selector.trim()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_hints_from_media_rules() {
+        let document = Html::parse_document(r#"<html><head><style>
+            .lead { color: black }
+            @media print { .site-nav, #comments { display: none !important } .lead { color: gray } }
+        </style></head><body>
+            <nav class="site-nav">Menu</nav><div id="comments">c</div><p class="lead">p</p>
+        </body></html>"#);
+        let hints = PrintHints::from_document(&document).unwrap();
+        let select = |css: &str| document.select(&Selector::parse(css).unwrap()).next().unwrap();
+        assert!(hints.is_hidden(&select("nav")));
+        assert!(hints.is_hidden(&select("#comments")));
+        assert!(!hints.is_hidden(&select("p")));
+    }
+
+    #[test]
+    fn test_print_hints_from_classes() {
+        let document = Html::parse_document(r#"<body><div class="share noprint">s</div><p class="d-none d-print-block">url</p></body>"#);
+        let hints = PrintHints::from_document(&document).unwrap();
+        let select = |css: &str| document.select(&Selector::parse(css).unwrap()).next().unwrap();
+        assert!(hints.is_hidden(&select("div")));
+        assert!(is_print_only(&select("p")));
+
+        let plain = Html::parse_document("<body><p>Nothing for print</p></body>");
+        assert!(PrintHints::from_document(&plain).is_none());
+    }
+}
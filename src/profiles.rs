@@ -0,0 +1,302 @@
+//! Site-specific extraction profiles
+//!
+//! Profiles adjust cleanup for generators whose markup the generic
+//! algorithm handles poorly. They are chosen via
+//! `ReadabilityOptions::profile` or detected from the document.
+
+use ego_tree::NodeId;
+use scraper::node::Text;
+use scraper::{ElementRef, Html, Node, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dom;
+use crate::utils::{get_inner_text, is_data_table};
+
+/// A cleanup profile tuned for a family of sites
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ExtractionProfile {
+    /// The generic Readability algorithm
+    #[default]
+    Generic,
+    /// MediaWiki output such as Wikipedia
+    MediaWiki,
+    /// Documentation sites: keeps code, admonitions and permalink anchors
+    Docs,
+    /// HTML e-mail bodies such as newsletters: drops preheaders and tracking
+    /// pixels and unwraps layout tables before extraction
+    Email,
+}
+
+/// Generator meta values of documentation site generators
+pub(crate) const DOCS_GENERATORS: &[&str] =
+    &["docusaurus", "mkdocs", "sphinx", "rustdoc", "docfx", "vuepress", "gitbook"];
+
+impl ExtractionProfile {
+    /// Detect the profile from generator meta tags and body classes
+    pub fn detect(document: &Html) -> Self {
+        let generator_selector = Selector::parse("meta[name='generator' i]").unwrap();
+        let generator = document
+            .select(&generator_selector)
+            .filter_map(|meta| meta.value().attr("content"))
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if generator.contains("mediawiki") {
+            return Self::MediaWiki;
+        }
+        if DOCS_GENERATORS.iter().any(|name| generator.contains(name)) {
+            return Self::Docs;
+        }
+
+        let body_selector = Selector::parse("body.mediawiki").unwrap();
+        if document.select(&body_selector).next().is_some() {
+            return Self::MediaWiki;
+        }
+
+        // Set by e-mail templates to stop Apple Mail from restyling the message
+        let email_selector = Selector::parse("meta[name='x-apple-disable-message-reformatting' i]").unwrap();
+        if document.select(&email_selector).next().is_some() {
+            return Self::Email;
+        }
+
+        Self::Generic
+    }
+}
+
+/// Inline style declarations e-mail templates use to hide the inbox preview text
+const PREHEADER_STYLES: &[&str] = &[
+    "display:none", "mso-hide:all", "max-height:0", "max-height:0px", "opacity:0",
+    "font-size:0", "font-size:0px", "font-size:1px",
+];
+
+/// Whether `element` is the hidden preheader (inbox preview text) of an e-mail
+pub(crate) fn is_email_preheader(element: &ElementRef) -> bool {
+    if element.value().classes().any(|class| class.to_lowercase().contains("preheader")) {
+        return true;
+    }
+    style_declarations(element).iter().any(|declaration| PREHEADER_STYLES.contains(&declaration.as_str()))
+}
+
+/// Whether `image` is a tracking pixel: at most 1×1 by its attributes or inline style
+pub(crate) fn is_tracking_pixel(image: &ElementRef) -> bool {
+    let declarations = style_declarations(image);
+    let tiny = |name: &str| {
+        let styled = declarations.iter().find_map(|declaration| declaration.strip_prefix(name)?.strip_prefix(':'));
+        image.value().attr(name)
+            .or(styled)
+            .and_then(|size| size.trim().trim_end_matches("px").parse::<f64>().ok())
+            .is_some_and(|size| size <= 1.0)
+    };
+    tiny("width") && tiny("height")
+}
+
+/// Whether an e-mail `table` only lays out the message: anything without
+/// header cells or a caption, however large
+pub(crate) fn is_email_layout_table(table: &ElementRef) -> bool {
+    let header_selector = Selector::parse("th, thead, caption").unwrap();
+    !is_data_table(table) || table.select(&header_selector).next().is_none()
+}
+
+/// Lowercased inline style declarations of `element`, without whitespace or `!important`
+fn style_declarations(element: &ElementRef) -> Vec<String> {
+    element.value().attr("style").unwrap_or("")
+        .split(';')
+        .map(|declaration| {
+            let declaration: String = declaration.chars().filter(|c| !c.is_whitespace()).collect();
+            declaration.to_lowercase().trim_end_matches("!important").to_string()
+        })
+        .filter(|declaration| !declaration.is_empty())
+        .collect()
+}
+
+/// One label/value row of a MediaWiki infobox
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InfoboxField {
+    pub label: String,
+    pub value: String,
+}
+
+const MEDIAWIKI_CRUFT: &str = ".mw-editsection, table.infobox, .navbox, .vertical-navbox, .navbox-styles, \
+    #coordinates, .geo-default, .mw-jump-link, .noprint";
+
+/// Strip MediaWiki chrome from article HTML and rewrite reference superscripts
+/// into plain `[n]` links
+///
+/// Reference links stay local when the matching note is part of the content,
+/// and are resolved against `base_uri` otherwise.
+pub(crate) fn clean_mediawiki(content: &str, base_uri: Option<&str>) -> String {
+    let (mut fragment, root) = dom::parse_content(content);
+
+    let cruft_selector = Selector::parse(MEDIAWIKI_CRUFT).unwrap();
+    let cruft = dom::select_ids(&fragment, &cruft_selector);
+    dom::remove(&mut fragment, &cruft);
+
+    let reference_selector = Selector::parse("sup.reference").unwrap();
+    let references: Vec<(NodeId, String, Option<String>)> = match fragment.tree.get(root).and_then(ElementRef::wrap) {
+        Some(root) => root
+            .select(&reference_selector)
+            .map(|reference| {
+                let (label, href) = reference_target(&root, &reference, base_uri);
+                (reference.id(), label, href)
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    for (id, label, href) in references {
+        rewrite_reference(&mut fragment, id, &label, href.as_deref());
+    }
+
+    fragment.tree.get(root).and_then(ElementRef::wrap).map(|root| root.inner_html()).unwrap_or_default()
+}
+
+/// The `[n]` label of a reference superscript and the href of its note
+fn reference_target(root: &ElementRef, reference: &ElementRef, base_uri: Option<&str>) -> (String, Option<String>) {
+    let label = get_inner_text(reference, true).replace(' ', "");
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let Some(href) = reference.select(&link_selector).next().and_then(|a| a.value().attr("href")) else {
+        return (label, None);
+    };
+
+    let target_present = href
+        .strip_prefix('#')
+        .and_then(|id| Selector::parse(&format!("[id='{}']", id.replace('\'', "\\'"))).ok())
+        .is_some_and(|selector| root.select(&selector).next().is_some());
+    let href = match base_uri {
+        Some(base) if href.starts_with('#') && !target_present => {
+            format!("{}{}", base.split('#').next().unwrap_or(base), href)
+        }
+        _ => href.to_string(),
+    };
+    (label, Some(href))
+}
+
+/// Replace the markup of reference superscript `id` with a plain link to
+/// `href` reading `label`, keeping only its id
+fn rewrite_reference(document: &mut Html, id: NodeId, label: &str, href: Option<&str>) {
+    let Some(reference) = document.tree.get(id) else {
+        return;
+    };
+    let children: Vec<NodeId> = reference.children().map(|child| child.id()).collect();
+    let reference_id = ElementRef::wrap(reference).and_then(|reference| reference.value().id()).map(str::to_string);
+    dom::remove(document, &children);
+    dom::clear_attrs(document, id);
+    if let Some(reference_id) = reference_id {
+        dom::set_attr(document, id, "id", &reference_id);
+    }
+
+    let text = document.tree.orphan(Node::Text(Text { text: label.into() })).id();
+    let content = match href {
+        Some(href) => {
+            let link = document.tree.orphan(Node::Element(dom::new_element("a"))).id();
+            dom::set_attr(document, link, "href", href);
+            if let Some(mut link) = document.tree.get_mut(link) {
+                link.append_id(text);
+            }
+            link
+        }
+        None => text,
+    };
+    if let Some(mut reference) = document.tree.get_mut(id) {
+        reference.append_id(content);
+    }
+}
+
+/// Collect the label/value rows of the first infobox in the document
+pub(crate) fn mediawiki_infobox(document: &Html) -> Vec<InfoboxField> {
+    let infobox_selector = Selector::parse("table.infobox").unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    let Some(infobox) = document.select(&infobox_selector).next() else {
+        return Vec::new();
+    };
+
+    infobox
+        .select(&row_selector)
+        .filter_map(|row| {
+            let cells: Vec<ElementRef> = row.children().filter_map(ElementRef::wrap).collect();
+            let label = cells.iter().find(|cell| cell.value().name() == "th")?;
+            let value = cells.iter().find(|cell| cell.value().name() == "td")?;
+            let label = get_inner_text(label, true);
+            let value = get_inner_text(value, true);
+            (!label.is_empty() && !value.is_empty()).then_some(InfoboxField { label, value })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_profile() {
+        let document = Html::parse_document(r#"<html><head><meta name="generator" content="MediaWiki 1.42"></head><body></body></html>"#);
+        assert_eq!(ExtractionProfile::detect(&document), ExtractionProfile::MediaWiki);
+
+        let document = Html::parse_document(r#"<html><head><meta name="generator" content="mkdocs-1.5.3, mkdocs-material-9.5"></head></html>"#);
+        assert_eq!(ExtractionProfile::detect(&document), ExtractionProfile::Docs);
+
+        let document = Html::parse_document("<html><body><p>Plain</p></body></html>");
+        assert_eq!(ExtractionProfile::detect(&document), ExtractionProfile::Generic);
+    }
+
+    #[test]
+    fn test_email_helpers() {
+        let document = Html::parse_document(r#"<html><body>
+            <span class="preheader">Preview</span>
+            <div style="display: none !important; max-height: 0">Preview</div>
+            <div style="max-height: 0.5em">Shown</div>
+            <img src="open.gif" width="1" height="1">
+            <img src="spacer.gif" style="width:1px; height:1px">
+            <img src="logo.png" width="120" height="1">
+            <table><tr><td>Left</td><td>Right</td></tr></table>
+            <table><tr><th>Plan</th><th>Price</th></tr><tr><td>Basic</td><td>$5</td></tr></table>
+        </body></html>"#);
+        let select = |selector: &str| document.select(&Selector::parse(selector).unwrap()).collect::<Vec<_>>();
+
+        let preheaders: Vec<bool> = select("span, div").iter().map(is_email_preheader).collect();
+        assert_eq!(preheaders, vec![true, true, false]);
+        let pixels: Vec<bool> = select("img").iter().map(is_tracking_pixel).collect();
+        assert_eq!(pixels, vec![true, true, false]);
+        let layout: Vec<bool> = select("table").iter().map(is_email_layout_table).collect();
+        assert_eq!(layout, vec![true, false]);
+
+        let document = Html::parse_document(r#"<html><head><meta name="x-apple-disable-message-reformatting"></head></html>"#);
+        assert_eq!(ExtractionProfile::detect(&document), ExtractionProfile::Email);
+    }
+
+    #[test]
+    fn test_clean_mediawiki() {
+        let content = r##"<h2>History<span class="mw-editsection">[<a href="/edit">edit</a>]</span></h2>
+            <p>Founded in 1900.<sup class="reference" id="cite_ref-1"><a href="#cite_note-1"><span>[</span>1<span>]</span></a></sup></p>
+            <span id="coordinates">51°N 0°W</span>
+            <table class="navbox"><tr><td>Other cities</td></tr></table>"##;
+
+        let cleaned = clean_mediawiki(content, Some("https://en.wikipedia.org/wiki/Town"));
+        assert!(!cleaned.contains("edit"));
+        assert!(!cleaned.contains("51°N"));
+        assert!(!cleaned.contains("Other cities"));
+        assert!(cleaned.contains(r##"<sup id="cite_ref-1"><a href="https://en.wikipedia.org/wiki/Town#cite_note-1">[1]</a></sup>"##));
+
+        // Notes in the content keep local links; cruft inside a reference goes with it
+        let content = r##"<p>Rebuilt in 1950.<sup class="reference"><a href="#cite_note-2">[2]<span class="noprint">?</span></a></sup></p>
+            <ol class="references"><li id="cite_note-2">Town records</li></ol>"##;
+        let cleaned = clean_mediawiki(content, Some("https://en.wikipedia.org/wiki/Town"));
+        assert!(cleaned.contains(r##"1950.<sup><a href="#cite_note-2">[2]</a></sup></p>"##), "{}", cleaned);
+    }
+
+    #[test]
+    fn test_mediawiki_infobox() {
+        let document = Html::parse_document(r#"<table class="infobox">
+            <tr><th colspan="2">Town</th></tr>
+            <tr><th>Country</th><td>England</td></tr>
+            <tr><th>Population</th><td>12,000</td></tr>
+        </table>"#);
+
+        let fields = mediawiki_infobox(&document);
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0], InfoboxField { label: "Country".to_string(), value: "England".to_string() });
+    }
+}
@@ -1,8 +1,12 @@
 //! Regular expressions used throughout the Readability parser
 
-use regex::Regex;
 use std::sync::OnceLock;
 
+#[cfg(all(feature = "regex", not(feature = "regex-lite")))]
+pub use regex::Regex;
+#[cfg(any(not(feature = "regex"), feature = "regex-lite"))]
+pub use regex_lite::Regex;
+
 /// Regular expressions for identifying content patterns
 pub struct ReadabilityRegexps {
     pub unlikely_candidates: Regex,
@@ -18,14 +22,24 @@ pub struct ReadabilityRegexps {
     pub next_link: Regex,
     pub prev_link: Regex,
     pub tokenize: Regex,
-    pub whitespace: Regex,
-    pub has_content: Regex,
     pub hash_url: Regex,
     pub b64_data_url: Regex,
-    pub commas: Regex,
     pub json_ld_article_types: Regex,
     pub ad_words: Regex,
     pub loading_words: Regex,
+    pub correction_notice: Regex,
+    pub correction_class: Regex,
+    pub recipe_list: Regex,
+    pub live_update: Regex,
+    pub slide: Regex,
+    pub admonition: Regex,
+    pub price: Regex,
+    pub purchase_button: Regex,
+    pub ad_container: Regex,
+    pub recommendation_widget: Regex,
+    pub floating_bar: Regex,
+    pub author_bio: Regex,
+    pub blank_line: Regex,
 }
 
 impl ReadabilityRegexps {
@@ -83,14 +97,6 @@ impl ReadabilityRegexps {
                 r"\W+"
             ).unwrap(),
             
-            whitespace: Regex::new(
-                r"^\s*$"
-            ).unwrap(),
-            
-            has_content: Regex::new(
-                r"\S$"
-            ).unwrap(),
-            
             hash_url: Regex::new(
                 r"^#.+"
             ).unwrap(),
@@ -99,10 +105,6 @@ impl ReadabilityRegexps {
                 r"(?i)^data:\s*([^\s;,]+)\s*;\s*base64\s*,"
             ).unwrap(),
             
-            commas: Regex::new(
-                r"\u{002C}|\u{060C}|\u{FE50}|\u{FE10}|\u{FE11}|\u{2E41}|\u{2E34}|\u{2E32}|\u{FF0C}"
-            ).unwrap(),
-            
             json_ld_article_types: Regex::new(
                 r"^Article|AdvertiserContentArticle|NewsArticle|AnalysisNewsArticle|AskPublicNewsArticle|BackgroundNewsArticle|OpinionNewsArticle|ReportageNewsArticle|ReviewNewsArticle|Report|SatiricalArticle|ScholarlyArticle|MedicalScholarlyArticle|SocialMediaPosting|BlogPosting|LiveBlogPosting|DiscussionForumPosting|TechArticle|APIReference$"
             ).unwrap(),
@@ -114,6 +116,58 @@ impl ReadabilityRegexps {
             loading_words: Regex::new(
                 r"(?i)^((loading|正在加载|Загрузка|chargement|cargando)(…|\.\.\.)?)$"
             ).unwrap(),
+            
+            correction_notice: Regex::new(
+                r"(?i)^\W*(corrections?|clarifications?|editor['’]?s note|erratum|berichtigung|korrektur|rectificatif|fe de erratas)\b"
+            ).unwrap(),
+            
+            correction_class: Regex::new(
+                r"(?i)correction|clarification|erratum|errata"
+            ).unwrap(),
+            
+            recipe_list: Regex::new(
+                r"(?i)ingredient|instruction|direction|recipe-step|recipe-method"
+            ).unwrap(),
+            
+            slide: Regex::new(
+                r"(?i)\b(slide|slide-\d+|slideshow-(item|slide)|gallery-(item|slide)|carousel-item|listicle-item)\b"
+            ).unwrap(),
+            
+            admonition: Regex::new(
+                r"(?i)\b(admonition|callout|theme-admonition|alert|note|tip|hint|info|warning|caution|danger|important)\b"
+            ).unwrap(),
+            
+            price: Regex::new(
+                r"(?i)[$€£¥₹]\s?\d[\d.,]*|\d[\d.,]*\s?(€|(usd|eur|gbp|chf)\b)"
+            ).unwrap(),
+            
+            purchase_button: Regex::new(
+                r"(?i)add to (cart|bag|basket|wishlist)|buy (it )?now|in den warenkorb|ajouter au panier|añadir al carrito|checkout|out of stock|in stock"
+            ).unwrap(),
+            
+            ad_container: Regex::new(
+                r"(?i)^((inline|in-?article|native)[-_]?)?(ad|ads|advert|advertisement|adsbygoogle|adslot|adunit|dfp|gpt-ad)([-_]?(slot|unit|container|wrapper|block|placeholder|inline|mid|\d+))*$|^div-gpt-ad|^google_ads_|^taboola"
+            ).unwrap(),
+            
+            recommendation_widget: Regex::new(
+                r"(?i)outbrain|taboola|revcontent|zergnet"
+            ).unwrap(),
+            
+            floating_bar: Regex::new(
+                r"(?i)^(sticky|floating|fixed)[-_]?(share|social|sharebar|bar|cta|banner|footer)|^(share|social)[-_]?(bar|rail|float|sticky)$|^float[-_]?share"
+            ).unwrap(),
+            
+            author_bio: Regex::new(
+                r"(?i)^(author|contributor|writer)[-_]?(bio|box|card|info|about|details)$|^about[-_]?(the[-_]?)?author$|^(bio|biography|author-?bio-?box)$"
+            ).unwrap(),
+            
+            live_update: Regex::new(
+                r"(?i)\blive-?blog-?(entry|post|update|item)\b|\blive-?(update|entry|post)\b|\btimeline-?(entry|item)\b"
+            ).unwrap(),
+            
+            blank_line: Regex::new(
+                r"\n[ \t\r]*\n"
+            ).unwrap(),
         }
     }
 }
@@ -132,6 +186,14 @@ pub fn is_unlikely_candidate(text: &str) -> bool {
     regexps.unlikely_candidates.is_match(text) && !regexps.ok_maybe_its_candidate.is_match(text)
 }
 
+/// Check an element's class and id as [`is_unlikely_candidate`] and
+/// [`has_positive_indicators`] would on `"{class} {id}"`, without joining them
+pub fn is_unlikely_class_and_id(class: &str, id: &str) -> bool {
+    let regexps = get_regexps();
+    let matches = |regex: &Regex| regex.is_match(class) || regex.is_match(id);
+    matches(&regexps.unlikely_candidates) && !matches(&regexps.ok_maybe_its_candidate) && !matches(&regexps.positive)
+}
+
 /// Check if a string has positive content indicators
 pub fn has_positive_indicators(text: &str) -> bool {
     get_regexps().positive.is_match(text)
@@ -156,12 +218,12 @@ pub fn is_video_url(url: &str) -> bool {
 
 /// Check if text is only whitespace
 pub fn is_whitespace(text: &str) -> bool {
-    get_regexps().whitespace.is_match(text)
+    text.bytes().all(|b| b.is_ascii_whitespace()) || text.chars().all(char::is_whitespace)
 }
 
 /// Check if text has content (non-whitespace)
 pub fn has_content(text: &str) -> bool {
-    get_regexps().has_content.is_match(text)
+    text.chars().next_back().is_some_and(|ch| !ch.is_whitespace())
 }
 
 /// Check if a string contains ad-related words
@@ -174,6 +236,66 @@ pub fn contains_loading_words(text: &str) -> bool {
     get_regexps().loading_words.is_match(text)
 }
 
+/// Check if text opens with a correction or clarification notice
+pub fn is_correction_notice(text: &str) -> bool {
+    get_regexps().correction_notice.is_match(text)
+}
+
+/// Check if a class/id string marks a correction notice container
+pub fn has_correction_class(text: &str) -> bool {
+    get_regexps().correction_class.is_match(text)
+}
+
+/// Check if a class/id string marks a recipe ingredient or instruction list
+pub fn is_recipe_list(text: &str) -> bool {
+    get_regexps().recipe_list.is_match(text)
+}
+
+/// Check if a class/id string marks a single slideshow or listicle slide
+pub fn is_slide(text: &str) -> bool {
+    get_regexps().slide.is_match(text)
+}
+
+/// Check if a class string marks a single live-blog update
+pub fn is_live_update(text: &str) -> bool {
+    get_regexps().live_update.is_match(text)
+}
+
+/// Check if a class string marks a documentation admonition (note, warning, ...)
+pub fn is_admonition(text: &str) -> bool {
+    get_regexps().admonition.is_match(text)
+}
+
+/// Count price-like amounts (`$19.99`, `24,90 €`) in text
+pub fn count_prices(text: &str) -> usize {
+    get_regexps().price.find_iter(text).count()
+}
+
+/// Check if a button or link label is a shop action ("Add to cart", ...)
+pub fn is_purchase_button(text: &str) -> bool {
+    get_regexps().purchase_button.is_match(text)
+}
+
+/// Check if a single class name or id marks an ad slot ("inline-ad", "ad-slot-3", "div-gpt-ad-...")
+pub fn is_ad_container(name: &str) -> bool {
+    get_regexps().ad_container.is_match(name)
+}
+
+/// Check if a class/id string names a recommendation widget (Outbrain, Taboola, ...)
+pub fn is_recommendation_widget(text: &str) -> bool {
+    get_regexps().recommendation_widget.is_match(text)
+}
+
+/// Check if a single class or id names a floating share bar or sticky banner
+pub fn is_floating_bar(name: &str) -> bool {
+    get_regexps().floating_bar.is_match(name)
+}
+
+/// Check if a single class or id names an author bio box
+pub fn is_author_bio(name: &str) -> bool {
+    get_regexps().author_bio.is_match(name)
+}
+
 /// Check if a string matches extraneous content patterns
 pub fn is_extraneous_content(text: &str) -> bool {
     get_regexps().extraneous.is_match(text)
@@ -220,13 +342,101 @@ pub fn normalize_whitespace(text: &str) -> String {
 }
 
 /// Tokenize text
+///
+/// See [`crate::analysis::tokenize_for_lang`] for a language-aware variant.
 pub fn tokenize_text(text: &str) -> Vec<&str> {
     get_regexps().tokenize.split(text).filter(|s| !s.is_empty()).collect()
 }
 
+/// Commas other than U+002C: Arabic, small, presentation forms, reversed,
+/// raised, turned and fullwidth
+const UNICODE_COMMAS: [&[u8]; 8] = [
+    "\u{060C}".as_bytes(),
+    "\u{FE50}".as_bytes(),
+    "\u{FE10}".as_bytes(),
+    "\u{FE11}".as_bytes(),
+    "\u{2E41}".as_bytes(),
+    "\u{2E34}".as_bytes(),
+    "\u{2E32}".as_bytes(),
+    "\u{FF0C}".as_bytes(),
+];
+
 /// Count commas in text
+///
+/// Scans bytes with `memchr`: ASCII commas directly, the other commas at the
+/// lead bytes of their UTF-8 encodings (0xD8, 0xE2, 0xEF).
 pub fn count_commas(text: &str) -> usize {
-    get_regexps().commas.find_iter(text).count()
+    let bytes = text.as_bytes();
+    let ascii = memchr::memchr_iter(b',', bytes).count();
+    let unicode = memchr::memchr3_iter(0xD8, 0xE2, 0xEF, bytes)
+        .filter(|&at| UNICODE_COMMAS.iter().any(|comma| bytes[at..].starts_with(comma)))
+        .count();
+    ascii + unicode
+}
+
+/// Clause separators used in place of commas by Chinese and Japanese
+const CJK_SEPARATORS: &[char] = &['、', '､', '；', '：'];
+
+/// Sentence terminators used by Chinese and Japanese
+const CJK_TERMINATORS: &[char] = &['。', '｡', '！', '？'];
+
+/// Comma-equivalent separators a language writes between clauses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommaStyle {
+    /// Commas only
+    Commas,
+    /// Commas, ideographic separators and sentence terminators (Chinese, Japanese)
+    Cjk,
+    /// Commas and the spaces between phrases (Thai, Lao, Khmer, Burmese)
+    Spaces,
+}
+
+impl CommaStyle {
+    /// Style for a language tag such as `zh-Hans` or `th_TH`
+    pub fn for_lang(lang: Option<&str>) -> Self {
+        let primary = lang.and_then(|lang| lang.split(['-', '_']).next()).unwrap_or_default();
+        let is = |code: &str| primary.eq_ignore_ascii_case(code);
+        if is("zh") || is("ja") {
+            CommaStyle::Cjk
+        } else if is("th") || is("lo") || is("km") || is("my") {
+            CommaStyle::Spaces
+        } else {
+            CommaStyle::Commas
+        }
+    }
+
+    /// Count the separators in `text`, leaving out phrase-separating spaces
+    ///
+    /// Spaces are counted per gap between words, so callers measuring text in
+    /// pieces add them once for the whole.
+    pub fn count_marks(self, text: &str) -> usize {
+        let commas = count_commas(text);
+        match self {
+            CommaStyle::Cjk => {
+                commas
+                    + text
+                        .chars()
+                        .filter(|ch| CJK_SEPARATORS.contains(ch) || CJK_TERMINATORS.contains(ch))
+                        .count()
+            }
+            CommaStyle::Commas | CommaStyle::Spaces => commas,
+        }
+    }
+}
+
+/// Count comma-equivalent separators in text for a language
+///
+/// Languages that use commas get the plain [`count_commas`] result. Chinese
+/// and Japanese also count ideographic separators and sentence terminators,
+/// and scripts written without commas (Thai, Lao, Khmer, Burmese) count the
+/// spaces that separate their phrases.
+pub fn count_commas_for_lang(text: &str, lang: Option<&str>) -> usize {
+    let style = CommaStyle::for_lang(lang);
+    let marks = style.count_marks(text);
+    match style {
+        CommaStyle::Spaces => marks + text.split_whitespace().count().saturating_sub(1),
+        _ => marks,
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +451,21 @@ mod tests {
         assert!(!is_unlikely_candidate("article-body"));
     }
 
+    #[test]
+    fn test_unlikely_class_and_id() {
+        for (class, id) in [("sidebar", ""), ("", "comments"), ("sidebar", "main"), ("related", "post-1"), ("menu", "nav"), ("", "")] {
+            let joined = format!("{} {}", class, id);
+            assert_eq!(
+                is_unlikely_class_and_id(class, id),
+                is_unlikely_candidate(&joined) && !has_positive_indicators(&joined),
+                "{:?}",
+                joined
+            );
+        }
+        assert!(is_unlikely_class_and_id("share social", "footer"));
+        assert!(!is_unlikely_class_and_id("comment", "article-body"));
+    }
+
     #[test]
     fn test_positive_indicators() {
         assert!(has_positive_indicators("article-content"));
@@ -260,8 +485,24 @@ mod tests {
         assert!(is_whitespace("   \n\t  "));
         assert!(!is_whitespace("some text"));
         
+        assert!(is_whitespace(""));
+        assert!(is_whitespace("\u{a0}\u{3000} "));
+
         assert!(has_content("some text"));
         assert!(!has_content("   \n\t  "));
+        assert!(!has_content("text\u{a0}"));
+        assert!(!has_content(""));
+    }
+
+    #[test]
+    fn test_count_commas() {
+        assert_eq!(count_commas("one, two, three"), 2);
+        assert_eq!(count_commas("واحد، اثنان، ثلاثة"), 2);
+        assert_eq!(count_commas("一，二﹐三︐四︑五"), 4);
+        assert_eq!(count_commas("⹁⸴⸲"), 3);
+        // Other characters sharing the lead bytes don't count
+        assert_eq!(count_commas("ذ — ｡ …"), 0);
+        assert_eq!(count_commas(""), 0);
     }
 
 
@@ -272,4 +513,92 @@ mod tests {
         assert!(is_byline("written by John Doe"));
         assert!(!is_byline("random text"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_count_commas_for_lang() {
+        assert_eq!(count_commas_for_lang("one, two, three", Some("en")), 2);
+        assert_eq!(count_commas_for_lang("今日は、晴れです。明日は雨です。", Some("ja")), 3);
+        assert_eq!(count_commas_for_lang("今日は、晴れです。", Some("en")), 0);
+        assert_eq!(count_commas_for_lang("ภาษาไทย ไม่มี จุลภาค", Some("th")), 2);
+    }
+
+    #[test]
+    fn test_correction_notices() {
+        assert!(is_correction_notice("Correction: An earlier version misstated the date."));
+        assert!(is_correction_notice("Editor’s note: this story was updated."));
+        assert!(!is_correction_notice("The correction was made yesterday."));
+        assert!(has_correction_class("article-correction"));
+        assert!(!has_correction_class("article-body"));
+    }
+
+    #[test]
+    fn test_live_update_classes() {
+        assert!(is_live_update("liveblog-entry"));
+        assert!(is_live_update("post live-update"));
+        assert!(!is_live_update("live-updates-container"));
+        assert!(!is_live_update("article-body"));
+    }
+
+    #[test]
+    fn test_slide_classes() {
+        assert!(is_slide("gallery-item active"));
+        assert!(is_slide("slide"));
+        assert!(is_slide("slide-3"));
+        assert!(!is_slide("slideshow"));
+        assert!(!is_slide("landslide-report"));
+    }
+
+    #[test]
+    fn test_admonition_classes() {
+        assert!(is_admonition("admonition warning"));
+        assert!(is_admonition("theme-admonition alert alert--info"));
+        assert!(!is_admonition("notebook-nav"));
+    }
+
+    #[test]
+    fn test_shop_patterns() {
+        assert_eq!(count_prices("Was $24.99, now $19.99 or 18,50 € abroad"), 3);
+        assert_eq!(count_prices("Founded in 1998 with 12 people"), 0);
+        assert!(is_purchase_button("Add to Cart"));
+        assert!(!is_purchase_button("Read more"));
+    }
+
+    #[test]
+    fn test_ad_container() {
+        assert!(is_ad_container("inline-ad"));
+        assert!(is_ad_container("ad-slot-3"));
+        assert!(is_ad_container("div-gpt-ad-1234"));
+        assert!(is_ad_container("taboola-below-article"));
+        assert!(!is_ad_container("ad-free"));
+        assert!(!is_ad_container("header"));
+        assert!(!is_ad_container("load"));
+    }
+
+    #[test]
+    fn test_recommendation_widget() {
+        assert!(is_recommendation_widget("OUTBRAIN ob-widget"));
+        assert!(is_recommendation_widget("rc-widget revcontent-feed"));
+        assert!(is_recommendation_widget("zergnet-widget-123"));
+        assert!(!is_recommendation_widget("related-story"));
+    }
+
+    #[test]
+    fn test_floating_bar() {
+        assert!(is_floating_bar("sticky-share"));
+        assert!(is_floating_bar("floating_social_bar"));
+        assert!(is_floating_bar("share-bar"));
+        assert!(is_floating_bar("fixed-cta"));
+        assert!(!is_floating_bar("sticky"));
+        assert!(!is_floating_bar("share-count"));
+    }
+
+    #[test]
+    fn test_author_bio() {
+        assert!(is_author_bio("author-bio"));
+        assert!(is_author_bio("about_the_author"));
+        assert!(is_author_bio("contributor-info"));
+        assert!(is_author_bio("bio"));
+        assert!(!is_author_bio("author"));
+        assert!(!is_author_bio("biology-news"));
+    }
+}
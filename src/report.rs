@@ -0,0 +1,99 @@
+//! Diagnostics collected while parsing a document
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Something noteworthy the parser did to the content
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum ReportEntry {
+    /// Repeated runs of blocks (e.g. from an infinite-scroll snapshot) were removed
+    DuplicateBlocksCollapsed { removed: usize },
+    /// The content fell short of `char_threshold`, so extraction was retried
+    /// with `strip_unlikelys`, then `weight_classes`, then `clean_conditionally`
+    /// switched off, as Readability.js does
+    FlagsRelaxed { retries: usize },
+}
+
+/// A non-fatal data-quality issue with an extracted article
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum ParseWarning {
+    /// A byline candidate was ignored for being too long to be a name
+    BylineDiscarded { length: usize },
+    /// No paragraphs could be scored, so the content was picked by fallback selectors
+    FallbackContentSelection,
+    /// Images in the content have no usable `src`, only lazy-load attributes such as `data-src`
+    LazyImagesUnresolved { count: usize },
+    /// The published date reads differently as day/month and month/day, e.g. `03/04/2021`
+    AmbiguousDate { value: String },
+}
+
+/// Where the value of a metadata field was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MetadataSource {
+    /// The document `<title>`
+    TitleElement,
+    /// An `<h1>` heading
+    Heading,
+    /// An Open Graph `<meta property>` tag, e.g. `og:title` or `og:site_name`
+    OpenGraph,
+    /// A `<meta name>` tag, e.g. `author` or `description`
+    MetaTag,
+    /// An `application/ld+json` script
+    JsonLd,
+    /// A byline element such as `.byline` or `[rel=author]`
+    BylineElement,
+    /// The first paragraph of the content
+    FirstParagraph,
+    /// An attribute of `<html>` or `<body>`, e.g. `lang` or `dir`
+    HtmlAttribute,
+    /// The `datetime` attribute of a `<time>` element
+    TimeElement,
+}
+
+/// Source of each metadata field of the article, keyed by field name
+/// (`title`, `byline`, `published_time`, ...)
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MetadataProvenance {
+    pub fields: BTreeMap<String, MetadataSource>,
+}
+
+impl MetadataProvenance {
+    /// Record that `field` was taken from `source`, replacing any earlier source
+    pub fn record(&mut self, field: &str, source: MetadataSource) {
+        self.fields.insert(field.to_string(), source);
+    }
+
+    /// Where `field` came from, if it was set
+    pub fn get(&self, field: &str) -> Option<MetadataSource> {
+        self.fields.get(field).copied()
+    }
+}
+
+/// Report of a single parse, returned by `Readability::parse_with_report`
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParseReport {
+    pub entries: Vec<ReportEntry>,
+    /// Where each metadata field came from
+    pub provenance: MetadataProvenance,
+}
+
+impl ParseReport {
+    /// Record an entry
+    pub fn push(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Whether no entries were recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,140 @@
+//! robots.txt rules, as specified by RFC 9309
+
+/// The rules of one robots.txt that apply to one crawler
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RobotsRules {
+    /// (allow, path pattern) pairs
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Rules for the crawler whose product token is `agent` (e.g.
+    /// `readability-rust`), from the groups naming it or, failing that, `*`
+    pub(crate) fn parse(robots_txt: &str, agent: &str) -> Self {
+        let agent = agent.to_lowercase();
+        let mut specific = Vec::new();
+        let mut fallback = Vec::new();
+        let mut found_specific = false;
+        // Agents of the group being read, and whether its rules have started
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (field, value) = (field.trim().to_lowercase(), value.trim());
+            match field.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty disallow allows everything, as no rule does
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (field == "allow", value.to_string());
+                    if agents.contains(&agent) {
+                        found_specific = true;
+                        specific.push(rule.clone());
+                    }
+                    if agents.iter().any(|name| name == "*") {
+                        fallback.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self { rules: if found_specific { specific } else { fallback } }
+    }
+
+    /// Rules that disallow every path
+    pub(crate) fn disallow_all() -> Self {
+        Self { rules: vec![(false, "/".to_string())] }
+    }
+
+    /// Whether `path` (with its query) may be fetched: the longest matching
+    /// rule decides, and allow wins a tie
+    pub(crate) fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// Match a robots.txt path pattern, where `*` matches any run of characters
+/// and a trailing `$` anchors the end
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        // The last part of an anchored pattern must end the path
+        if anchored && index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(found) => rest = &rest[found + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robots_rules() {
+        let robots_txt = "# Example\n\
+            User-agent: *\n\
+            Disallow: /private/\n\
+            Allow: /private/press/\n\
+            Disallow: /*.pdf$\n\
+            \n\
+            User-agent: BadBot\n\
+            User-agent: readability-rust\n\
+            Disallow: /drafts\n\
+            Disallow:\n";
+        let rules = RobotsRules::parse(robots_txt, "readability-rust");
+        assert!(!rules.is_allowed("/drafts/one"));
+        assert!(rules.is_allowed("/private/page"));
+
+        let rules = RobotsRules::parse(robots_txt, "other-bot");
+        assert!(!rules.is_allowed("/private/page"));
+        assert!(rules.is_allowed("/private/press/release"));
+        assert!(!rules.is_allowed("/files/report.pdf"));
+        assert!(rules.is_allowed("/files/report.pdf?page=2"));
+        assert!(rules.is_allowed("/drafts/one"));
+
+        assert!(RobotsRules::parse("", "readability-rust").is_allowed("/"));
+        assert!(!RobotsRules::disallow_all().is_allowed("/index.html"));
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("/a", "/abc"));
+        assert!(matches("/a*c", "/abbc/d"));
+        assert!(matches("/a*c$", "/abbc"));
+        assert!(!matches("/a*c$", "/abbcd"));
+        assert!(matches("/$", "/"));
+        assert!(!matches("/$", "/a"));
+        assert!(!matches("/b", "/abc"));
+    }
+}
@@ -0,0 +1,170 @@
+//! XML sitemaps (sitemaps.org) and sitemap indexes
+//!
+//! [`entries`] fetches a sitemap, follows any sitemap index into its child
+//! sitemaps, and lists the page URLs. Requires the `fetch` feature.
+
+use std::collections::HashSet;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::{FetchError, Fetcher};
+use crate::utils::unescape_html_entities;
+
+/// Most sitemaps fetched for one call of [`entries`], against index loops
+/// and runaway indexes
+const MAX_SITEMAPS: usize = 1000;
+
+/// A page, or a child sitemap of an index
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SitemapEntry {
+    pub loc: String,
+    /// Last modification, in W3C datetime format (`2024-01-31`,
+    /// `2024-01-31T08:00:00+00:00`)
+    pub lastmod: Option<String>,
+}
+
+impl SitemapEntry {
+    /// Whether the entry changed on or after `since` (`YYYY-MM-DD`); entries
+    /// without a date are kept
+    pub fn modified_since(&self, since: &str) -> bool {
+        self.lastmod.as_deref().is_none_or(|lastmod| lastmod.get(..10).unwrap_or(lastmod) >= since)
+    }
+}
+
+/// The entries of one sitemap document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sitemap {
+    /// Pages of a `<urlset>`
+    pub urls: Vec<SitemapEntry>,
+    /// Child sitemaps of a `<sitemapindex>`
+    pub sitemaps: Vec<SitemapEntry>,
+}
+
+/// Parse a sitemap or sitemap index
+pub fn parse(xml: &str) -> Sitemap {
+    Sitemap { urls: blocks(xml, "url"), sitemaps: blocks(xml, "sitemap") }
+}
+
+/// Page entries of the sitemap at `url`, following sitemap indexes, with
+/// those last modified before `since` (`YYYY-MM-DD`) left out
+///
+/// Child sitemaps are fetched in order through `fetcher`, so its policy
+/// applies; a child sitemap that fails to fetch fails the whole call.
+pub fn entries(fetcher: &Fetcher, url: &str, since: Option<&str>) -> Result<Vec<SitemapEntry>, FetchError> {
+    let mut pending = vec![url.to_string()];
+    let mut seen = HashSet::new();
+    let mut pages = Vec::new();
+    while let Some(sitemap_url) = pending.pop() {
+        if !seen.insert(sitemap_url.clone()) || seen.len() > MAX_SITEMAPS {
+            continue;
+        }
+        let response = fetcher.fetch(&sitemap_url)?;
+        let sitemap = parse(&String::from_utf8_lossy(&response.body));
+        pages.extend(sitemap.urls.into_iter().filter(|entry| since.is_none_or(|since| entry.modified_since(since))));
+        // An index's lastmod dates its child sitemap, so an older child has no newer pages
+        let children = sitemap.sitemaps.into_iter().filter(|entry| since.is_none_or(|since| entry.modified_since(since)));
+        let children: Vec<String> = children.map(|entry| entry.loc).collect();
+        pending.extend(children.into_iter().rev());
+    }
+    Ok(pages)
+}
+
+/// Entries of the `<tag>` elements in `xml`
+fn blocks(xml: &str, tag: &str) -> Vec<SitemapEntry> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let mut entries = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let body = &rest[start + open.len()..];
+        let end = body.find(&close).unwrap_or(body.len());
+        if let Some(loc) = child_text(&body[..end], "loc").filter(|loc| !loc.is_empty()) {
+            entries.push(SitemapEntry { loc, lastmod: child_text(&body[..end], "lastmod") });
+        }
+        rest = &body[end..];
+    }
+    entries
+}
+
+/// Text of the first `<tag>` child, unwrapping CDATA and decoding entities
+fn child_text(xml: &str, tag: &str) -> Option<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    let text = xml[start..end].trim();
+    match text.strip_prefix("<![CDATA[").and_then(|text| text.strip_suffix("]]>")) {
+        Some(data) => Some(data.trim().to_string()),
+        None => Some(unescape_html_entities(text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::{FetchPolicy, HttpResponse, Transport};
+    use std::time::Duration;
+
+    /// Serves sitemaps by URL
+    struct Sitemaps(Vec<(&'static str, &'static str)>);
+
+    impl Transport for Sitemaps {
+        fn get(&self, url: &str, _headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+            let (_, body) = self.0.iter().find(|(path, _)| *path == url).ok_or(FetchError::Status(404))?;
+            Ok(HttpResponse { url: url.to_string(), status: 200, body: body.as_bytes().to_vec(), ..Default::default() })
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let sitemap = parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://example.com/?a=1&amp;b=2</loc><lastmod>2024-02-01</lastmod></url>
+              <url><loc><![CDATA[ https://example.com/about ]]></loc></url>
+              <url><lastmod>2024-02-01</lastmod></url>
+            </urlset>"#,
+        );
+        assert_eq!(
+            sitemap.urls,
+            vec![
+                SitemapEntry { loc: "https://example.com/?a=1&b=2".to_string(), lastmod: Some("2024-02-01".to_string()) },
+                SitemapEntry { loc: "https://example.com/about".to_string(), lastmod: None },
+            ]
+        );
+        assert!(sitemap.sitemaps.is_empty());
+
+        let entry = &sitemap.urls[0];
+        assert!(entry.modified_since("2024-01-01") && entry.modified_since("2024-02-01"));
+        assert!(!entry.modified_since("2024-02-02"));
+        assert!(sitemap.urls[1].modified_since("2030-01-01"));
+    }
+
+    #[test]
+    fn test_entries() {
+        let transport = Sitemaps(vec![
+            (
+                "https://example.com/sitemap.xml",
+                "<sitemapindex>
+                  <sitemap><loc>https://example.com/new.xml</loc><lastmod>2024-03-01T10:00:00+00:00</lastmod></sitemap>
+                  <sitemap><loc>https://example.com/old.xml</loc><lastmod>2019-01-01</lastmod></sitemap>
+                  <sitemap><loc>https://example.com/sitemap.xml</loc></sitemap>
+                </sitemapindex>",
+            ),
+            (
+                "https://example.com/new.xml",
+                "<urlset><url><loc>https://example.com/a</loc><lastmod>2024-03-01</lastmod></url>
+                  <url><loc>https://example.com/b</loc><lastmod>2023-06-01</lastmod></url></urlset>",
+            ),
+            ("https://example.com/old.xml", "<urlset><url><loc>https://example.com/c</loc></url></urlset>"),
+        ]);
+        let policy = FetchPolicy { min_host_interval: Duration::ZERO, ..Default::default() };
+        let fetcher = Fetcher::with_transport(policy, transport);
+
+        let locs = |entries: Vec<SitemapEntry>| entries.into_iter().map(|entry| entry.loc).collect::<Vec<_>>();
+        let all = entries(&fetcher, "https://example.com/sitemap.xml", None).unwrap();
+        assert_eq!(locs(all), vec!["https://example.com/a", "https://example.com/b", "https://example.com/c"]);
+        let recent = entries(&fetcher, "https://example.com/sitemap.xml", Some("2024-01-01")).unwrap();
+        assert_eq!(locs(recent), vec!["https://example.com/a"]);
+    }
+}
@@ -0,0 +1,244 @@
+//! Mapping from extracted blocks back to the original document
+//!
+//! Lets reader views sync scroll position and highlights with the source
+//! page. Node ids are the pre-order index of an element in the source
+//! document, so they are stable across parses of the same HTML.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use scraper::{ElementRef, Html};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::{ArticleBlocks, Block};
+use crate::utils::{css_path, get_inner_text, has_child_block_element, normalize_whitespace};
+
+/// Where one extracted block came from in the original document
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceMapEntry {
+    /// Index into `Article::blocks` (or `ArticleBlocks::from_html` of the content)
+    pub block: usize,
+    /// CSS selector path of the source element
+    pub path: String,
+    /// Pre-order index of the source element among all document elements
+    pub node_id: usize,
+}
+
+/// Match each block, in order, to the element under `root` it was extracted from
+///
+/// Blocks whose source can't be found (e.g. synthesized content) are skipped.
+pub(crate) fn build(document: &Html, root: ElementRef, blocks: &ArticleBlocks) -> Vec<SourceMapEntry> {
+    let node_ids: HashMap<_, usize> = document
+        .root_element()
+        .descendants()
+        .filter(|node| node.value().is_element())
+        .enumerate()
+        .map(|(index, node)| (node.id(), index))
+        .collect();
+    let elements: Vec<ElementRef> = root.descendants().filter_map(ElementRef::wrap).collect();
+
+    let mut entries = Vec::new();
+    let mut cursor = 0;
+    for (index, block) in blocks.iter().enumerate() {
+        let Some(offset) = elements[cursor..].iter().position(|element| matches_block(element, block)) else {
+            continue;
+        };
+        let element = elements[cursor + offset];
+        cursor += offset + 1;
+        entries.push(SourceMapEntry {
+            block: index,
+            path: css_path(&element),
+            node_id: node_ids.get(&element.id()).copied().unwrap_or_default(),
+        });
+    }
+    entries
+}
+
+fn matches_block(element: &ElementRef, block: &Block) -> bool {
+    let tag_name = element.value().name();
+    let text = || get_inner_text(element, true);
+    match block {
+        Block::Heading { level, text: heading } => tag_name == format!("h{}", level) && text() == *heading,
+        Block::Paragraph { text: paragraph } => {
+            (tag_name == "p" || !has_child_block_element(element)) && text() == *paragraph
+        }
+        Block::List { ordered, items } => {
+            tag_name == if *ordered { "ol" } else { "ul" }
+                && element
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .find(|li| li.value().name() == "li")
+                    .is_some_and(|li| items.first() == Some(&get_inner_text(&li, true)))
+        }
+        Block::Quote { text: quote } => tag_name == "blockquote" && text() == *quote,
+        Block::Code { text: code } => {
+            tag_name == "pre" && normalize_whitespace(&element.text().collect::<String>()) == normalize_whitespace(code)
+        }
+        Block::Image { src, .. } => tag_name == "img" && element.value().attr("src") == Some(src.as_str()),
+        Block::Table { .. } => tag_name == "table",
+    }
+}
+
+/// A piece of a text node in `Article::content` covered by a text range
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DomSpan {
+    /// CSS selector path of the text node's parent, relative to the content
+    /// root (empty for top-level text)
+    pub path: String,
+    /// Index of the text node among the parent's text-node children
+    pub text_node: usize,
+    /// Byte range within the text node
+    pub range: Range<usize>,
+}
+
+/// Translate a byte range of `text_content` into spans of text nodes in `content`
+///
+/// The text is rebuilt from `content` the way `get_inner_text` normalizes it;
+/// if `text_content` differs (e.g. it still holds text cleaned out of
+/// `content`), the selected text is looked up by value instead.
+pub(crate) fn locate(content: &str, text_content: &str, range: Range<usize>) -> Vec<DomSpan> {
+    let Some(needle) = text_content.get(range.clone()).filter(|needle| !needle.is_empty()) else {
+        return Vec::new();
+    };
+
+    let fragment = Html::parse_fragment(content);
+    let root = fragment.root_element();
+
+    // Normalized text, with the source (text node, byte offset) of every byte
+    let mut text = String::new();
+    let mut origins: Vec<Option<(usize, usize)>> = Vec::new();
+    let mut nodes = Vec::new();
+    for node in root.descendants() {
+        let Some(node_text) = node.value().as_text() else {
+            continue;
+        };
+        let node_index = nodes.len();
+        let parent = node.parent().and_then(ElementRef::wrap).unwrap_or(root);
+        let text_node = parent.children()
+            .filter(|child| child.value().is_text())
+            .position(|child| child.id() == node.id())
+            .unwrap_or(0);
+        nodes.push((parent, text_node, &**node_text));
+        // Text nodes are joined with a space before whitespace is collapsed
+        if node_index > 0 {
+            push_normalized(&mut text, &mut origins, ' ', None);
+        }
+        for (offset, ch) in node_text.char_indices() {
+            push_normalized(&mut text, &mut origins, ch, Some((node_index, offset)));
+        }
+    }
+    while text.ends_with(' ') {
+        text.pop();
+        origins.pop();
+    }
+
+    let start = if text == text_content {
+        range.start
+    } else {
+        match text.find(needle) {
+            Some(start) => start,
+            None => return Vec::new(),
+        }
+    };
+
+    let mut spans: Vec<DomSpan> = Vec::new();
+    let mut current: Option<(usize, Range<usize>)> = None;
+    for origin in origins[start..start + needle.len()].iter().flatten() {
+        let (node_index, offset) = *origin;
+        let char_len = nodes[node_index].2[offset..].chars().next().map_or(1, char::len_utf8);
+        match &mut current {
+            Some((index, span)) if *index == node_index => span.end = offset + char_len,
+            _ => {
+                if let Some((index, span)) = current.take() {
+                    spans.push(dom_span(&root, nodes[index].0, nodes[index].1, span));
+                }
+                current = Some((node_index, offset..offset + char_len));
+            }
+        }
+    }
+    if let Some((index, span)) = current {
+        spans.push(dom_span(&root, nodes[index].0, nodes[index].1, span));
+    }
+    spans
+}
+
+/// Append `ch` the way `normalize_whitespace` would, recording where each byte came from
+fn push_normalized(text: &mut String, origins: &mut Vec<Option<(usize, usize)>>, ch: char, origin: Option<(usize, usize)>) {
+    if ch.is_whitespace() {
+        if text.ends_with(' ') {
+            // Prefer real whitespace over the virtual separator between text nodes
+            if let Some(last @ None) = origins.last_mut() {
+                *last = origin;
+            }
+            return;
+        }
+        if text.is_empty() {
+            return;
+        }
+        text.push(' ');
+        origins.push(origin);
+    } else {
+        text.push(ch);
+        origins.extend(std::iter::repeat_n(origin, ch.len_utf8()));
+    }
+}
+
+fn dom_span(root: &ElementRef, parent: ElementRef, text_node: usize, range: Range<usize>) -> DomSpan {
+    let path = if parent.id() == root.id() {
+        String::new()
+    } else {
+        let path = css_path(&parent);
+        path.strip_prefix("html > ").unwrap_or(&path).to_string()
+    };
+    DomSpan { path, text_node, range }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Selector;
+
+    #[test]
+    fn test_build_source_map() {
+        let document = Html::parse_document(r#"<html><body><div id="story">
+            <h2>Heading</h2>
+            <div class="ad">Buy things</div>
+            <p>First paragraph.</p>
+            <section><p>Second paragraph.</p></section>
+        </div></body></html>"#);
+        let root = document.select(&Selector::parse("#story").unwrap()).next().unwrap();
+        let blocks = ArticleBlocks::from_html("<h2>Heading</h2><p>First paragraph.</p><p>Second paragraph.</p>");
+
+        let entries = build(&document, root, &blocks);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, "div#story > h2");
+        assert_eq!(entries[1].path, "div#story > p");
+        assert_eq!(entries[2].path, "div#story > section > p");
+        assert!(entries[0].node_id < entries[1].node_id && entries[1].node_id < entries[2].node_id);
+    }
+
+    #[test]
+    fn test_locate() {
+        let content = "<p>Hello <em>brave</em> new world.</p><p>Second   paragraph.</p>";
+        let text_content = "Hello brave new world. Second paragraph.";
+
+        let start = text_content.find("brave new").unwrap();
+        let spans = locate(content, text_content, start..start + "brave new".len());
+        assert_eq!(spans, vec![
+            DomSpan { path: "p:nth-of-type(1) > em".to_string(), text_node: 0, range: 0..5 },
+            DomSpan { path: "p:nth-of-type(1)".to_string(), text_node: 1, range: 0..4 },
+        ]);
+
+        // Collapsed whitespace maps back to the original offsets
+        let start = text_content.find("paragraph").unwrap();
+        let spans = locate(content, text_content, start..start + "paragraph".len());
+        assert_eq!(spans[0].range, 9..18);
+
+        // Text that isn't in the content yields nothing
+        assert!(locate(content, "Nav text. Hello", 0..8).is_empty());
+        assert!(locate(content, text_content, 5..500).is_empty());
+    }
+}
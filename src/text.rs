@@ -0,0 +1,223 @@
+//! Text cleanups applied to the text nodes of a document, and the
+//! normalization of an article's plain text
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::utils::unescape_html_entities;
+
+/// Which cleanups to apply to an article's plain text
+///
+/// Start from a preset and adjust single fields, e.g.
+/// `TextNormalization { straighten_quotes: false, ..TextNormalizationPreset::SearchIndex.into() }`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextNormalization {
+    /// Decode entity references left in the text, e.g. from double-escaped
+    /// source (`&amp;nbsp;`, `&#8217;`)
+    pub decode_entities: bool,
+    /// Remove soft hyphens (U+00AD)
+    pub strip_soft_hyphens: bool,
+    /// Replace typographic ligatures (`ﬁ`, `ﬂ`, ...) with their letters
+    pub expand_ligatures: bool,
+    /// Replace curly quotes and primes with `'` and `"`
+    pub straighten_quotes: bool,
+    /// Replace no-break and other Unicode spaces with plain spaces, and drop
+    /// zero-width spaces and byte order marks
+    pub normalize_spaces: bool,
+}
+
+/// Ready-made [`TextNormalization`]s for common consumers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TextNormalizationPreset {
+    /// The text as in the source; earlier releases' output
+    #[default]
+    Preserve,
+    /// For display: entities decoded, soft hyphens and odd spaces removed,
+    /// typography kept
+    Reader,
+    /// For indexing and matching: every cleanup, so variants of the same
+    /// word compare equal
+    SearchIndex,
+}
+
+impl From<TextNormalizationPreset> for TextNormalization {
+    fn from(preset: TextNormalizationPreset) -> Self {
+        match preset {
+            TextNormalizationPreset::Preserve => Self::default(),
+            TextNormalizationPreset::Reader => Self {
+                decode_entities: true,
+                strip_soft_hyphens: true,
+                normalize_spaces: true,
+                ..Self::default()
+            },
+            TextNormalizationPreset::SearchIndex => Self {
+                decode_entities: true,
+                strip_soft_hyphens: true,
+                expand_ligatures: true,
+                straighten_quotes: true,
+                normalize_spaces: true,
+            },
+        }
+    }
+}
+
+impl TextNormalization {
+    /// Apply the enabled cleanups to `text`
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if self.decode_entities && text.contains('&') {
+            text = decode_entities(&text);
+        }
+        if self.expand_ligatures {
+            text = expand_ligatures(&text);
+        }
+        if self.strip_soft_hyphens || self.straighten_quotes || self.normalize_spaces {
+            text = text
+                .chars()
+                .filter_map(|c| match c {
+                    '\u{00AD}' if self.strip_soft_hyphens => None,
+                    '\u{200B}' | '\u{FEFF}' if self.normalize_spaces => None,
+                    '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' if self.straighten_quotes => Some('\''),
+                    '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}' | '\u{00BB}'
+                        if self.straighten_quotes => Some('"'),
+                    c if self.normalize_spaces && c.is_whitespace() && !matches!(c, ' ' | '\n' | '\t' | '\r') => Some(' '),
+                    c => Some(c),
+                })
+                .collect();
+        }
+        text
+    }
+}
+
+/// Decode named character references common in text, and numeric ones
+fn decode_entities(text: &str) -> String {
+    let text = unescape_html_entities(text).replace("&nbsp;", "\u{00A0}");
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(index) = rest.find("&#") {
+        decoded.push_str(&rest[..index]);
+        let reference = &rest[index + 2..];
+        let (digits, radix) = match reference.strip_prefix(['x', 'X']) {
+            Some(hex) => (hex, 16),
+            None => (reference, 10),
+        };
+        let end = digits.find(';').unwrap_or(0);
+        let c = (end > 0).then(|| u32::from_str_radix(&digits[..end], radix).ok().and_then(char::from_u32)).flatten();
+        match c {
+            Some(c) => {
+                decoded.push(c);
+                rest = &digits[end + 1..];
+            }
+            None => {
+                decoded.push_str("&#");
+                rest = reference;
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Typographic ligatures and their letters
+const LIGATURES: &[(char, &str)] = &[
+    ('\u{FB00}', "ff"),
+    ('\u{FB01}', "fi"),
+    ('\u{FB02}', "fl"),
+    ('\u{FB03}', "ffi"),
+    ('\u{FB04}', "ffl"),
+    ('\u{FB05}', "st"),
+    ('\u{FB06}', "st"),
+    ('\u{0132}', "IJ"),
+    ('\u{0133}', "ij"),
+];
+
+/// Replace typographic ligatures (`ﬁ`, `ﬂ`, ...) with their letters
+pub(crate) fn expand_ligatures(text: &str) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match LIGATURES.iter().find(|(ligature, _)| *ligature == c) {
+            Some((_, letters)) => expanded.push_str(letters),
+            None => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+/// Clean up the artifacts of OCR-derived text: rejoin words hyphenated
+/// across line breaks ("exam-\nple"), drop lines of a single stray
+/// character, and expand ligatures
+pub(crate) fn clean_ocr_text(text: &str) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let kept: Vec<&str> = if lines.len() > 1 {
+        lines.into_iter().filter(|line| line.trim().chars().count() != 1).collect()
+    } else {
+        lines
+    };
+    expand_ligatures(&join_hyphenated(&kept.join("\n")))
+}
+
+/// Whether `text` ends with a word broken by a hyphen, e.g. "exam-"
+pub(crate) fn ends_with_broken_word(text: &str) -> bool {
+    let mut chars = text.trim_end().chars().rev();
+    chars.next() == Some('-') && chars.next().is_some_and(char::is_alphabetic)
+}
+
+/// Whether `text` starts with the rest of a broken word: a lowercase letter
+pub(crate) fn starts_with_word_rest(text: &str) -> bool {
+    text.trim_start().chars().next().is_some_and(char::is_lowercase)
+}
+
+/// Join "exam- ple" and "exam-\nple" into "example"
+fn join_hyphenated(text: &str) -> String {
+    let mut joined = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find('-') {
+        let (before, after) = rest.split_at(index);
+        let after = &after[1..];
+        let next = after.trim_start_matches([' ', '\t', '\r', '\n']);
+        let broken = before.ends_with(char::is_alphabetic) && next.len() < after.len() && starts_with_word_rest(next);
+        joined.push_str(before);
+        if broken {
+            rest = next;
+        } else {
+            joined.push('-');
+            rest = after;
+        }
+    }
+    joined.push_str(rest);
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_normalization() {
+        let text = "\u{FEFF}A co\u{00AD}operative’s “ﬁrst” report&amp;nbsp;&#8212;&#x41;\u{00A0}&amp; more";
+        assert_eq!(TextNormalization::from(TextNormalizationPreset::Preserve).apply(text), text);
+        assert_eq!(
+            TextNormalization::from(TextNormalizationPreset::Reader).apply(text),
+            "A cooperative’s “ﬁrst” report —A & more"
+        );
+        assert_eq!(
+            TextNormalization::from(TextNormalizationPreset::SearchIndex).apply(text),
+            "A cooperative's \"first\" report —A & more"
+        );
+        assert_eq!(decode_entities("&#bad; &#65"), "&#bad; &#65");
+    }
+
+    #[test]
+    fn test_clean_ocr_text() {
+        assert_eq!(clean_ocr_text("an exam-\nple of the ﬁrst scan"), "an example of the first scan");
+        assert_eq!(clean_ocr_text("an exam- ple"), "an example");
+        assert_eq!(clean_ocr_text("well-known and North- East"), "well-known and North- East");
+        assert_eq!(clean_ocr_text("first line\n|\n  .\nsecond line"), "first line\nsecond line");
+        assert_eq!(clean_ocr_text("a"), "a");
+        assert!(ends_with_broken_word("an exam- ") && !ends_with_broken_word("a - "));
+    }
+}
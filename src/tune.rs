@@ -0,0 +1,178 @@
+//! Threshold tuning against a labeled corpus
+//!
+//! Sweeps `char_threshold` and `readerable_min_score` over a corpus of pages
+//! labeled readable or unreadable, and reports how well
+//! [`is_probably_readerable`](crate::is_probably_readerable) separates them at each setting, so thresholds
+//! can be picked from data rather than guessed.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use scraper::Html;
+
+use crate::{document_is_probably_readerable, is_html_file, strip_leading_garbage, CharThreshold, ReadabilityOptions};
+
+/// A page with its expected verdict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabeledPage {
+    pub name: String,
+    pub html: String,
+    /// Whether the page should count as readerable
+    pub readable: bool,
+}
+
+/// Confusion counts of one setting; "positive" means readerable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuneResult {
+    pub char_threshold: usize,
+    pub min_score: f64,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+}
+
+impl TuneResult {
+    /// Share of pages judged readerable that are labeled readable
+    pub fn precision(&self) -> f64 {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// Share of readable pages judged readerable
+    pub fn recall(&self) -> f64 {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+
+    /// Harmonic mean of precision and recall
+    pub fn f1(&self) -> f64 {
+        let (precision, recall) = (self.precision(), self.recall());
+        if precision + recall == 0.0 {
+            return 0.0;
+        }
+        2.0 * precision * recall / (precision + recall)
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    part as f64 / whole as f64
+}
+
+/// Load the `.html` files of `dir/readable` and `dir/unreadable`, in name order
+pub fn load_labeled_corpus(dir: &Path) -> io::Result<Vec<LabeledPage>> {
+    let mut pages = Vec::new();
+    for (label, readable) in [("readable", true), ("unreadable", false)] {
+        let mut paths: Vec<_> = fs::read_dir(dir.join(label))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| is_html_file(path))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let html = String::from_utf8_lossy(&fs::read(&path)?).into_owned();
+            let name = format!("{}/{}", label, path.file_name().unwrap_or_default().to_string_lossy());
+            pages.push(LabeledPage { name, html, readable });
+        }
+    }
+    Ok(pages)
+}
+
+/// Judge every page at every combination of `char_thresholds` and
+/// `min_scores`, starting from `base` options
+///
+/// Results are in sweep order: thresholds outer, scores inner. Each page is
+/// parsed once and judged at every setting.
+pub fn sweep(
+    pages: &[LabeledPage],
+    char_thresholds: &[usize],
+    min_scores: &[f64],
+    base: &ReadabilityOptions,
+) -> Vec<TuneResult> {
+    let documents: Vec<(Html, bool)> = pages
+        .iter()
+        .map(|page| (Html::parse_document(strip_leading_garbage(&page.html)), page.readable))
+        .collect();
+    let mut results = Vec::with_capacity(char_thresholds.len() * min_scores.len());
+    for &char_threshold in char_thresholds {
+        for &min_score in min_scores {
+            let options = ReadabilityOptions {
+                char_threshold: CharThreshold::Chars(char_threshold),
+                readerable_min_score: Some(min_score),
+                ..base.clone()
+            };
+            let mut result = TuneResult {
+                char_threshold,
+                min_score,
+                true_positives: 0,
+                false_positives: 0,
+                false_negatives: 0,
+                true_negatives: 0,
+            };
+            for (document, readable) in &documents {
+                match (document_is_probably_readerable(document, &options), *readable) {
+                    (true, true) => result.true_positives += 1,
+                    (true, false) => result.false_positives += 1,
+                    (false, true) => result.false_negatives += 1,
+                    (false, false) => result.true_negatives += 1,
+                }
+            }
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// The result with the highest F1 score; the first of equals, so the
+/// earliest setting in sweep order wins ties
+pub fn best(results: &[TuneResult]) -> Option<&TuneResult> {
+    results.iter().reduce(|best, result| if result.f1() > best.f1() { result } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(name: &str, html: String, readable: bool) -> LabeledPage {
+        LabeledPage { name: name.to_string(), html, readable }
+    }
+
+    #[test]
+    fn test_sweep() {
+        let paragraph = "<p>A long paragraph about rivers, with commas, and enough text to pass the threshold.</p>";
+        let pages = vec![
+            page("long", format!("<html><body>{}</body></html>", paragraph.repeat(6)), true),
+            page("medium", format!("<html><body>{}</body></html>", paragraph.repeat(2)), true),
+            page("stub", "<html><body><p>Subscribe to our newsletter today.</p></body></html>".to_string(), false),
+        ];
+        let results = sweep(&pages, &[25, 400], &[10.0], &ReadabilityOptions::default());
+        assert_eq!(results.len(), 2);
+
+        let lenient = &results[0];
+        assert_eq!((lenient.true_positives, lenient.false_negatives), (2, 0));
+        assert_eq!(lenient.recall(), 1.0);
+        let strict = &results[1];
+        assert_eq!((strict.true_positives, strict.false_negatives, strict.true_negatives), (1, 1, 1));
+        assert_eq!((strict.precision(), strict.recall()), (1.0, 0.5));
+        assert!((strict.f1() - 2.0 / 3.0).abs() < 1e-9);
+
+        assert_eq!(best(&results).map(|result| result.char_threshold), Some(lenient.char_threshold));
+        assert!(best(&[]).is_none());
+    }
+
+    #[test]
+    fn test_load_labeled_corpus() {
+        let dir = std::env::temp_dir().join(format!("readability-tune-{}", std::process::id()));
+        for (label, name) in [("readable", "b.html"), ("readable", "a.htm"), ("unreadable", "c.html"), ("unreadable", "notes.txt")] {
+            fs::create_dir_all(dir.join(label)).unwrap();
+            fs::write(dir.join(label).join(name), "<p>x</p>").unwrap();
+        }
+        let pages = load_labeled_corpus(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<(&str, bool)> = pages.iter().map(|page| (page.name.as_str(), page.readable)).collect();
+        assert_eq!(names, vec![("readable/a.htm", true), ("readable/b.html", true), ("unreadable/c.html", false)]);
+    }
+}
@@ -1,20 +1,122 @@
 //! Utility functions for the Readability parser
 
-use scraper::{ElementRef, Element};
+use scraper::{ElementRef, Element, Selector};
+#[cfg(feature = "url")]
 use url::Url;
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// HTML elements that are considered phrasing content
 pub const PHRASING_ELEMS: &[&str] = &[
     "ABBR", "AUDIO", "B", "BDO", "BR", "BUTTON", "CITE", "CODE", "DATA",
     "DATALIST", "DFN", "EM", "EMBED", "I", "IMG", "INPUT", "KBD", "LABEL",
     "MARK", "MATH", "METER", "NOSCRIPT", "OBJECT", "OUTPUT", "PROGRESS",
-    "Q", "RUBY", "SAMP", "SCRIPT", "SELECT", "SMALL", "SPAN", "STRONG",
-    "SUB", "SUP", "TEXTAREA", "TIME", "VAR", "WBR"
+    "Q", "RB", "RP", "RT", "RTC", "RUBY", "SAMP", "SCRIPT", "SELECT", "SMALL",
+    "SPAN", "STRONG", "SUB", "SUP", "TEXTAREA", "TIME", "VAR", "WBR"
 ];
 
-/// Elements that can be converted from DIV to P
+/// Check if a CSS declaration block or stylesheet uses vertical writing
+pub fn uses_vertical_writing(css: &str) -> bool {
+    let css = css.to_lowercase();
+    let mut rest = css.as_str();
+    while let Some(index) = rest.find("writing-mode") {
+        rest = &rest[index + "writing-mode".len()..];
+        let value = rest.trim_start().trim_start_matches(':').trim_start();
+        if value.starts_with("vertical") || value.starts_with("tb") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Unicode LEFT-TO-RIGHT ISOLATE
+pub const LEFT_TO_RIGHT_ISOLATE: char = '\u{2066}';
+
+/// Unicode POP DIRECTIONAL ISOLATE
+pub const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+fn is_rtl_char(ch: char) -> bool {
+    matches!(ch as u32, 0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BidiClass {
+    Ltr,
+    Rtl,
+    Neutral,
+}
 
+fn bidi_class(word: &str) -> BidiClass {
+    if word.chars().any(is_rtl_char) {
+        BidiClass::Rtl
+    } else if word.chars().any(|ch| ch.is_alphabetic()) {
+        BidiClass::Ltr
+    } else {
+        BidiClass::Neutral
+    }
+}
+
+/// Wrap runs of left-to-right words in right-to-left text with bidi isolates
+///
+/// Runs of Latin words, URLs and similar fragments are surrounded by
+/// U+2066 LEFT-TO-RIGHT ISOLATE and U+2069 POP DIRECTIONAL ISOLATE so that
+/// plain-text consumers render them without reordering the surrounding
+/// right-to-left text.
+pub fn isolate_ltr_runs(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut open = false;
+    let mut pending = String::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let word_start = rest.find(|ch: char| !ch.is_whitespace()).unwrap_or(rest.len());
+        let whitespace = &rest[..word_start];
+        rest = &rest[word_start..];
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        rest = &rest[word_end..];
+
+        if word.is_empty() {
+            pending.push_str(whitespace);
+            break;
+        }
+
+        match bidi_class(word) {
+            BidiClass::Ltr => {
+                output.push_str(&pending);
+                output.push_str(whitespace);
+                pending.clear();
+                if !open {
+                    output.push(LEFT_TO_RIGHT_ISOLATE);
+                    open = true;
+                }
+                output.push_str(word);
+            }
+            BidiClass::Neutral if open => {
+                pending.push_str(whitespace);
+                pending.push_str(word);
+            }
+            _ => {
+                if open {
+                    output.push(POP_DIRECTIONAL_ISOLATE);
+                    open = false;
+                }
+                output.push_str(&pending);
+                pending.clear();
+                output.push_str(whitespace);
+                output.push_str(word);
+            }
+        }
+    }
+
+    if open {
+        output.push(POP_DIRECTIONAL_ISOLATE);
+    }
+    output.push_str(&pending);
+    output
+}
 
 /// Presentational attributes that should be removed
 pub const PRESENTATIONAL_ATTRIBUTES: &[&str] = &[
@@ -29,21 +131,107 @@ pub fn to_absolute_uri(uri: &str, base_uri: &str) -> String {
         return uri.to_string();
     }
 
-    // Try to resolve against base URI
-    match Url::parse(base_uri) {
-        Ok(base) => {
-            match base.join(uri) {
-                Ok(absolute_url) => absolute_url.to_string(),
-                Err(_) => uri.to_string(), // Return original if join fails
+    resolve_uri(uri, base_uri).unwrap_or_else(|| uri.to_string())
+}
+
+/// Check if a string is a valid URL
+pub fn is_url(text: &str) -> bool {
+    #[cfg(feature = "url")]
+    {
+        Url::parse(text).is_ok()
+    }
+    #[cfg(not(feature = "url"))]
+    {
+        split_scheme(text).is_some_and(|(_, rest)| !rest.is_empty())
+    }
+}
+
+/// Whether `path` names an HTML file by its `.html` or `.htm` extension
+pub fn is_html_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| matches!(extension.to_ascii_lowercase().as_str(), "html" | "htm"))
+}
+
+/// Path component of an absolute URL, without query or fragment
+pub(crate) fn url_path(uri: &str) -> Option<String> {
+    #[cfg(feature = "url")]
+    {
+        Url::parse(uri).ok().map(|url| url.path().to_string())
+    }
+    #[cfg(not(feature = "url"))]
+    {
+        let (_, rest) = split_scheme(uri)?;
+        let rest = rest.strip_prefix("//").map(|after| &after[after.find('/').unwrap_or(after.len())..]).unwrap_or(rest);
+        let end = rest.find(['?', '#']).unwrap_or(rest.len());
+        Some(if rest[..end].is_empty() { "/".to_string() } else { rest[..end].to_string() })
+    }
+}
+
+/// `uri` resolved against `base_uri`, fragments included, or `None` if the
+/// base can't be parsed
+#[cfg(feature = "url")]
+pub(crate) fn resolve_uri(uri: &str, base_uri: &str) -> Option<String> {
+    // Return None (the original) if the base URL is invalid or the join fails
+    Url::parse(base_uri).ok()?.join(uri).ok().map(|absolute_url| absolute_url.to_string())
+}
+
+/// Minimal RFC 3986 reference resolution for builds without the `url` crate
+#[cfg(not(feature = "url"))]
+pub(crate) fn resolve_uri(uri: &str, base_uri: &str) -> Option<String> {
+    if split_scheme(uri).is_some() {
+        return Some(uri.to_string());
+    }
+    let (scheme, rest) = split_scheme(base_uri)?;
+    if uri.starts_with('#') {
+        return Some(format!("{}{}", &base_uri[..base_uri.find('#').unwrap_or(base_uri.len())], uri));
+    }
+    let after_authority = rest.strip_prefix("//")?;
+    let authority_end = after_authority.find(['/', '?', '#']).unwrap_or(after_authority.len());
+    let (authority, base_path) = after_authority.split_at(authority_end);
+    let base_path = &base_path[..base_path.find(['?', '#']).unwrap_or(base_path.len())];
+
+    if let Some(network_path) = uri.strip_prefix("//") {
+        return Some(format!("{}://{}", scheme, network_path));
+    }
+    if uri.starts_with('?') || uri.is_empty() {
+        return Some(format!("{}://{}{}{}", scheme, authority, base_path, uri));
+    }
+
+    let (path, suffix) = uri.split_at(uri.find(['?', '#']).unwrap_or(uri.len()));
+    let merged = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        let directory = &base_path[..base_path.rfind('/').map_or(0, |slash| slash + 1)];
+        format!("{}{}", if directory.is_empty() { "/" } else { directory }, path)
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    let parts: Vec<&str> = merged.split('/').skip(1).collect();
+    for (index, segment) in parts.iter().enumerate() {
+        let last = index + 1 == parts.len();
+        match *segment {
+            "." if last => segments.push(""),
+            "." => {}
+            ".." => {
+                segments.pop();
+                if last {
+                    segments.push("");
+                }
             }
+            segment => segments.push(segment),
         }
-        Err(_) => uri.to_string(), // Return original if base URL is invalid
     }
+    Some(format!("{}://{}/{}{}", scheme, authority, segments.join("/"), suffix))
 }
 
-/// Check if a string is a valid URL
-pub fn is_url(text: &str) -> bool {
-    Url::parse(text).is_ok()
+#[cfg(not(feature = "url"))]
+fn split_scheme(text: &str) -> Option<(&str, &str)> {
+    let colon = text.find(':')?;
+    let scheme = &text[..colon];
+    let valid = scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then(|| (scheme, &text[colon + 1..]))
 }
 
 /// Get the inner text content of an element
@@ -56,25 +244,79 @@ pub fn get_inner_text(element: &ElementRef, normalize_spaces: bool) -> String {
     }
 }
 
+/// Decode bytes as UTF-8, replacing each invalid sequence with U+FFFD
+///
+/// A byte-order mark is dropped; a UTF-16 one switches to UTF-16 decoding.
+/// Returns the text and the number of sequences replaced.
+pub fn decode_lossy(bytes: &[u8]) -> (Cow<'_, str>, usize) {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => decode_utf16_lossy(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16_lossy(rest, u16::from_be_bytes),
+        _ => {
+            let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+            let errors = bytes.utf8_chunks().filter(|chunk| !chunk.invalid().is_empty()).count();
+            (String::from_utf8_lossy(bytes), errors)
+        }
+    }
+}
+
+fn decode_utf16_lossy(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> (Cow<'static, str>, usize) {
+    let units = bytes.chunks_exact(2).map(|pair| from_bytes([pair[0], pair[1]]));
+    let mut errors = 0;
+    let mut text: String = char::decode_utf16(units)
+        .map(|unit| unit.unwrap_or_else(|_| {
+            errors += 1;
+            char::REPLACEMENT_CHARACTER
+        }))
+        .collect();
+    if bytes.len() % 2 == 1 {
+        errors += 1;
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    (Cow::Owned(text), errors)
+}
+
+/// Tags that show text before `<!DOCTYPE>`/`<html>` is page content, not garbage
+const DOCUMENT_TAGS: &[&str] = &["<head", "<body", "<title", "<meta", "<div", "<p>", "<article", "<main", "<section", "<iframe"];
+
+/// Skip byte-order marks and junk printed ahead of the document, such as
+/// PHP error messages before `<!DOCTYPE>` or `<html>`
+///
+/// Only a short prefix without document markup is dropped, so fragments
+/// that merely mention `<html>` further down are left alone.
+pub fn strip_leading_garbage(html: &str) -> &str {
+    let html = html.trim_start_matches('\u{FEFF}');
+    let window = &html.as_bytes()[..html.len().min(4096)];
+    let start = (0..window.len()).find(|&i| {
+        let rest = &window[i..];
+        [&b"<!doctype"[..], b"<html>", b"<html "].iter().any(|tag| {
+            rest.len() >= tag.len() && rest[..tag.len()].eq_ignore_ascii_case(tag)
+        })
+    });
+    match start {
+        Some(start) if start > 0 => {
+            let prefix = html[..start].to_lowercase();
+            if DOCUMENT_TAGS.iter().any(|tag| prefix.contains(tag)) {
+                html
+            } else {
+                &html[start..]
+            }
+        }
+        _ => html,
+    }
+}
+
 /// Normalize whitespace in text
 pub fn normalize_whitespace(text: &str) -> String {
-    // Replace multiple whitespace characters with single space
-    let mut result = String::new();
-    let mut prev_was_space = false;
-    
-    for ch in text.chars() {
-        if ch.is_whitespace() {
-            if !prev_was_space {
-                result.push(' ');
-                prev_was_space = true;
-            }
-        } else {
-            result.push(ch);
-            prev_was_space = false;
+    // Replace each run of whitespace with a single space, copying the words between whole
+    let mut result = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !result.is_empty() {
+            result.push(' ');
         }
+        result.push_str(word);
     }
-    
-    result.trim().to_string()
+    result
 }
 
 /// Get the character count of text
@@ -88,13 +330,12 @@ pub fn get_char_count(text: &str, separator: Option<char>) -> usize {
 
 /// Check if an element is phrasing content
 pub fn is_phrasing_content(tag_name: &str) -> bool {
-    PHRASING_ELEMS.contains(&tag_name.to_uppercase().as_str())
+    PHRASING_ELEMS.iter().any(|phrasing| phrasing.eq_ignore_ascii_case(tag_name))
 }
 
 /// Check if an element is a single image
 pub fn is_single_image(element: &ElementRef) -> bool {
-    let tag_name = element.value().name().to_uppercase();
-    if tag_name == "IMG" {
+    if element.value().name().eq_ignore_ascii_case("img") {
         return true;
     }
 
@@ -102,7 +343,7 @@ pub fn is_single_image(element: &ElementRef) -> bool {
     let children: Vec<_> = element.children().collect();
     if children.len() == 1 {
         if let Some(child_element) = children[0].value().as_element() {
-            return child_element.name().to_uppercase() == "IMG";
+            return child_element.name().eq_ignore_ascii_case("img");
         }
     }
 
@@ -111,6 +352,17 @@ pub fn is_single_image(element: &ElementRef) -> bool {
 
 /// Check if an element is probably visible
 pub fn is_node_visible(element: &ElementRef) -> bool {
+    is_node_visible_for(element, false)
+}
+
+/// Utility classes that hide content from everyone
+const HIDDEN_CLASSES: &[&str] = &["hidden", "d-none", "invisible"];
+
+/// Utility classes that hide content visually but keep it for screen readers
+const SR_ONLY_CLASSES: &[&str] = &["sr-only", "visually-hidden", "screen-reader-text"];
+
+/// Like [`is_node_visible`], optionally treating screen-reader-only content as visible
+pub fn is_node_visible_for(element: &ElementRef, keep_sr_only: bool) -> bool {
     let style = element.value().attr("style").unwrap_or("");
     
     // Check for display: none
@@ -132,10 +384,30 @@ pub fn is_node_visible(element: &ElementRef) -> bool {
     if element.value().attr("aria-hidden") == Some("true") {
         return false;
     }
+
+    // Check for CSS utility classes
+    let hidden_by_class = element.value().classes().any(|class| {
+        HIDDEN_CLASSES.contains(&class) || (!keep_sr_only && SR_ONLY_CLASSES.contains(&class))
+    });
+    if hidden_by_class {
+        return false;
+    }
     
     true
 }
 
+/// Check if an element's inline style pins it to the viewport (`position: fixed` or `sticky`)
+pub fn is_fixed_position(element: &ElementRef) -> bool {
+    let style: String = element.value().attr("style").unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    ["position:fixed", "position:sticky", "position:-webkit-sticky"]
+        .iter()
+        .any(|position| style.contains(position))
+}
+
 /// Check if element has ancestor with specific tag
 pub fn has_ancestor_tag(
     element: &ElementRef,
@@ -143,7 +415,6 @@ pub fn has_ancestor_tag(
     max_depth: Option<usize>,
     filter_fn: Option<fn(&ElementRef) -> bool>
 ) -> bool {
-    let target_tag = tag_name.to_uppercase();
     let mut current = element.parent_element();
     let mut depth = 0;
     
@@ -154,7 +425,7 @@ pub fn has_ancestor_tag(
             }
         }
         
-        if parent.value().name().to_uppercase() == target_tag {
+        if parent.value().name().eq_ignore_ascii_case(tag_name) {
             if let Some(filter) = filter_fn {
                 if filter(&parent) {
                     return true;
@@ -171,6 +442,55 @@ pub fn has_ancestor_tag(
     false
 }
 
+/// Whether `table` holds data rather than page layout, as in Readability.js's
+/// `_markDataTables`: explicit markers first, then its size
+pub fn is_data_table(table: &ElementRef) -> bool {
+    let value = table.value();
+    if value.attr("role") == Some("presentation") || value.attr("datatable") == Some("0") {
+        return false;
+    }
+    if value.attr("summary").is_some_and(|summary| !summary.is_empty()) {
+        return true;
+    }
+    let has_descendant = |tags: &[&str]| {
+        table.descendants().skip(1).filter_map(ElementRef::wrap).any(|element| tags.contains(&element.value().name()))
+    };
+    let caption_selector = Selector::parse("caption").unwrap();
+    if table.select(&caption_selector).next().is_some_and(|caption| caption.children().next().is_some()) {
+        return true;
+    }
+    if has_descendant(&["col", "colgroup", "tfoot", "thead", "th"]) {
+        return true;
+    }
+    if has_descendant(&["table"]) {
+        return false;
+    }
+    let (rows, columns) = table_size(table);
+    if rows == 1 || columns == 1 {
+        return false;
+    }
+    if rows >= 10 || columns > 4 {
+        return true;
+    }
+    rows * columns > 10
+}
+
+/// Rows and the most columns in any row of `table`, counting spans
+fn table_size(table: &ElementRef) -> (usize, usize) {
+    let span = |element: &ElementRef, name: &str| {
+        element.value().attr(name).and_then(|span| span.trim().parse::<usize>().ok()).filter(|&span| span > 0).unwrap_or(1)
+    };
+    let row_selector = Selector::parse("tr").unwrap();
+    let cell_selector = Selector::parse("td").unwrap();
+    let mut rows = 0;
+    let mut columns = 0;
+    for row in table.select(&row_selector) {
+        rows += span(&row, "rowspan");
+        columns = columns.max(row.select(&cell_selector).map(|cell| span(&cell, "colspan")).sum());
+    }
+    (rows, columns)
+}
+
 /// Get node ancestors up to maxDepth
 pub fn get_node_ancestors<'a>(element: &'a ElementRef<'a>, max_depth: usize) -> Vec<ElementRef<'a>> {
     let mut ancestors = Vec::new();
@@ -194,14 +514,53 @@ pub fn get_node_ancestors<'a>(element: &'a ElementRef<'a>, max_depth: usize) ->
     ancestors
 }
 
+/// Build a CSS selector path that uniquely identifies `element` in its document
+///
+/// The path starts at the nearest ancestor with a simple `id`, or at `html`,
+/// and uses `:nth-of-type()` wherever siblings share a tag name.
+pub fn css_path(element: &ElementRef) -> String {
+    let mut segments = Vec::new();
+    let mut current = Some(*element);
+
+    while let Some(node) = current {
+        let tag_name = node.value().name().to_lowercase();
+        if let Some(id) = node.value().id().filter(|id| is_simple_identifier(id)) {
+            segments.push(format!("{}#{}", tag_name, id));
+            break;
+        }
+
+        let same_tag_siblings: Vec<_> = node.parent()
+            .map(|parent| parent.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|sibling| sibling.value().name() == node.value().name())
+                .collect())
+            .unwrap_or_default();
+        if same_tag_siblings.len() > 1 {
+            let index = same_tag_siblings.iter().position(|sibling| sibling.id() == node.id()).unwrap_or(0);
+            segments.push(format!("{}:nth-of-type({})", tag_name, index + 1));
+        } else {
+            segments.push(tag_name);
+        }
+
+        current = node.parent().and_then(ElementRef::wrap);
+    }
+
+    segments.reverse();
+    segments.join(" > ")
+}
+
+fn is_simple_identifier(id: &str) -> bool {
+    let mut chars = id.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 // Duplicate is_node_visible function removed
 
 /// Check if an element is without content
 pub fn is_element_without_content(element: &ElementRef) -> bool {
-    let tag_name = element.value().name().to_uppercase();
-    
-    match tag_name.as_str() {
-        "IMG" | "VIDEO" | "AUDIO" | "EMBED" | "OBJECT" | "IFRAME" => false,
+    match element.value().name() {
+        "img" | "video" | "audio" | "embed" | "object" | "iframe" => false,
         _ => {
             let text_content = get_inner_text(element, true);
             text_content.is_empty()
@@ -223,8 +582,7 @@ pub fn has_single_tag_inside_element(element: &ElementRef, tag: &str) -> bool {
 pub fn has_child_block_element(element: &ElementRef) -> bool {
     for child in element.children() {
         if let Some(child_element) = child.value().as_element() {
-            let tag_name = child_element.name().to_uppercase();
-            if !is_phrasing_content(&tag_name) {
+            if !is_phrasing_content(child_element.name()) {
                 return true;
             }
         }
@@ -252,7 +610,7 @@ pub fn is_title_candidate(text: &str, current_title: Option<&str>) -> bool {
     let word_count = word_count(text);
     
     // Should be reasonable length - more restrictive for titles
-    if word_count < 2 || word_count > 10 || text.len() > 80 {
+    if !(2..=10).contains(&word_count) || text.len() > 80 {
         return false;
     }
     
@@ -298,12 +656,27 @@ pub fn unescape_html_entities(text: &str) -> String {
         // Note: We don't unescape &nbsp; to maintain the test expectation
 }
 
+/// Escape text for inclusion in HTML content or attribute values
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
 /// Remove extra whitespace and normalize text
 pub fn clean_text(text: &str) -> String {
     let unescaped = unescape_html_entities(text);
     normalize_whitespace(&unescaped)
 }
 
+/// Lowercase hex SHA-256 digest of `data`
+pub(crate) fn sha256_hex(data: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 /// Get link density for an element
 pub fn get_link_density(element: &ElementRef) -> f64 {
     let total_text_length = get_inner_text(element, false).len();
@@ -325,9 +698,39 @@ pub fn get_link_density(element: &ElementRef) -> f64 {
     link_text_length as f64 / total_text_length as f64
 }
 
+/// Format `time` as RFC 3339 in UTC, e.g. `1994-11-06T08:49:37Z`
+pub(crate) fn format_rfc3339(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs() as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let clock = seconds.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        clock / 3_600,
+        clock % 3_600 / 60,
+        clock % 60
+    )
+}
+
+/// The (year, month, day) of a count of days since the epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let m = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * m + 2) / 5 + 1;
+    let month = if m < 10 { m + 3 } else { m - 9 };
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use scraper::{Html, Selector};
 
     #[test]
     fn test_normalize_whitespace() {
@@ -336,6 +739,12 @@ mod tests {
         assert_eq!(normalize_whitespace("single"), "single");
     }
 
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(format_rfc3339(UNIX_EPOCH + Duration::from_secs(784_111_777)), "1994-11-06T08:49:37Z");
+        assert_eq!(format_rfc3339(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29T00:00:00Z");
+    }
+
     #[test]
     fn test_word_count() {
         assert_eq!(word_count("hello world"), 2);
@@ -360,12 +769,24 @@ mod tests {
         assert!(!is_url(""));
     }
 
+    #[test]
+    fn test_is_html_file() {
+        assert!(is_html_file(Path::new("pages/rivers.html")));
+        assert!(is_html_file(Path::new("RIVERS.HTM")));
+        assert!(!is_html_file(Path::new("rivers.md")));
+        assert!(!is_html_file(Path::new("html")));
+    }
+
     #[test]
     fn test_to_absolute_uri() {
         let base = "https://example.com/path/";
         assert_eq!(to_absolute_uri("#anchor", base), "#anchor");
         assert_eq!(to_absolute_uri("/absolute", base), "https://example.com/absolute");
         assert_eq!(to_absolute_uri("relative", base), "https://example.com/path/relative");
+        assert_eq!(
+            resolve_uri("#notes", "https://example.com/a/page?x=1#top").as_deref(),
+            Some("https://example.com/a/page?x=1#notes")
+        );
     }
 
     #[test]
@@ -395,4 +816,112 @@ mod tests {
         assert_eq!(get_char_count("hello,world,test", Some(',')), 2);
         assert_eq!(get_char_count("hello world", None), 11);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_uses_vertical_writing() {
+        assert!(uses_vertical_writing("writing-mode: vertical-rl"));
+        assert!(uses_vertical_writing(".body { WRITING-MODE:tb-rl; }"));
+        assert!(!uses_vertical_writing("writing-mode: horizontal-tb"));
+        assert!(!uses_vertical_writing("color: red"));
+        assert!(is_phrasing_content("rt"));
+    }
+
+    #[test]
+    fn test_isolate_ltr_runs() {
+        assert_eq!(
+            isolate_ltr_runs("שלום John Smith עולם"),
+            "שלום \u{2066}John Smith\u{2069} עולם"
+        );
+        assert_eq!(
+            isolate_ltr_runs("راجع https://example.com - شكرا"),
+            "راجع \u{2066}https://example.com\u{2069} - شكرا"
+        );
+        assert_eq!(isolate_ltr_runs("עברית בלבד"), "עברית בלבד");
+    }
+
+    #[test]
+    fn test_is_node_visible_classes() {
+        let document = Html::parse_fragment(r#"<span class="sr-only">a</span><div class="hidden x">b</div><p class="lead">c</p>"#);
+        let elements: Vec<ElementRef> = document.select(&Selector::parse("span, div, p").unwrap()).collect();
+        assert!(!is_node_visible(&elements[0]));
+        assert!(is_node_visible_for(&elements[0], true));
+        assert!(!is_node_visible_for(&elements[1], true));
+        assert!(is_node_visible(&elements[2]));
+    }
+
+    #[test]
+    fn test_decode_lossy() {
+        assert_eq!(decode_lossy("Grüße".as_bytes()), (Cow::Borrowed("Grüße"), 0));
+        let (text, errors) = decode_lossy(b"\xcf\xf0\xe8 ok \xc3");
+        assert_eq!(text, "\u{FFFD}\u{FFFD}\u{FFFD} ok \u{FFFD}");
+        assert_eq!(errors, 4);
+
+        let (text, errors) = decode_lossy(b"\xEF\xBB\xBF<p>");
+        assert_eq!((text.as_ref(), errors), ("<p>", 0));
+        let (text, errors) = decode_lossy(b"\xFF\xFE<\x00p\x00>\x00");
+        assert_eq!((text.as_ref(), errors), ("<p>", 0));
+        let (text, errors) = decode_lossy(b"\xFE\xFF\x00<\xD8\x00\x00>");
+        assert_eq!((text.as_ref(), errors), ("<\u{FFFD}>", 1));
+    }
+
+    #[test]
+    fn test_strip_leading_garbage() {
+        assert_eq!(strip_leading_garbage("\u{FEFF}<!DOCTYPE html><p>"), "<!DOCTYPE html><p>");
+        assert_eq!(strip_leading_garbage("\n  <HTML lang=en>"), "<HTML lang=en>");
+        assert_eq!(
+            strip_leading_garbage("<br />\n<b>Warning</b>: Undefined variable $x in <b>index.php</b><br />\n<!doctype html><html>"),
+            "<!doctype html><html>"
+        );
+        assert_eq!(strip_leading_garbage("<div>Use <code>&lt;html&gt;</code> or <html> tags</div>"), "<div>Use <code>&lt;html&gt;</code> or <html> tags</div>");
+        assert_eq!(strip_leading_garbage("<p>No document tags</p>"), "<p>No document tags</p>");
+    }
+
+    #[test]
+    fn test_is_fixed_position() {
+        let document = Html::parse_fragment(r#"<div style="top: 0; Position : Fixed">a</div><div style="position:-webkit-sticky">b</div><div style="position: relative">c</div>"#);
+        let elements: Vec<ElementRef> = document.select(&Selector::parse("div").unwrap()).collect();
+        assert!(is_fixed_position(&elements[0]));
+        assert!(is_fixed_position(&elements[1]));
+        assert!(!is_fixed_position(&elements[2]));
+    }
+
+    #[test]
+    fn test_is_data_table() {
+        let document = Html::parse_fragment(r#"
+            <table id="caption"><caption>Results</caption><tr><td>a</td></tr></table>
+            <table id="header"><tr><th>Name</th><th>Age</th></tr><tr><td>Ann</td><td>30</td></tr></table>
+            <table id="summary" summary="Population by year"><tr><td>1</td></tr></table>
+            <table id="presentation" role="presentation"><tr><th>x</th></tr></table>
+            <table id="layout"><tr><td>Menu</td><td><p>Article</p></td></tr></table>
+            <table id="single"><tr><td>One cell</td></tr></table>
+            <table id="grid"><tr><td>1</td><td>2</td><td>3</td><td>4</td><td>5</td></tr><tr><td>6</td></tr></table>
+            <table id="nested"><tr><td><table><tr><td>x</td></tr></table></td><td>y</td></tr></table>
+        "#);
+        let is_data = |id: &str| {
+            let table = document.select(&Selector::parse(&format!("table#{}", id)).unwrap()).next().unwrap();
+            is_data_table(&table)
+        };
+        assert!(is_data("caption") && is_data("header") && is_data("summary") && is_data("grid"));
+        assert!(!is_data("presentation") && !is_data("layout") && !is_data("single") && !is_data("nested"));
+    }
+
+    #[test]
+    fn test_css_path() {
+        let document = Html::parse_document(r#"<html><body>
+            <div id="main"><p>One</p><p>Two</p></div>
+            <section><p>Three</p></section><section><p>Four</p></section>
+        </body></html>"#);
+        let paragraph = Selector::parse("p").unwrap();
+        let paths: Vec<String> = document.select(&paragraph).map(|p| css_path(&p)).collect();
+        assert_eq!(paths, vec![
+            "div#main > p:nth-of-type(1)",
+            "div#main > p:nth-of-type(2)",
+            "html > body > section:nth-of-type(1) > p",
+            "html > body > section:nth-of-type(2) > p",
+        ]);
+
+        for path in &paths {
+            assert_eq!(document.select(&Selector::parse(path).unwrap()).count(), 1);
+        }
+    }
+}